@@ -1,15 +1,23 @@
+use crate::ai::generic::projectile::behavior::ProjectileImpactEvent;
+use crate::player_control::player_embodiment::combat::collision::handle_player_being_hit;
 use crate::GameState;
 use bevy::prelude::*;
 
 pub(crate) mod generic;
 
 pub(crate) fn ai_plugin(app: &mut App) {
-    app.add_systems(
-        (
-            generic::projectile::behavior::fly_toward_player,
-            generic::projectile::behavior::handle_projectile_lifetimes,
-        )
-            .chain()
-            .in_set(OnUpdate(GameState::Playing)),
-    );
+    app.register_type::<ProjectileImpactEvent>()
+        .add_event::<ProjectileImpactEvent>()
+        .add_systems(
+            (
+                generic::projectile::behavior::collide_with_world,
+                generic::projectile::behavior::fly_toward_target,
+                generic::projectile::behavior::fly_in_arc,
+                generic::projectile::behavior::handle_projectile_hits,
+                generic::projectile::behavior::handle_projectile_lifetimes,
+            )
+                .chain()
+                .after(handle_player_being_hit)
+                .in_set(OnUpdate(GameState::Playing)),
+        );
 }
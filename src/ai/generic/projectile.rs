@@ -1,21 +1,34 @@
-use crate::ai::generic::projectile::components::SimpleProjectile;
+use crate::ai::generic::projectile::components::{
+    ArcingProjectile, HomingDelay, HomingTarget, PiercingProjectile, ProjectileSpawner,
+    SimpleProjectile,
+};
 use crate::combat::{
-    Projectile, ProjectileAttackFn, ProjectileAttackFnInput, ProjectileAttackFnOutput,
-    ProjectileKind, ProjectileSpawnInput,
+    Enemy, Projectile, ProjectileAttackFn, ProjectileAttackFnInput, ProjectileAttackFnOutput,
+    ProjectileKind, ProjectileSpawnInput, ProjectileTarget,
 };
+use crate::player_control::camera::IngameCamera;
 use crate::player_control::player_embodiment::Player;
+use crate::util::trait_extension::{F32Ext, Vec3Ext};
+use bevy::ecs::system::SystemParam;
 use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
 use spew::prelude::SpawnEvent;
 
 pub(crate) mod behavior;
 pub(crate) mod components;
 
 pub(crate) fn spawn_simple_projectile(input: ProjectileSpawnInput) -> Box<dyn ProjectileAttackFn> {
+    spawn_projectile(ProjectileKind::Simple, input)
+}
+
+pub(crate) fn spawn_projectile(
+    kind: ProjectileKind,
+    input: ProjectileSpawnInput,
+) -> Box<dyn ProjectileAttackFn> {
     Box::new(
         move |ProjectileAttackFnInput { spawner, .. }: ProjectileAttackFnInput| {
-            let object = ProjectileKind::Simple;
             let input = (spawner, input.clone());
-            let event = SpawnEvent::with_data(object, input);
+            let event = SpawnEvent::with_data(kind, input);
 
             ProjectileAttackFnOutput {
                 spawn_events: vec![event],
@@ -24,41 +37,258 @@ pub(crate) fn spawn_simple_projectile(input: ProjectileSpawnInput) -> Box<dyn Pr
     )
 }
 
-pub(crate) fn spawn_actual_simple_projectile(
-    In((
-        spawner,
-        ProjectileSpawnInput {
-            speed,
-            attack,
-            model,
-            tracking,
-            max_lifetime,
+/// Spawns `count` projectiles fanned out evenly over `angle` degrees around the direction to the player.
+pub(crate) fn spawn_projectile_spread(
+    kind: ProjectileKind,
+    input: ProjectileSpawnInput,
+    count: usize,
+    angle: f32,
+) -> Box<dyn ProjectileAttackFn> {
+    Box::new(
+        move |ProjectileAttackFnInput { spawner, .. }: ProjectileAttackFnInput| {
+            let spawn_events = get_spread_offsets(count, angle)
+                .map(|yaw_offset| {
+                    let input = ProjectileSpawnInput {
+                        yaw_offset: input.yaw_offset + yaw_offset,
+                        ..input.clone()
+                    };
+                    SpawnEvent::with_data(kind, (spawner, input))
+                })
+                .collect();
+
+            ProjectileAttackFnOutput { spawn_events }
         },
-    )): In<(Entity, ProjectileSpawnInput)>,
+    )
+}
+
+fn get_spread_offsets(count: usize, angle: f32) -> impl Iterator<Item = f32> {
+    let step = if count > 1 {
+        angle / (count - 1) as f32
+    } else {
+        0.0
+    };
+    let start = -step * count.saturating_sub(1) as f32 / 2.0;
+    (0..count).map(move |index| start + step * index as f32)
+}
+
+/// Where a projectile is fired from and what it is aimed at.
+struct Aim {
+    spawner_transform: Transform,
+    /// The entity the projectile homes in on, if any.
+    target: Option<Entity>,
+    target_translation: Vec3,
+    target_velocity: Vec3,
+    projectile_target: ProjectileTarget,
+}
+
+/// Enemies aim at the player. The player aims at the enemy closest to where the camera is looking, or straight ahead if there is none.
+#[derive(SystemParam)]
+pub(crate) struct ProjectileAiming<'w, 's> {
+    spawners:
+        Query<'w, 's, (&'static Transform, Option<&'static Player>), Without<SimpleProjectile>>,
+    players: Query<
+        'w,
+        's,
+        (Entity, &'static Transform, &'static Velocity),
+        (With<Player>, Without<SimpleProjectile>),
+    >,
+    enemies: Query<
+        'w,
+        's,
+        (Entity, &'static Transform, &'static Enemy),
+        (Without<Player>, Without<SimpleProjectile>),
+    >,
+    cameras: Query<'w, 's, &'static Transform, (With<IngameCamera>, Without<SimpleProjectile>)>,
+}
+
+impl<'w, 's> ProjectileAiming<'w, 's> {
+    fn aim(&self, spawner: Entity) -> Option<Aim> {
+        let (spawner_transform, player) = self.spawners.get(spawner).ok()?;
+        let spawner_transform = *spawner_transform;
+        if player.is_none() {
+            let (player_entity, player_transform, player_velocity) = self.players.iter().next()?;
+            return Some(Aim {
+                spawner_transform,
+                target: Some(player_entity),
+                target_translation: player_transform.translation,
+                target_velocity: player_velocity.linvel,
+                projectile_target: ProjectileTarget::Player,
+            });
+        }
+        // The player aims with the camera, which their model follows
+        let spawner_transform = self
+            .cameras
+            .iter()
+            .next()
+            .map(|camera_transform| spawner_transform.with_rotation(camera_transform.rotation))
+            .unwrap_or(spawner_transform);
+        let forward = spawner_transform.forward();
+        let target = self
+            .enemies
+            .iter()
+            .filter(|(_, _, enemy)| !enemy.is_dead)
+            .filter_map(|(entity, transform, _)| {
+                let to_enemy = transform.translation - spawner_transform.translation;
+                let angle = forward.angle_between(to_enemy).to_degrees();
+                (to_enemy.length_squared() < get_max_player_aim_distance().squared()
+                    && angle < get_max_player_aim_angle())
+                .then_some((entity, transform.translation, angle))
+            })
+            .min_by(|(.., a), (.., b)| a.total_cmp(b));
+        let aim = match target {
+            Some((entity, translation, _)) => Aim {
+                spawner_transform,
+                target: Some(entity),
+                target_translation: translation,
+                target_velocity: Vec3::ZERO,
+                projectile_target: ProjectileTarget::Enemy,
+            },
+            None => Aim {
+                spawner_transform,
+                target: None,
+                target_translation: spawner_transform.translation
+                    + forward * get_max_player_aim_distance(),
+                target_velocity: Vec3::ZERO,
+                projectile_target: ProjectileTarget::Enemy,
+            },
+        };
+        Some(aim)
+    }
+}
+
+fn get_max_player_aim_distance() -> f32 {
+    20.0
+}
+
+fn get_max_player_aim_angle() -> f32 {
+    30.0
+}
+
+pub(crate) fn spawn_actual_simple_projectile(
+    In((spawner, input)): In<(Entity, ProjectileSpawnInput)>,
     mut commands: Commands,
-    spawners: Query<(&Transform,), (Without<Player>,)>,
-    players: Query<(&Transform,), (With<Player>, Without<SimpleProjectile>)>,
+    aiming: ProjectileAiming,
 ) {
-    let (transform,) = spawners.get(spawner).unwrap();
-    for (player_transform,) in players.iter() {
-        let transform = transform
-            .looking_at(player_transform.translation, transform.up())
-            .with_scale(Vec3::splat(0.1));
-        commands.spawn((
+    let Some(aim) = aiming.aim(spawner) else {
+        return;
+    };
+    let transform = get_initial_transform(&aim, &input);
+    spawn_projectile_entity(&mut commands, spawner, &aim, transform, &input);
+}
+
+pub(crate) fn spawn_actual_delayed_homing_projectile(
+    In((spawner, input)): In<(Entity, ProjectileSpawnInput)>,
+    mut commands: Commands,
+    aiming: ProjectileAiming,
+) {
+    let Some(aim) = aiming.aim(spawner) else {
+        return;
+    };
+    let transform = get_initial_transform(&aim, &input);
+    let entity = spawn_projectile_entity(&mut commands, spawner, &aim, transform, &input);
+    commands
+        .entity(entity)
+        .insert(HomingDelay(input.homing_delay));
+}
+
+pub(crate) fn spawn_actual_arcing_projectile(
+    In((spawner, input)): In<(Entity, ProjectileSpawnInput)>,
+    mut commands: Commands,
+    aiming: ProjectileAiming,
+) {
+    let Some(aim) = aiming.aim(spawner) else {
+        return;
+    };
+    let up = aim.spawner_transform.up();
+    let velocity = get_arcing_velocity(
+        aim.spawner_transform.translation,
+        aim.target_translation,
+        aim.target_velocity,
+        input.speed,
+        input.gravity,
+    );
+    let velocity = Quat::from_axis_angle(up, input.yaw_offset.to_radians()) * velocity;
+    let transform = aim
+        .spawner_transform
+        .looking_to(velocity, up)
+        .with_scale(Vec3::splat(0.1));
+    let entity = spawn_projectile_entity(&mut commands, spawner, &aim, transform, &input);
+    commands.entity(entity).insert(ArcingProjectile {
+        velocity,
+        gravity: input.gravity,
+    });
+}
+
+fn get_initial_transform(aim: &Aim, input: &ProjectileSpawnInput) -> Transform {
+    let up = aim.spawner_transform.up();
+    let mut transform = aim
+        .spawner_transform
+        .looking_at(aim.target_translation, up)
+        .with_scale(Vec3::splat(0.1));
+    transform.rotation =
+        Quat::from_axis_angle(up, input.yaw_offset.to_radians()) * transform.rotation;
+    transform
+}
+
+/// Leads the target by assuming it keeps its current velocity for the duration of the flight.
+/// The horizontal speed of the projectile is fixed, the vertical speed is chosen such that gravity makes it land on the predicted position.
+fn get_arcing_velocity(
+    origin: Vec3,
+    target: Vec3,
+    target_velocity: Vec3,
+    speed: f32,
+    gravity: f32,
+) -> Vec3 {
+    let horizontal_distance = (target - origin).split(Vec3::Y).horizontal.length();
+    let time_of_flight = (horizontal_distance / speed.max(1e-5)).max(1e-2);
+    let predicted_target = target + target_velocity * time_of_flight;
+    let delta = predicted_target - origin;
+    let horizontal_velocity = delta.split(Vec3::Y).horizontal / time_of_flight;
+    let vertical_velocity = (delta.y + 0.5 * gravity * time_of_flight.squared()) / time_of_flight;
+    horizontal_velocity + Vec3::Y * vertical_velocity
+}
+
+fn spawn_projectile_entity(
+    commands: &mut Commands,
+    spawner: Entity,
+    aim: &Aim,
+    transform: Transform,
+    ProjectileSpawnInput {
+        model,
+        attack,
+        speed,
+        tracking,
+        max_lifetime,
+        is_piercing,
+        ..
+    }: &ProjectileSpawnInput,
+) -> Entity {
+    let entity = commands
+        .spawn((
             Name::new("Projectile"),
             SimpleProjectile {
-                speed,
-                tracking,
+                speed: *speed,
+                tracking: *tracking,
                 current_lifetime: 0.0,
-                max_lifetime,
+                max_lifetime: *max_lifetime,
             },
             SceneBundle {
                 scene: model.clone(),
                 transform,
                 ..Default::default()
             },
-            Projectile,
+            Projectile {
+                target: aim.projectile_target,
+            },
+            ProjectileSpawner(spawner),
             attack.clone(),
-        ));
+        ))
+        .id();
+    if let Some(target) = aim.target {
+        commands.entity(entity).insert(HomingTarget(target));
+    }
+    if *is_piercing {
+        commands.entity(entity).insert(PiercingProjectile);
     }
+    entity
 }
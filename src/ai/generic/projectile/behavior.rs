@@ -1,30 +1,122 @@
-use crate::ai::generic::projectile::components::SimpleProjectile;
-use crate::player_control::player_embodiment::Player;
+use crate::ai::generic::projectile::components::{
+    ArcingProjectile, HomingDelay, HomingTarget, PiercingProjectile, ProjectileSpawner,
+    SimpleProjectile,
+};
+use crate::combat::collision::{EnemyHitEvent, PlayerHitEvent};
+use crate::combat::{AttackHitbox, Projectile, ProjectileTarget};
+use crate::level_instantiation::spawning::objects::GameCollisionGroup;
+use crate::player_control::player_embodiment::combat::collision::DeflectedByPlayerEvent;
 use crate::util::smoothness_to_lerp_factor;
 use bevy::prelude::*;
+use bevy::utils::HashSet;
+use bevy_rapier3d::prelude::*;
+use serde::{Deserialize, Serialize};
 
-pub(crate) fn fly_toward_player(
+#[derive(Debug, Clone, PartialEq, Reflect, Serialize, Deserialize, FromReflect, Default)]
+#[reflect(Serialize, Deserialize)]
+pub(crate) struct ProjectileImpactEvent {
+    pub(crate) location: Vec3,
+    pub(crate) normal: Vec3,
+}
+
+pub(crate) fn fly_toward_target(
     time: Res<Time>,
-    mut projectiles: Query<(&mut Transform, &SimpleProjectile)>,
-    players: Query<(&Transform,), (With<Player>, Without<SimpleProjectile>)>,
+    mut projectiles: Query<
+        (
+            &mut Transform,
+            &SimpleProjectile,
+            Option<&HomingTarget>,
+            Option<&HomingDelay>,
+        ),
+        Without<ArcingProjectile>,
+    >,
+    targets: Query<(&Transform,), Without<SimpleProjectile>>,
 ) {
     let dt = time.delta_seconds();
-    for (mut transform, projectile) in projectiles.iter_mut() {
-        for (player_transform,) in players.iter() {
-            let current_direction = transform.forward();
-            let tracking_direction =
-                (player_transform.translation - transform.translation).normalize();
-            let speed = projectile.speed;
+    for (mut transform, projectile, homing_target, homing_delay) in projectiles.iter_mut() {
+        let current_direction = transform.forward();
+        let tracking_direction = homing_target
+            .and_then(|target| targets.get(target.0).ok())
+            .map(|(target_transform,)| {
+                (target_transform.translation - transform.translation).normalize_or_zero()
+            })
+            .filter(|direction| *direction != Vec3::ZERO)
+            .unwrap_or(current_direction);
+        let speed = projectile.speed;
+        let is_homing = homing_delay
+            .map(|delay| projectile.current_lifetime > delay.0)
+            .unwrap_or(true);
+        let factor = if is_homing {
             let smoothing = (1.0 - projectile.tracking) * 100.0;
-            let factor = smoothness_to_lerp_factor(smoothing, dt);
-            let direction = current_direction.lerp(tracking_direction, factor);
-            transform.translation += direction * speed * dt;
-            let up = transform.up();
-            transform.look_to(direction, up);
+            smoothness_to_lerp_factor(smoothing, dt)
+        } else {
+            0.0
+        };
+        let direction = current_direction.lerp(tracking_direction, factor);
+        transform.translation += direction * speed * dt;
+        let up = transform.up();
+        transform.look_to(direction, up);
+    }
+}
+
+/// Destroys projectiles that would pass through level geometry this frame.
+pub(crate) fn collide_with_world(
+    time: Res<Time>,
+    mut commands: Commands,
+    projectiles: Query<(
+        Entity,
+        &Transform,
+        &SimpleProjectile,
+        Option<&ArcingProjectile>,
+    )>,
+    rapier_context: Res<RapierContext>,
+    mut impact_events: EventWriter<ProjectileImpactEvent>,
+) {
+    let dt = time.delta_seconds();
+    // Only level geometry has the default collision groups, which include `OTHER`
+    let filter = QueryFilter::new()
+        .exclude_sensors()
+        .groups(CollisionGroups::new(
+            GameCollisionGroup::ATTACK.into(),
+            GameCollisionGroup::OTHER.into(),
+        ));
+    for (entity, transform, projectile, arcing) in projectiles.iter() {
+        let velocity = arcing
+            .map(|arcing| arcing.velocity)
+            .unwrap_or(transform.forward() * projectile.speed);
+        let speed = velocity.length();
+        if speed < 1e-5 {
+            continue;
+        }
+        if let Some((_entity, intersection)) = rapier_context.cast_ray_and_get_normal(
+            transform.translation,
+            velocity / speed,
+            speed * dt,
+            true,
+            filter,
+        ) {
+            impact_events.send(ProjectileImpactEvent {
+                location: intersection.point,
+                normal: intersection.normal,
+            });
+            commands.entity(entity).despawn_recursive();
         }
     }
 }
 
+pub(crate) fn fly_in_arc(
+    time: Res<Time>,
+    mut projectiles: Query<(&mut Transform, &mut ArcingProjectile)>,
+) {
+    let dt = time.delta_seconds();
+    for (mut transform, mut projectile) in projectiles.iter_mut() {
+        projectile.velocity.y -= projectile.gravity * dt;
+        transform.translation += projectile.velocity * dt;
+        let up = transform.up();
+        transform.look_to(projectile.velocity, up);
+    }
+}
+
 pub(crate) fn handle_projectile_lifetimes(
     time: Res<Time>,
     mut commands: Commands,
@@ -37,3 +129,56 @@ pub(crate) fn handle_projectile_lifetimes(
         }
     }
 }
+
+/// Despawns projectiles that hit their target, unless they are piercing.
+/// Projectiles that were deflected by the player are instead sent back toward their spawner and now hit enemies.
+pub(crate) fn handle_projectile_hits(
+    mut commands: Commands,
+    mut player_hit_events: EventReader<PlayerHitEvent>,
+    mut enemy_hit_events: EventReader<EnemyHitEvent>,
+    mut deflect_events: EventReader<DeflectedByPlayerEvent>,
+    mut projectiles: Query<(
+        &mut Projectile,
+        &mut AttackHitbox,
+        &mut SimpleProjectile,
+        &ProjectileSpawner,
+        Option<&PiercingProjectile>,
+    )>,
+) {
+    let mut deflected = HashSet::new();
+    for event in deflect_events.iter() {
+        let Ok((mut projectile, mut hitbox, mut flight, spawner, _)) = projectiles.get_mut(event.attacker) else {
+            continue;
+        };
+        if projectile.target == ProjectileTarget::Enemy {
+            continue;
+        }
+        projectile.target = ProjectileTarget::Enemy;
+        // Renaming the attack makes the hit cache treat the returning projectile as a new attack
+        hitbox.attack.name = format!("Deflected {}", hitbox.attack.name);
+        flight.tracking = flight.tracking.max(get_min_deflected_tracking());
+        flight.current_lifetime = 0.0;
+        commands
+            .entity(event.attacker)
+            .remove::<(ArcingProjectile, HomingDelay)>()
+            .insert(HomingTarget(spawner.0));
+        deflected.insert(event.attacker);
+    }
+
+    let hit_sources = player_hit_events
+        .iter()
+        .map(|event| event.source)
+        .chain(enemy_hit_events.iter().map(|event| event.source));
+    for source in hit_sources {
+        if deflected.contains(&source) {
+            continue;
+        }
+        if let Ok((.., piercing)) = projectiles.get(source) && piercing.is_none() {
+            commands.entity(source).despawn_recursive();
+        }
+    }
+}
+
+fn get_min_deflected_tracking() -> f32 {
+    0.9
+}
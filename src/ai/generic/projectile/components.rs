@@ -1,6 +1,8 @@
 use bevy::prelude::*;
 use serde::{Deserialize, Serialize};
 
+/// Shared flight data of all projectiles.
+/// Projectiles without an [`ArcingProjectile`] fly straight and steer toward their [`HomingTarget`] according to `tracking`.
 #[derive(Debug, Component, Clone, PartialEq, Default, Reflect, Serialize, Deserialize)]
 #[reflect(Component, Serialize, Deserialize)]
 pub(crate) struct SimpleProjectile {
@@ -9,3 +11,28 @@ pub(crate) struct SimpleProjectile {
     pub(crate) current_lifetime: f32,
     pub(crate) max_lifetime: f32,
 }
+
+#[derive(Debug, Component, Clone, PartialEq, Default, Reflect, Serialize, Deserialize)]
+#[reflect(Component, Serialize, Deserialize)]
+pub(crate) struct ArcingProjectile {
+    pub(crate) velocity: Vec3,
+    pub(crate) gravity: f32,
+}
+
+/// Marks a projectile that is not destroyed when hitting its target, see [`crate::combat::ProjectileSpawnInput::is_piercing`].
+#[derive(Debug, Component, Clone, Copy, PartialEq, Default, Reflect, Serialize, Deserialize)]
+#[reflect(Component, Serialize, Deserialize)]
+pub(crate) struct PiercingProjectile;
+
+/// Time in seconds after spawning during which a projectile does not track the player.
+#[derive(Debug, Component, Clone, Copy, PartialEq, Default, Reflect, Serialize, Deserialize)]
+#[reflect(Component, Serialize, Deserialize)]
+pub(crate) struct HomingDelay(pub(crate) f32);
+
+/// The entity that spawned a projectile. A deflected projectile flies back toward it.
+#[derive(Debug, Component, Clone, Copy, Deref, DerefMut)]
+pub(crate) struct ProjectileSpawner(pub(crate) Entity);
+
+/// The entity a projectile steers toward according to [`SimpleProjectile::tracking`]. Projectiles without it fly straight.
+#[derive(Debug, Component, Clone, Copy, Deref, DerefMut)]
+pub(crate) struct HomingTarget(pub(crate) Entity);
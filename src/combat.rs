@@ -1,4 +1,7 @@
-use crate::ai::generic::projectile::spawn_actual_simple_projectile;
+use crate::ai::generic::projectile::{
+    spawn_actual_arcing_projectile, spawn_actual_delayed_homing_projectile,
+    spawn_actual_simple_projectile,
+};
 use crate::combat::collision::{
     BlockedByEnemyEvent, DeflectedByEnemyEvent, EnemyHitEvent, EnemyHurtEvent, HitCache,
     HitboxHits, PlayerHitEvent,
@@ -12,6 +15,7 @@ pub(crate) use components::*;
 use seldom_fn_plugin::FnPluginExt;
 use spew::prelude::*;
 
+pub(crate) mod archetype;
 pub(crate) mod collision;
 pub(crate) mod components;
 mod constitution;
@@ -33,6 +37,7 @@ pub(crate) fn combat_plugin(app: &mut App) {
         .register_type::<AttackHitbox>()
         .register_type::<Attack>()
         .register_type::<Projectile>()
+        .register_type::<ProjectileKind>()
         .register_type::<ProjectileSpawnInput>()
         .register_type::<PlayerHitEvent>()
         .register_type::<EnemyHitEvent>()
@@ -40,6 +45,7 @@ pub(crate) fn combat_plugin(app: &mut App) {
         .register_type::<HitCache>()
         .register_type::<HitboxHits>()
         .register_type::<HitboxParentModel>()
+        .register_type::<archetype::EnemyParameters>()
         .add_event::<PlayerHitEvent>()
         .add_event::<EnemyHitEvent>()
         .add_event::<ReadMoveMetadataEvent>()
@@ -48,7 +54,14 @@ pub(crate) fn combat_plugin(app: &mut App) {
         .add_event::<BlockedByEnemyEvent>()
         .add_event::<DeflectedByEnemyEvent>()
         .add_plugin(SpewPlugin::<ProjectileKind, (Entity, ProjectileSpawnInput)>::default())
-        .add_spawners(((ProjectileKind::Simple, spawn_actual_simple_projectile),))
+        .add_spawners((
+            (ProjectileKind::Simple, spawn_actual_simple_projectile),
+            (ProjectileKind::Arcing, spawn_actual_arcing_projectile),
+            (
+                ProjectileKind::DelayedHoming,
+                spawn_actual_delayed_homing_projectile,
+            ),
+        ))
         .init_resource::<HitCache>()
         .fn_plugin(ui::enemy_combat_ui_plugin)
         .add_systems(
@@ -61,6 +74,7 @@ pub(crate) fn combat_plugin(app: &mut App) {
                 collision::handle_block_events,
                 collision::handle_deflect_events,
                 update_states::update_condition_tracker,
+                update_states::update_aggro,
                 decision::decide_choreography,
                 execution::execute_choreography,
                 execution::read_move_metadata,
@@ -74,7 +88,11 @@ pub(crate) fn combat_plugin(app: &mut App) {
                 .in_set(CombatSystemSet),
         )
         .add_systems(
-            (constitution::update_posture, constitution::handle_death)
+            (
+                archetype::apply_enemy_parameters,
+                constitution::update_posture,
+                constitution::handle_death,
+            )
                 .chain()
                 .after(link_animations)
                 .after(reset_forces_and_impulses)
@@ -0,0 +1,175 @@
+use crate::combat::Constitution;
+use crate::file_system_interaction::asset_loading::EnemyAssets;
+use crate::file_system_interaction::level_serialization::PendingLevelComponents;
+use crate::level_instantiation::spawning::GameObject;
+use anyhow::{bail, Context, Result};
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use bevy::reflect::TypeUuid;
+use bevy::utils::HashMap;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use spew::prelude::*;
+use std::sync::LazyLock;
+
+/// An enemy that `[enemy: <name>]` tags and waves can spawn, read from `assets/enemies/<name>.enemy.ron`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TypeUuid)]
+#[uuid = "2d3f8c1e-96a4-4b7e-8f0d-5c21a7e4b913"]
+pub(crate) struct EnemyArchetype {
+    /// Spawns the model, collider and choreographies of the enemy.
+    pub(crate) game_object: GameObject,
+    /// Overrides the health the spawner gives the enemy.
+    #[serde(default)]
+    pub(crate) max_health: Option<f32>,
+    /// Overrides the posture the spawner gives the enemy.
+    #[serde(default)]
+    pub(crate) max_posture: Option<f32>,
+    /// Overrides the posture recovery per second the spawner gives the enemy.
+    #[serde(default)]
+    pub(crate) posture_recovery: Option<f32>,
+    /// Health and posture are multiplied by this once per level above 1.
+    #[serde(default = "default_level_scaling")]
+    pub(crate) level_scaling: f32,
+}
+
+fn default_level_scaling() -> f32 {
+    1.25
+}
+
+/// The archetype and parameters an enemy was spawned with, e.g. from `[enemy: dummy, level: 3, patrol: path_a]`.
+#[derive(
+    Debug, Clone, PartialEq, Component, Reflect, FromReflect, Serialize, Deserialize, Default,
+)]
+#[reflect(Component, Serialize, Deserialize)]
+pub(crate) struct EnemyParameters {
+    pub(crate) archetype: String,
+    pub(crate) level: u32,
+    /// Id of the path the enemy should walk along while not aggroed.
+    pub(crate) patrol: Option<String>,
+    /// Every other `key: value` pair of the tag, for archetypes that need more than the above.
+    pub(crate) extra: HashMap<String, String>,
+}
+
+static PARAMETER_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^\s*(\w+)\s*:\s*([\w.-]+)\s*$").expect("Failed to compile enemy parameter regex")
+});
+
+impl EnemyParameters {
+    pub(crate) fn new(archetype: impl Into<String>) -> Self {
+        Self {
+            archetype: archetype.into(),
+            level: 1,
+            ..default()
+        }
+    }
+
+    /// Reads the arguments of an `[enemy: <name>, <key>: <value>, ...]` tag.
+    pub(crate) fn parse(arguments: &str) -> Result<Self> {
+        let mut arguments = arguments.split(',');
+        let archetype = arguments.next().unwrap_or_default().trim().to_lowercase();
+        if archetype.is_empty() || !archetype.chars().all(|c| c.is_alphanumeric() || c == '_') {
+            bail!("\"{archetype}\" is not a valid enemy name");
+        }
+        let mut parameters = Self::new(archetype);
+        for argument in arguments {
+            let captures = PARAMETER_REGEX.captures(argument).with_context(|| {
+                format!("Expected `<key>: <value>`, got \"{}\"", argument.trim())
+            })?;
+            parameters.set(&captures[1], &captures[2])?;
+        }
+        Ok(parameters)
+    }
+
+    fn set(&mut self, key: &str, value: &str) -> Result<()> {
+        match key {
+            "level" => {
+                self.level = value
+                    .parse()
+                    .with_context(|| format!("Invalid level \"{value}\""))?;
+                if self.level == 0 {
+                    bail!("Levels start at 1");
+                }
+            }
+            "patrol" => self.patrol = Some(value.to_string()),
+            _ => {
+                self.extra.insert(key.to_string(), value.to_string());
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Spawns enemies by archetype name and hands them their [`EnemyParameters`] once they showed up.
+#[derive(SystemParam)]
+pub(crate) struct EnemySpawner<'w> {
+    enemy_assets: Res<'w, EnemyAssets>,
+    archetypes: Res<'w, Assets<EnemyArchetype>>,
+    spawn_events: EventWriter<'w, SpawnEvent<GameObject, Transform>>,
+    pending_components: ResMut<'w, PendingLevelComponents>,
+}
+
+impl<'w> EnemySpawner<'w> {
+    pub(crate) fn spawn(
+        &mut self,
+        parameters: EnemyParameters,
+        transform: Transform,
+    ) -> Result<()> {
+        let game_object =
+            get_archetype(&parameters.archetype, &self.enemy_assets, &self.archetypes)
+                .with_context(|| {
+                    format!(
+                        "Tried to spawn invalid enemy type \"{}\". Available enemies: {:?}",
+                        parameters.archetype,
+                        self.enemy_assets.enemies.keys()
+                    )
+                })?
+                .game_object;
+        self.pending_components.push(
+            game_object,
+            transform.translation,
+            vec![Box::new(parameters)],
+        );
+        self.spawn_events
+            .send(SpawnEvent::with_data(game_object, transform));
+        Ok(())
+    }
+}
+
+pub(crate) fn get_archetype<'a>(
+    name: &str,
+    enemy_assets: &EnemyAssets,
+    archetypes: &'a Assets<EnemyArchetype>,
+) -> Option<&'a EnemyArchetype> {
+    enemy_assets
+        .enemies
+        .get(&format!("enemies/{name}.enemy.ron"))
+        .and_then(|handle| archetypes.get(handle))
+}
+
+pub(super) fn apply_enemy_parameters(
+    mut enemies: Query<(&EnemyParameters, &mut Constitution), Added<EnemyParameters>>,
+    enemy_assets: Res<EnemyAssets>,
+    archetypes: Res<Assets<EnemyArchetype>>,
+) {
+    for (parameters, mut constitution) in enemies.iter_mut() {
+        let Some(archetype) = get_archetype(&parameters.archetype, &enemy_assets, &archetypes) else {
+            continue;
+        };
+        let level_factor = archetype
+            .level_scaling
+            .powi(parameters.level.saturating_sub(1) as i32);
+        let max_health = archetype
+            .max_health
+            .unwrap_or_else(|| constitution.max_health());
+        let max_posture = archetype
+            .max_posture
+            .unwrap_or_else(|| constitution.max_posture());
+        let mut scaled = constitution
+            .with_max_health(max_health * level_factor)
+            .with_max_posture(max_posture * level_factor);
+        if let Some(posture_recovery) = archetype.posture_recovery {
+            scaled = scaled.with_base_posture_recovery(posture_recovery);
+        }
+        *constitution = scaled;
+    }
+}
@@ -1,4 +1,4 @@
-use crate::combat::{Attack, AttackHitbox, Enemy, HitboxToParentLink};
+use crate::combat::{Attack, AttackHitbox, Enemy, HitboxToParentLink, Projectile};
 use crate::player_control::player_embodiment::Player;
 use anyhow::{Context, Error, Result};
 use bevy::prelude::*;
@@ -20,8 +20,11 @@ pub(crate) struct PlayerHitEvent {
 #[reflect(Serialize, Deserialize)]
 pub(crate) struct EnemyHitEvent {
     pub(crate) target: Entity,
+    pub(crate) source: Entity,
     pub(crate) attack: Attack,
     pub(crate) target_to_contact: Vec3,
+    /// Whether the hit came from a thrown [`Projectile`] rather than a melee attack.
+    pub(crate) is_projectile: bool,
 }
 
 #[derive(
@@ -119,6 +122,7 @@ pub(crate) fn detect_hits(
     rapier_context: Res<RapierContext>,
     mut hit_cache: ResMut<HitCache>,
     transforms: Query<&Transform>,
+    projectiles: Query<(), With<Projectile>>,
 ) -> Result<()> {
     for event in collision_events.iter() {
         let (entity_a, entity_b, ongoing) = unpack_event(event);
@@ -173,13 +177,15 @@ pub(crate) fn detect_hits(
             determine_enemy_and_hitbox(&combatants, &attacks, entity_a, entity_b)
         {
             let send_enemy_hit = |target_entity: Entity,
-                                  _source_entity: Entity,
+                                  source_entity: Entity,
                                   hitbox: AttackHitbox,
                                   target_to_contact: Vec3| {
                 enemy_hit_events.send(EnemyHitEvent {
                     target: target_entity,
+                    source: source_entity,
                     attack: hitbox.attack,
                     target_to_contact,
+                    is_projectile: projectiles.contains(source_entity),
                 });
             };
 
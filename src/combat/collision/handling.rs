@@ -37,6 +37,11 @@ pub(crate) fn handle_enemy_being_hit(
             .xz()
             .angle_between(event.target_to_contact.xz())
             .to_degrees();
+        // Only melee attacks can sneak up on an enemy, a thrown kunai merely hurts it
+        if !event.is_projectile && !enemy.is_aggroed && angle.abs() > get_min_backstab_angle() {
+            enemy.die();
+            continue;
+        }
         match enemy.current_move() {
             Some(move_) => match move_.metadata.state {
                 EnemyCombatState::Deathblow => {
@@ -72,6 +77,11 @@ fn get_max_block_angle() -> f32 {
     100.0
 }
 
+/// Minimum angle in degrees between an enemy's forward direction and the direction to an attacker for the attack to count as coming from behind.
+pub(crate) fn get_min_backstab_angle() -> f32 {
+    120.0
+}
+
 fn roll_for_deflect() -> bool {
     const CHANCE_FOR_DEFLECT: f32 = 1.0 / 3.0;
     let rand = rand::random::<f32>();
@@ -111,6 +121,7 @@ impl From<EnemyHitEvent> for BlockedByEnemyEvent {
 #[derive(Debug, Clone, PartialEq)]
 pub(crate) struct DeflectedByEnemyEvent {
     pub(crate) enemy: Entity,
+    pub(crate) source: Entity,
     pub(crate) attack: Attack,
 }
 
@@ -118,6 +129,7 @@ impl From<EnemyHitEvent> for DeflectedByEnemyEvent {
     fn from(event: EnemyHitEvent) -> Self {
         Self {
             enemy: event.target,
+            source: event.source,
             attack: event.attack.clone(),
         }
     }
@@ -1,3 +1,4 @@
+use crate::level_instantiation::spawning::objects::GameCollisionGroup;
 use crate::movement::general_movement::ManualRotation;
 use bevy::prelude::*;
 use bevy::utils::HashMap;
@@ -32,8 +33,12 @@ pub(crate) struct Enemy {
     pub(crate) time_since_last_animation: f32,
     pub(crate) time_since_hurt_or_block: f32,
     pub(crate) forced_choreography: Option<usize>,
+    /// Where to continue the forced choreography instead of starting it from its first move.
+    pub(crate) resumed_progress: Option<ChoreographyProgress>,
     pub(crate) special_choreographies: SpecialChoreographies,
     pub(crate) is_dead: bool,
+    /// Enemies start out unaware of the player and can be killed instantly by attacking them from behind.
+    pub(crate) is_aggroed: bool,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -42,6 +47,7 @@ pub(crate) struct SpecialChoreographies {
     pub(crate) block: usize,
     pub(crate) posture_broken: usize,
     pub(crate) death: usize,
+    pub(crate) unaware: usize,
 }
 
 impl Enemy {
@@ -83,10 +89,26 @@ impl Enemy {
         })
     }
 
+    pub(crate) fn progress(&self) -> Option<ChoreographyProgress> {
+        self.current.map(|current| ChoreographyProgress {
+            choreography: current.choreography,
+            move_: current.move_,
+            time_since_last_move: self.time_since_last_move,
+            time_since_last_animation: self.time_since_last_animation,
+        })
+    }
+
+    /// Continues a choreography where it left off, e.g. after loading a save.
+    pub(crate) fn resume(&mut self, progress: ChoreographyProgress) {
+        self.forced_choreography = Some(progress.choreography);
+        self.resumed_progress = Some(progress);
+    }
+
     pub(crate) fn block(&mut self) {
         if self.is_dead {
             return;
         }
+        self.aggro();
         self.forced_choreography = Some(self.special_choreographies.block);
     }
 
@@ -94,9 +116,14 @@ impl Enemy {
         if self.is_dead {
             return;
         }
+        self.aggro();
         self.forced_choreography = Some(self.special_choreographies.hurt);
     }
 
+    pub(crate) fn aggro(&mut self) {
+        self.is_aggroed = true;
+    }
+
     pub(crate) fn break_posture(&mut self) {
         if self.is_dead {
             return;
@@ -110,19 +137,21 @@ impl Enemy {
     }
 }
 
-#[derive(Debug, Clone, Copy, Component, Reflect, FromReflect)]
-#[reflect(Component)]
+#[derive(
+    Debug, Clone, Copy, PartialEq, Component, Reflect, FromReflect, Serialize, Deserialize,
+)]
+#[reflect(Component, Serialize, Deserialize)]
 pub(crate) struct Constitution {
-    health: f32,
-    max_health: f32,
-    posture: f32,
-    vanilla_posture_recovery: f32,
-    vanilla_max_posture: f32,
-    vanilla_max_health: f32,
-    max_posture: f32,
-    base_posture_recovery: f32,
-    is_posture_broken: bool,
-    is_dead: bool,
+    pub(crate) health: f32,
+    pub(crate) max_health: f32,
+    pub(crate) posture: f32,
+    pub(crate) vanilla_posture_recovery: f32,
+    pub(crate) vanilla_max_posture: f32,
+    pub(crate) vanilla_max_health: f32,
+    pub(crate) max_posture: f32,
+    pub(crate) base_posture_recovery: f32,
+    pub(crate) is_posture_broken: bool,
+    pub(crate) is_dead: bool,
 }
 
 impl Constitution {
@@ -164,6 +193,10 @@ impl Constitution {
         self.health
     }
 
+    pub(crate) fn max_health(&self) -> f32 {
+        self.vanilla_max_health
+    }
+
     pub(crate) fn health_fraction(&self) -> f32 {
         self.health / self.max_health
     }
@@ -172,6 +205,10 @@ impl Constitution {
         self.posture
     }
 
+    pub(crate) fn max_posture(&self) -> f32 {
+        self.vanilla_max_posture
+    }
+
     pub(crate) fn posture_fraction(&self) -> f32 {
         self.posture / self.max_posture
     }
@@ -303,6 +340,15 @@ pub(crate) struct CurrentMove {
     pub(crate) start_transform: Transform,
 }
 
+/// How far an enemy got into its current choreography.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Default)]
+pub(crate) struct ChoreographyProgress {
+    pub(crate) choreography: usize,
+    pub(crate) move_: usize,
+    pub(crate) time_since_last_move: f32,
+    pub(crate) time_since_last_animation: f32,
+}
+
 #[derive(Debug, Clone, Default)]
 pub(crate) struct Choreography {
     pub(crate) name: String,
@@ -404,7 +450,28 @@ impl Attack {
     Debug, Component, Clone, Copy, PartialEq, Default, Reflect, FromReflect, Serialize, Deserialize,
 )]
 #[reflect(Component, Serialize, Deserialize)]
-pub(crate) struct Projectile;
+pub(crate) struct Projectile {
+    pub(crate) target: ProjectileTarget,
+}
+
+#[derive(
+    Debug, Clone, Copy, Eq, PartialEq, Default, Reflect, FromReflect, Serialize, Deserialize,
+)]
+#[reflect(Serialize, Deserialize)]
+pub(crate) enum ProjectileTarget {
+    #[default]
+    Player,
+    Enemy,
+}
+
+impl ProjectileTarget {
+    pub(crate) fn collision_group(self) -> GameCollisionGroup {
+        match self {
+            Self::Player => GameCollisionGroup::PLAYER,
+            Self::Enemy => GameCollisionGroup::ENEMY,
+        }
+    }
+}
 
 #[derive(Debug, Clone, Eq, PartialEq, Component, Reflect, Serialize, Deserialize, Default)]
 #[reflect(Component, Serialize, Deserialize)]
@@ -48,6 +48,8 @@ impl Default for MoveDuration {
 pub(crate) struct ReadMoveMetadataEvent {
     pub(crate) source: Entity,
     pub(crate) move_: MoveMetadata,
+    /// How far into its animation the move starts, only non-zero when resuming a choreography.
+    pub(crate) elapsed_time: f32,
 }
 
 #[derive(Debug, Clone)]
@@ -48,12 +48,18 @@ pub(crate) struct ProjectileAttackFnOutput {
     pub(crate) spawn_events: Vec<SpawnEvent<ProjectileKind, (Entity, ProjectileSpawnInput)>>,
 }
 
-#[derive(Debug, Clone, Eq, PartialEq)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Default, Reflect, FromReflect)]
 pub(crate) enum ProjectileKind {
+    /// Flies straight and steers toward its target according to [`ProjectileSpawnInput::tracking`].
+    #[default]
     Simple,
+    /// Affected by [`ProjectileSpawnInput::gravity`] and aimed at where its target will be on impact.
+    Arcing,
+    /// Flies straight for [`ProjectileSpawnInput::homing_delay`] seconds before tracking its target.
+    DelayedHoming,
 }
 
-#[derive(Debug, Clone, Reflect, FromReflect, Default)]
+#[derive(Debug, Clone, Reflect, FromReflect)]
 pub(crate) struct ProjectileSpawnInput {
     pub(crate) model: Handle<Scene>,
     pub(crate) attack: AttackHitbox,
@@ -61,4 +67,28 @@ pub(crate) struct ProjectileSpawnInput {
     /// 0-1
     pub(crate) tracking: f32,
     pub(crate) max_lifetime: f32,
+    /// Rotation in degrees around the spawner's up axis, applied to the initial flight direction.
+    pub(crate) yaw_offset: f32,
+    /// Downward acceleration, only used by [`ProjectileKind::Arcing`].
+    pub(crate) gravity: f32,
+    /// Only used by [`ProjectileKind::DelayedHoming`].
+    pub(crate) homing_delay: f32,
+    /// Whether the projectile keeps flying after hitting its target. Non-piercing projectiles are destroyed on their first hit.
+    pub(crate) is_piercing: bool,
+}
+
+impl Default for ProjectileSpawnInput {
+    fn default() -> Self {
+        Self {
+            model: default(),
+            attack: default(),
+            speed: default(),
+            tracking: default(),
+            max_lifetime: default(),
+            yaw_offset: default(),
+            gravity: default(),
+            homing_delay: default(),
+            is_piercing: true,
+        }
+    }
 }
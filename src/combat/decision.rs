@@ -19,15 +19,29 @@ pub(crate) fn decide_choreography(
         if let Some(current) = combatant.current {
             combatant.last_choreography = Some(current.choreography);
         }
+        let move_count = combatant.choreographies[next_choreography_index]
+            .moves
+            .len();
+        let progress = combatant.resumed_progress.take().filter(|progress| {
+            progress.choreography == next_choreography_index && progress.move_ < move_count
+        });
+        let mut elapsed_time = 0.0;
+        if let Some(progress) = progress {
+            combatant.time_since_last_move = progress.time_since_last_move;
+            combatant.time_since_last_animation = progress.time_since_last_animation;
+            elapsed_time = progress.time_since_last_animation;
+        }
+        let move_index = progress.map_or(0, |progress| progress.move_);
         combatant.current = Some(CurrentMove {
             choreography: next_choreography_index,
-            move_: 0,
+            move_: move_index,
             start_transform: *transform,
         });
-        let next_move = &combatant.choreographies[next_choreography_index].moves[0];
+        let next_move = &combatant.choreographies[next_choreography_index].moves[move_index];
         init_move_event_writer.send(ReadMoveMetadataEvent {
             source: entity,
             move_: next_move.metadata.clone(),
+            elapsed_time,
         });
         execute_move_event_writer.send(ExecuteMoveFunctionsEvent {
             source: entity,
@@ -45,10 +59,15 @@ fn choose_next_choreography(
     combatant
         .forced_choreography
         .or(get_chained_choreography(&combatant))
+        .or(get_unaware_choreography(&combatant))
         .map(Ok)
         .unwrap_or_else(|| roll_next_choreography(&combatant, condition_tracker))
 }
 
+fn get_unaware_choreography(enemy: &Enemy) -> Option<usize> {
+    (!enemy.is_aggroed).then_some(enemy.special_choreographies.unaware)
+}
+
 fn get_chained_choreography(enemy: &Enemy) -> Option<usize> {
     enemy
         .last_choreography
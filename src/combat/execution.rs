@@ -42,9 +42,10 @@ pub(crate) fn read_move_metadata(
                 .context("animation_entity_link held entity without animation player")?;
 
             if let Some(animation) = &move_.animation {
-                combatant.time_since_last_animation = 0.0;
+                combatant.time_since_last_animation = event.elapsed_time;
                 animation_player
-                    .play_with_transition(animation.clone(), Duration::from_secs_f32(0.2));
+                    .play_with_transition(animation.clone(), Duration::from_secs_f32(0.2))
+                    .set_elapsed(event.elapsed_time);
                 animation_player.resume();
                 if move_.duration != MoveDuration::Animation {
                     animation_player.repeat();
@@ -216,6 +217,7 @@ pub(crate) fn execute_choreography(
                 init_move_event_writer.send(ReadMoveMetadataEvent {
                     source: entity,
                     move_: next_move.metadata.clone(),
+                    elapsed_time: 0.0,
                 });
                 execute_move_event_writer.send(ExecuteMoveFunctionsEvent {
                     source: entity,
@@ -2,6 +2,7 @@ use crate::combat::{
     AttackHitbox, HitboxParentModel, HitboxToParentLink, ParentToHitboxLink, Projectile,
 };
 use crate::level_instantiation::spawning::objects::GameCollisionGroup;
+use crate::level_instantiation::spawning::scene_tags::{SceneTag, SceneTags};
 use crate::movement::general_movement::Model;
 use crate::util::trait_extension::MeshExt;
 use anyhow::{Context, Result};
@@ -22,19 +23,19 @@ pub(crate) fn link_hitbox(
     children: Query<&Children>,
     mesh_handles: Query<&Handle<Mesh>>,
     meshes: Res<Assets<Mesh>>,
-    names: Query<&Name>,
+    tags: Query<&SceneTags>,
 ) -> Result<()> {
     for (parent, model) in parents.iter() {
         let mut mesh_child = None;
         let mut bone_child = None;
         for child in children.iter_descendants(parent) {
-            let Ok(name) = names.get(child) else {
+            let Ok(tags) = tags.get(child) else {
                 continue;
             };
-            if name.contains("[hitbox]") {
+            if tags.contains(&SceneTag::Hitbox) {
                 mesh_child = Some(child);
             }
-            if name.contains("[hitbox-bone]") {
+            if tags.contains(&SceneTag::HitboxBone) {
                 bone_child = Some(child);
             }
             if mesh_child.is_some() && bone_child.is_some() {
@@ -96,18 +97,17 @@ pub(crate) fn link_hitbox(
 
 #[sysfail(log(level = "error"))]
 pub(crate) fn sync_projectile_attack_hitbox(
-    projectiles: Query<(&AttackHitbox, &ParentToHitboxLink), With<Projectile>>,
+    projectiles: Query<(&Projectile, &AttackHitbox, &ParentToHitboxLink)>,
     mut hitboxes: Query<(&mut AttackHitbox, &mut CollisionGroups), Without<ParentToHitboxLink>>,
 ) -> Result<()> {
-    for (attack, link) in projectiles.iter() {
+    for (projectile, attack, link) in projectiles.iter() {
         let (mut hitbox, mut collision_groups) = hitboxes
             .get_mut(link.0)
             .context("ParentToHitboxLink of projectile holds invalid entity")?;
         *hitbox = attack.clone();
+        collision_groups.filters -= (GameCollisionGroup::PLAYER | GameCollisionGroup::ENEMY).into();
         if attack.active {
-            collision_groups.filters |= GameCollisionGroup::PLAYER.into();
-        } else {
-            collision_groups.filters -= GameCollisionGroup::PLAYER.into();
+            collision_groups.filters |= projectile.target.collision_group().into();
         }
     }
     Ok(())
@@ -1,6 +1,7 @@
-use crate::combat::ConditionTracker;
+use crate::combat::{ConditionTracker, Enemy};
 use crate::movement::general_movement::{Grounded, Height};
 use crate::player_control::player_embodiment::Player;
+use crate::util::trait_extension::F32Ext;
 use anyhow::Result;
 use bevy::prelude::*;
 use bevy_mod_sysfail::macros::*;
@@ -83,6 +84,32 @@ pub(crate) fn update_condition_tracker(
     Ok(())
 }
 
+pub(crate) fn update_aggro(mut enemies: Query<(&mut Enemy, &ConditionTracker, &Transform)>) {
+    for (mut enemy, condition_tracker, transform) in enemies.iter_mut() {
+        if enemy.is_aggroed {
+            continue;
+        }
+        let is_within_sight = condition_tracker.has_line_of_sight
+            && condition_tracker.player_direction.length_squared() < get_sight_distance().squared()
+            && transform
+                .forward()
+                .angle_between(condition_tracker.player_direction)
+                .to_degrees()
+                < get_max_sight_angle();
+        if is_within_sight {
+            enemy.aggro();
+        }
+    }
+}
+
+fn get_sight_distance() -> f32 {
+    12.0
+}
+
+fn get_max_sight_angle() -> f32 {
+    60.0
+}
+
 fn get_line_of_sight(
     rapier_context: &RapierContext,
     origin: Vec3,
@@ -68,6 +68,7 @@ impl EditorWindow for DevEditorWindow {
                 if ui.button("Load").clicked() {
                     world.send_event(WorldLoadRequest {
                         filename: state.level_name.clone(),
+                        ..default()
                     });
                     // Make sure the player is spawned after the level
                     world.send_event(
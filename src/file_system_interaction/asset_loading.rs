@@ -1,6 +1,11 @@
+use crate::combat::archetype::EnemyArchetype;
 use crate::file_system_interaction::config::GameConfig;
-use crate::file_system_interaction::level_serialization::SerializedLevel;
+use crate::file_system_interaction::level_serialization::{LevelLoader, SerializedLevel};
+use crate::file_system_interaction::user_dirs::{UserDirs, UserStorage};
+use crate::localization::StringTable;
+use crate::player_control::player_embodiment::combat::ComboDefinition;
 use crate::world_interaction::dialog::Dialog;
+use crate::world_interaction::room::RoomDefinition;
 use crate::GameState;
 use anyhow::{Context, Result};
 use bevy::prelude::*;
@@ -13,10 +18,16 @@ use bevy_egui::{egui, EguiContexts};
 use bevy_kira_audio::AudioSource;
 use bevy_mod_sysfail::macros::*;
 use iyes_progress::{ProgressCounter, ProgressPlugin};
+use std::path::Path;
 
 pub(crate) fn loading_plugin(app: &mut App) {
-    app.add_plugin(RonAssetPlugin::<SerializedLevel>::new(&["lvl.ron"]))
+    app.add_asset::<SerializedLevel>()
+        .init_asset_loader::<LevelLoader>()
         .add_plugin(RonAssetPlugin::<Dialog>::new(&["dlg.ron"]))
+        .add_plugin(RonAssetPlugin::<RoomDefinition>::new(&["room.ron"]))
+        .add_plugin(RonAssetPlugin::<EnemyArchetype>::new(&["enemy.ron"]))
+        .add_plugin(RonAssetPlugin::<StringTable>::new(&["strings.ron"]))
+        .add_plugin(RonAssetPlugin::<ComboDefinition>::new(&["combo.ron"]))
         .add_plugin(TomlAssetPlugin::<GameConfig>::new(&["game.toml"]))
         .add_plugin(ProgressPlugin::new(GameState::Loading).continue_to(GameState::Menu))
         .add_loading_state(LoadingState::new(GameState::Loading).continue_to_state(GameState::Menu))
@@ -29,6 +40,9 @@ pub(crate) fn loading_plugin(app: &mut App) {
         .add_collection_to_loading_state::<_, TextureAssets>(GameState::Loading)
         .add_collection_to_loading_state::<_, ConfigAssets>(GameState::Loading)
         .add_collection_to_loading_state::<_, RoomAssets>(GameState::Loading)
+        .add_collection_to_loading_state::<_, EnemyAssets>(GameState::Loading)
+        .add_collection_to_loading_state::<_, LocaleAssets>(GameState::Loading)
+        .add_collection_to_loading_state::<_, ComboAssets>(GameState::Loading)
         .add_system(show_progress.in_set(OnUpdate(GameState::Loading)))
         .add_system(update_config);
 }
@@ -62,14 +76,52 @@ pub(crate) struct SceneAssets {
 
 #[derive(AssetCollection, Resource, Clone)]
 pub(crate) struct RoomAssets {
-    #[asset(path = "scenes/intro_room.glb#Scene0")]
-    pub(crate) intro: Handle<Scene>,
-    #[asset(path = "scenes/room_one.glb#Scene0")]
-    pub(crate) one: Handle<Scene>,
-    #[asset(path = "scenes/room_two.glb#Scene0")]
-    pub(crate) two: Handle<Scene>,
-    #[asset(path = "scenes/room_three.glb#Scene0")]
-    pub(crate) three: Handle<Scene>,
+    #[cfg_attr(feature = "native", asset(path = "rooms", collection(typed, mapped)))]
+    #[cfg_attr(
+        feature = "wasm",
+        asset(
+            paths(
+                "rooms/intro_room.room.ron",
+                "rooms/room_one.room.ron",
+                "rooms/room_two.room.ron",
+                "rooms/room_three.room.ron"
+            ),
+            collection(typed, mapped)
+        )
+    )]
+    pub(crate) rooms: HashMap<String, Handle<RoomDefinition>>,
+}
+
+#[derive(AssetCollection, Resource, Clone)]
+pub(crate) struct EnemyAssets {
+    #[cfg_attr(feature = "native", asset(path = "enemies", collection(typed, mapped)))]
+    #[cfg_attr(
+        feature = "wasm",
+        asset(
+            paths("enemies/dummy.enemy.ron", "enemies/brute.enemy.ron"),
+            collection(typed, mapped)
+        )
+    )]
+    pub(crate) enemies: HashMap<String, Handle<EnemyArchetype>>,
+}
+
+#[derive(AssetCollection, Resource, Clone)]
+pub(crate) struct LocaleAssets {
+    #[cfg_attr(feature = "native", asset(path = "locales", collection(typed, mapped)))]
+    #[cfg_attr(
+        feature = "wasm",
+        asset(
+            paths("locales/en.strings.ron", "locales/de.strings.ron"),
+            collection(typed, mapped)
+        )
+    )]
+    pub(crate) locales: HashMap<String, Handle<StringTable>>,
+}
+
+#[derive(AssetCollection, Resource, Clone)]
+pub(crate) struct ComboAssets {
+    #[asset(path = "combos/player.combo.ron")]
+    pub(crate) player: Handle<ComboDefinition>,
 }
 
 #[derive(AssetCollection, Resource, Clone)]
@@ -163,6 +215,9 @@ fn show_progress(
     dummy_animation_assets: Option<Res<DummyAnimationAssets>>,
     fps_dummy_animation_assets: Option<Res<FpsDummyAnimationAssets>>,
     room_assets: Option<Res<RoomAssets>>,
+    enemy_assets: Option<Res<EnemyAssets>>,
+    locale_assets: Option<Res<LocaleAssets>>,
+    combo_assets: Option<Res<ComboAssets>>,
 ) {
     if let Some(progress) = progress.map(|counter| counter.progress()) {
         if progress.done > *last_done {
@@ -186,6 +241,9 @@ fn show_progress(
                     ui.checkbox(&mut texture_assets.is_some(), "Textures");
                     ui.checkbox(&mut config_assets.is_some(), "Config");
                     ui.checkbox(&mut room_assets.is_some(), "Rooms");
+                    ui.checkbox(&mut enemy_assets.is_some(), "Enemies");
+                    ui.checkbox(&mut locale_assets.is_some(), "Locales");
+                    ui.checkbox(&mut combo_assets.is_some(), "Combos");
                     ui.checkbox(&mut dummy_animation_assets.is_some(), "Dummy Animations");
                     ui.checkbox(
                         &mut fps_dummy_animation_assets.is_some(),
@@ -197,11 +255,15 @@ fn show_progress(
     }
 }
 
+/// Replaces the bundled `config/config.game.toml` when present in [`UserDirs::config`].
+const USER_CONFIG_PATH: &str = "config.game.toml";
+
 #[sysfail(log(level = "error"))]
 fn update_config(
     mut commands: Commands,
     config: Res<Assets<GameConfig>>,
     mut config_asset_events: EventReader<AssetEvent<GameConfig>>,
+    user_dirs: Res<UserDirs>,
 ) -> Result<()> {
     #[cfg(feature = "tracing")]
     let _span = info_span!("update_config").entered();
@@ -212,10 +274,32 @@ fn update_config(
                 let config = config
                     .get(handle)
                     .context("Failed to get config even though it was just created")?;
-                commands.insert_resource(config.clone());
+                let storage = user_dirs.config.as_ref();
+                let path = Path::new(USER_CONFIG_PATH);
+                let user_config = storage
+                    .exists(path)
+                    .then(|| read_user_config(storage, path))
+                    .and_then(|user_config| {
+                        user_config
+                            .map_err(|e| {
+                                error!(
+                                    "Failed to read config at {}, using the default one instead: {e:?}",
+                                    storage.describe(path)
+                                )
+                            })
+                            .ok()
+                    });
+                commands.insert_resource(user_config.unwrap_or_else(|| config.clone()));
             }
             AssetEvent::Removed { .. } => {}
         }
     }
     Ok(())
 }
+
+fn read_user_config(storage: &dyn UserStorage, path: &Path) -> Result<GameConfig> {
+    let serialized = storage
+        .read_to_string(path)
+        .context("Failed to read config file")?;
+    toml::from_str(&serialized).context("Failed to deserialize config")
+}
@@ -1,5 +1,8 @@
-use crate::file_system_interaction::asset_loading::AudioAssets;
-use crate::world_interaction::room::{EnterRoomEvent, RoomClearEvent};
+use crate::file_system_interaction::asset_loading::{AudioAssets, RoomAssets};
+use crate::level_instantiation::spawning::GameObject;
+use crate::world_interaction::room::{
+    EnterRoomEvent, MusicCue, PreviousRoom, Room, RoomClearEvent, RoomDefinition,
+};
 use crate::GameState;
 use anyhow::{Context, Result};
 use bevy::prelude::*;
@@ -23,6 +26,16 @@ pub(crate) struct AudioHandles {
     //pub(crate) outro: Handle<AudioInstance>,
 }
 
+impl AudioHandles {
+    fn get_music(&self, music_cue: MusicCue) -> &Handle<AudioInstance> {
+        match music_cue {
+            MusicCue::IntroAndLoop => &self.intro_and_loop,
+            MusicCue::IntroAndLoopFast => &self.intro_and_loop_fast,
+            MusicCue::FastLoopOnly => &self.fast_loop_only,
+        }
+    }
+}
+
 fn init_audio(mut commands: Commands, audio_assets: Res<AudioAssets>, audio: Res<Audio>) {
     audio.pause();
     let walking = audio
@@ -75,16 +88,35 @@ fn handle_audio_events(
     audio_handles: Res<AudioHandles>,
     mut play_next_outro: Local<bool>,
     mut intro_start_time: Local<f32>,
+    mut current_music_cue: Local<Option<MusicCue>>,
+    rooms: Query<&GameObject, (With<Room>, Without<PreviousRoom>)>,
+    room_assets: Res<RoomAssets>,
+    room_definitions: Res<Assets<RoomDefinition>>,
 ) -> Result<()> {
     for _ in room_entered_event.iter() {
         /* let outro = audio_instances
             .get_mut(&audio_handles.outro)
             .context("Failed to get audio instance from handle")?;
         outro.stop(default());*/
-        let intro = audio_instances
-            .get_mut(&audio_handles.intro_and_loop)
+        let music_cue = rooms
+            .iter()
+            .find_map(|game_object| match game_object {
+                GameObject::Room(room) => room.get_definition(&room_assets, &room_definitions),
+                _ => None,
+            })
+            .map(|definition| definition.music)
+            .unwrap_or_default();
+        if let Some(previous_music_cue) = *current_music_cue && previous_music_cue != music_cue {
+            audio_instances
+                .get_mut(audio_handles.get_music(previous_music_cue))
+                .context("Failed to get audio instance from handle")?
+                .pause(default());
+        }
+        *current_music_cue = Some(music_cue);
+        let music = audio_instances
+            .get_mut(audio_handles.get_music(music_cue))
             .context("Failed to get audio instance from handle")?;
-        intro.resume(default());
+        music.resume(default());
         *intro_start_time = time.elapsed_seconds();
     }
     for _ in room_cleared_event.iter() {
@@ -1,3 +1,4 @@
+use crate::world_interaction::room::RoomId;
 use bevy::prelude::*;
 use bevy::reflect::TypeUuid;
 use serde::{Deserialize, Serialize};
@@ -21,6 +22,8 @@ pub(crate) struct GameConfig {
     pub(crate) characters: Characters,
     pub(crate) player: Player,
     pub(crate) dialog: Dialog,
+    pub(crate) saves: Saves,
+    pub(crate) run: Run,
 }
 
 #[derive(Debug, Clone, PartialEq, Reflect, FromReflect, Serialize, Deserialize, Default)]
@@ -94,3 +97,28 @@ pub(crate) struct Player {
 pub(crate) struct Dialog {
     pub(crate) base_letters_per_second: f32,
 }
+
+#[derive(Debug, Clone, PartialEq, Reflect, FromReflect, Serialize, Deserialize, Default)]
+#[reflect(Serialize, Deserialize)]
+pub(crate) struct Saves {
+    pub(crate) autosave_on_room_enter: bool,
+    pub(crate) autosave_on_room_clear: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Reflect, FromReflect, Serialize, Deserialize, Default)]
+#[reflect(Serialize, Deserialize)]
+pub(crate) struct Run {
+    /// Used for every run if set, otherwise every run gets a random seed.
+    pub(crate) seed: Option<u64>,
+    pub(crate) start_room: RoomId,
+    /// Number of rooms between the start room and the boss room.
+    pub(crate) length: usize,
+    pub(crate) max_branches: usize,
+    /// Every this many rooms, one of the paths leads through a rest room. `0` disables rest rooms.
+    pub(crate) rest_every: usize,
+    /// Every this many rooms, one of the paths leads through a shop. `0` disables shops.
+    pub(crate) shop_every: usize,
+    /// Difficulty right after the start room, rising linearly to [`Run::end_difficulty`] at the boss room.
+    pub(crate) start_difficulty: f32,
+    pub(crate) end_difficulty: f32,
+}
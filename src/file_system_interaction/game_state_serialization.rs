@@ -1,38 +1,66 @@
+use crate::combat::{ChoreographyProgress, Constitution, Enemy};
+use crate::file_system_interaction::game_state_serialization::autosave::autosave;
+use crate::file_system_interaction::game_state_serialization::migration::{
+    deserialize_save, CURRENT_SAVE_VERSION,
+};
+use crate::file_system_interaction::game_state_serialization::slots::save_slots_plugin;
+use crate::file_system_interaction::game_state_serialization::thumbnail::{
+    thumbnail_plugin, ThumbnailRequest,
+};
 use crate::file_system_interaction::level_serialization::{CurrentLevel, WorldLoadRequest};
+use crate::file_system_interaction::user_dirs::{get_backup_path, UserDirs, UserStorage};
 use crate::level_instantiation::spawning::GameObject;
+use crate::player_control::player_embodiment::combat::PlayerCombatState;
 use crate::player_control::player_embodiment::Player;
-use crate::world_interaction::condition::ActiveConditions;
+use crate::world_interaction::condition::{ActiveConditions, Counters};
 use crate::world_interaction::dialog::{CurrentDialog, DialogEvent};
+use crate::world_interaction::room::{
+    CurrentEncounter, CurrentRoom, PreviousRoom, Room, RoomTransition, RunLayout,
+};
+use crate::world_interaction::side_effects::SideEffects;
 use crate::GameState;
 use anyhow::{Context, Result};
+use bevy::ecs::system::SystemParam;
 use bevy::prelude::*;
 use bevy_mod_sysfail::macros::*;
 use chrono::prelude::Local;
-use glob::glob;
+use seldom_fn_plugin::FnPluginExt;
 use serde::{Deserialize, Serialize};
+pub(crate) use slots::*;
 use spew::prelude::*;
-use std::borrow::Cow;
-use std::fs;
-use std::path::{Path, PathBuf};
+use std::path::Path;
+
+mod autosave;
+mod migration;
+mod slots;
+mod thumbnail;
 
 pub(crate) fn game_state_serialization_plugin(app: &mut App) {
-    app.add_event::<GameSaveRequest>()
+    app.fn_plugin(save_slots_plugin)
+        .fn_plugin(thumbnail_plugin)
+        .add_event::<GameSaveRequest>()
         .add_event::<GameLoadRequest>()
         .add_systems(
             (
                 handle_load_requests,
+                restore_run.run_if(resource_exists::<RunRestore>()),
                 handle_save_requests.run_if(resource_exists::<CurrentLevel>()),
+                // Sends its requests after they were handled this frame so that the player has been placed in the new room
+                autosave,
             )
                 .chain()
                 .in_set(OnUpdate(GameState::Playing)),
         );
 }
 
+/// Saves into the slot named `filename`, overwriting it if it exists.
+/// Without a filename, a new slot named after the current time is created.
 #[derive(Debug, Clone, Eq, PartialEq, Resource, Serialize, Deserialize, Default)]
 pub(crate) struct GameSaveRequest {
     pub(crate) filename: Option<String>,
 }
 
+/// Loads the slot named `filename`, or the newest slot if there is no filename.
 #[derive(Debug, Clone, Eq, PartialEq, Resource, Serialize, Deserialize, Default)]
 pub(crate) struct GameLoadRequest {
     pub(crate) filename: Option<String>,
@@ -40,106 +68,332 @@ pub(crate) struct GameLoadRequest {
 
 #[derive(Debug, Clone, PartialEq, Resource, Serialize, Deserialize, Default)]
 struct SaveModel {
+    /// See [`CURRENT_SAVE_VERSION`].
+    version: u32,
     scene: String,
     #[serde(default, skip_serializing_if = "ActiveConditions::is_empty")]
     conditions: ActiveConditions,
+    #[serde(default, skip_serializing_if = "Counters::is_empty")]
+    counters: Counters,
     player_transform: Transform,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     dialog_event: Option<DialogEvent>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    room: Option<GameObject>,
+    /// `None` places the room where the level does.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    room_transform: Option<Transform>,
+    #[serde(default)]
+    current_room: CurrentRoom,
+    /// `None` for saves migrated from before runs had a layout, which start a new one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    run_layout: Option<RunLayout>,
+    /// `None` if the save was made while walking into the next room, whose encounter then starts once it is entered.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    encounter: Option<CurrentEncounter>,
+    #[serde(default)]
+    side_effects: SideEffects,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    player: Option<SavedPlayer>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    enemies: Option<Vec<SavedEnemy>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct SavedPlayer {
+    constitution: Constitution,
+    combat_state: PlayerCombatState,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct SavedEnemy {
+    game_object: GameObject,
+    transform: Transform,
+    constitution: Constitution,
+    /// Continued from the saved move when loading.
+    choreography: Option<ChoreographyProgress>,
+    is_aggroed: bool,
+}
+
+/// Present while a loaded save is applied to the freshly loaded level.
+/// The resources are restored after [`WorldLoadRequest`] reset them, the player once they are spawned,
+/// and the enemies once the room scene has been instantiated, replacing the ones tagged in the room.
+/// Levels without a room count as instantiated once the player was spawned.
+#[derive(Debug, Clone, PartialEq, Resource, Default)]
+pub(crate) struct RunRestore {
+    conditions: Option<ActiveConditions>,
+    counters: Option<Counters>,
+    current_room: Option<CurrentRoom>,
+    run_layout: Option<RunLayout>,
+    encounter: Option<CurrentEncounter>,
+    side_effects: Option<SideEffects>,
+    player: Option<SavedPlayer>,
+    /// `None` for saves without enemies, in which case the room spawns its enemies as usual.
+    enemies: Option<Vec<SavedEnemy>>,
+    spawned_enemies: bool,
+    room_instantiated: bool,
+}
+
+impl RunRestore {
+    pub(crate) fn is_waiting_for_room(&self) -> bool {
+        !self.room_instantiated
+    }
+
+    pub(crate) fn mark_room_as_instantiated(&mut self) {
+        self.room_instantiated = true;
+    }
+
+    pub(crate) fn restores_enemies(&self) -> bool {
+        self.enemies.is_some()
+    }
+
+    fn is_done(&self) -> bool {
+        let are_enemies_done = match &self.enemies {
+            Some(enemies) => self.spawned_enemies && enemies.is_empty(),
+            None => self.room_instantiated,
+        };
+        self.conditions.is_none()
+            && self.counters.is_none()
+            && self.current_room.is_none()
+            && self.run_layout.is_none()
+            && self.encounter.is_none()
+            && self.side_effects.is_none()
+            && self.player.is_none()
+            && are_enemies_done
+    }
 }
 
-#[sysfail(log(level = "error"))]
 fn handle_load_requests(
     mut commands: Commands,
     mut load_events: EventReader<GameLoadRequest>,
     mut loader: EventWriter<WorldLoadRequest>,
     mut spawner: EventWriter<SpawnEvent<GameObject, Transform>>,
     mut dialog_event_writer: EventWriter<DialogEvent>,
-) -> Result<()> {
+    save_slots: Res<SaveSlots>,
+    mut playtime: ResMut<Playtime>,
+    user_dirs: Res<UserDirs>,
+) {
+    let storage = user_dirs.data.as_ref();
     for load in load_events.iter() {
-        let path = match load
+        let name = match load
             .filename
-            .as_ref()
-            .map(|filename| anyhow::Ok(Some(get_save_path(filename.clone()))))
-            .unwrap_or_else(|| {
-                let mut saves: Vec<_> = glob("./saves/*.sav.ron")
-                    .context("Failed to read glob pattern")?
-                    .filter_map(|entry| entry.ok())
-                    .filter(|entry| entry.is_file())
-                    .collect();
-                saves.sort_by_cached_key(|f| {
-                    f.metadata()
-                        .expect("Failed to read file metadata")
-                        .modified()
-                        .expect("Failed to read file modified time")
-                });
-                Ok(saves.last().map(|entry| entry.to_owned()))
-            })? {
-            Some(path) => path,
+            .clone()
+            .or_else(|| save_slots.newest().map(|slot| slot.name.clone()))
+        {
+            Some(name) => name,
             None => {
                 error!("Failed to load save: No filename provided and no saves found on disk");
                 continue;
             }
         };
-        let serialized = match fs::read_to_string(&path) {
-            Ok(serialized) => {
-                info!("Successfully read save at {}", path.to_string_lossy());
-                serialized
-            }
-            Err(e) => {
-                error!(
-                    "Failed to read save {:?} at {:?}: {}",
-                    &load.filename, path, e
-                );
-                continue;
-            }
-        };
-        let save_model: SaveModel = match ron::from_str(&serialized) {
+        let path = get_save_path(&name);
+        let save_model = match read_save(storage, &path) {
             Ok(save_model) => save_model,
             Err(e) => {
-                error!(
-                    "Failed to deserialize save {:?} at {:?}: {}",
-                    &load.filename, path, e
+                let backup_path = get_backup_path(&path);
+                warn!(
+                    "Failed to load save \"{name}\" at {}, falling back to its backup: {e:?}",
+                    storage.describe(&path)
                 );
-                continue;
+                match read_save(storage, &backup_path) {
+                    Ok(save_model) => save_model,
+                    Err(e) => {
+                        error!(
+                            "Failed to load backup of save \"{name}\" at {}: {e:?}",
+                            storage.describe(&backup_path)
+                        );
+                        continue;
+                    }
+                }
             }
         };
         loader.send(WorldLoadRequest {
             filename: save_model.scene,
+            room: save_model.room,
+            room_transform: save_model.room_transform,
         });
         if let Some(dialog_event) = save_model.dialog_event {
             dialog_event_writer.send(dialog_event);
         }
-        commands.insert_resource(save_model.conditions);
+        commands.insert_resource(RunRestore {
+            conditions: Some(save_model.conditions),
+            counters: Some(save_model.counters),
+            current_room: Some(save_model.current_room),
+            run_layout: save_model.run_layout,
+            encounter: save_model.encounter,
+            side_effects: Some(save_model.side_effects),
+            player: save_model.player,
+            enemies: save_model.enemies,
+            ..default()
+        });
+        *playtime = save_slots
+            .get(&name)
+            .and_then(|slot| slot.metadata.as_ref())
+            .map(|metadata| Playtime(metadata.playtime))
+            .unwrap_or_default();
 
         spawner.send(
             SpawnEvent::with_data(GameObject::Player, save_model.player_transform).delay_frames(2),
         );
     }
-    Ok(())
+}
+
+fn read_save(storage: &dyn UserStorage, path: &Path) -> Result<SaveModel> {
+    let serialized = storage
+        .read_to_string(path)
+        .context("Failed to read save")?;
+    let save_model = deserialize_save(&serialized)?;
+    info!("Successfully read save at {}", storage.describe(path));
+    Ok(save_model)
+}
+
+fn restore_run(
+    mut commands: Commands,
+    mut run_restore: ResMut<RunRestore>,
+    mut players: Query<(&mut Constitution, &mut PlayerCombatState), With<Player>>,
+    mut enemies: Query<
+        (&Transform, &GameObject, &mut Enemy, &mut Constitution),
+        (Added<Enemy>, Without<Player>),
+    >,
+    mut spawner: EventWriter<SpawnEvent<GameObject, Transform>>,
+) {
+    // Running one frame after the load request, so these are inserted after the level load reset them
+    if let Some(conditions) = run_restore.conditions.take() {
+        commands.insert_resource(conditions);
+    }
+    if let Some(counters) = run_restore.counters.take() {
+        commands.insert_resource(counters);
+    }
+    if let Some(current_room) = run_restore.current_room.take() {
+        commands.insert_resource(current_room);
+    }
+    if let Some(run_layout) = run_restore.run_layout.take() {
+        commands.insert_resource(run_layout);
+    }
+    if let Some(encounter) = run_restore.encounter.take() {
+        commands.insert_resource(encounter);
+    }
+    if let Some(side_effects) = run_restore.side_effects.take() {
+        commands.insert_resource(side_effects);
+    }
+    if let Ok((mut constitution, mut combat_state)) = players.get_single_mut() {
+        if let Some(player) = run_restore.player.take() {
+            *constitution = player.constitution;
+            *combat_state = PlayerCombatState {
+                started_animation: false,
+                ..player.combat_state
+            };
+        }
+    }
+    if run_restore.room_instantiated && !run_restore.spawned_enemies {
+        for enemy in run_restore.enemies.iter().flatten() {
+            spawner.send(SpawnEvent::with_data(enemy.game_object, enemy.transform));
+        }
+        run_restore.spawned_enemies = true;
+    }
+    if let Some(saved_enemies) = run_restore.enemies.as_mut() {
+        for (transform, game_object, mut enemy, mut constitution) in enemies.iter_mut() {
+            let closest_saved_enemy = saved_enemies
+                .iter()
+                .enumerate()
+                .filter(|(_, saved_enemy)| saved_enemy.game_object == *game_object)
+                .min_by(|(_, a), (_, b)| {
+                    let distance_a = a
+                        .transform
+                        .translation
+                        .distance_squared(transform.translation);
+                    let distance_b = b
+                        .transform
+                        .translation
+                        .distance_squared(transform.translation);
+                    distance_a.total_cmp(&distance_b)
+                })
+                .map(|(index, _)| index);
+            if let Some(index) = closest_saved_enemy {
+                let saved_enemy = saved_enemies.swap_remove(index);
+                *constitution = saved_enemy.constitution;
+                enemy.is_aggroed = saved_enemy.is_aggroed;
+                if let Some(progress) = saved_enemy.choreography {
+                    enemy.resume(progress);
+                }
+            }
+        }
+    }
+    if run_restore.is_done() {
+        commands.remove_resource::<RunRestore>();
+    }
+}
+
+/// The resources describing the progress of the current run.
+#[derive(SystemParam)]
+struct RunResources<'w> {
+    conditions: Res<'w, ActiveConditions>,
+    counters: Res<'w, Counters>,
+    current_room: Res<'w, CurrentRoom>,
+    run_layout: Option<Res<'w, RunLayout>>,
+    encounter: Option<Res<'w, CurrentEncounter>>,
+    side_effects: Res<'w, SideEffects>,
 }
 
 #[sysfail(log(level = "error"))]
 fn handle_save_requests(
     mut save_events: EventReader<GameSaveRequest>,
-    conditions: Res<ActiveConditions>,
+    run: RunResources,
     dialog: Option<Res<CurrentDialog>>,
-    player_query: Query<&GlobalTransform, With<Player>>,
+    player_query: Query<(&GlobalTransform, &Constitution, &PlayerCombatState), With<Player>>,
+    rooms: Query<(&GameObject, &Transform), (With<Room>, Without<PreviousRoom>)>,
+    room_transition: Option<Res<RoomTransition>>,
+    enemies: Query<(&GameObject, &Transform, &Enemy, &Constitution), Without<Player>>,
     current_level: Res<CurrentLevel>,
+    playtime: Res<Playtime>,
+    mut save_slots: ResMut<SaveSlots>,
+    mut thumbnail_requests: EventWriter<ThumbnailRequest>,
+    user_dirs: Res<UserDirs>,
 ) -> Result<()> {
+    let storage = user_dirs.data.as_ref();
     let dialog = dialog.map(|dialog| dialog.clone());
     for save in save_events.iter() {
-        for player in &player_query {
+        for (player, constitution, combat_state) in &player_query {
             let dialog_event = dialog.clone().map(|dialog| DialogEvent {
                 dialog: dialog.id,
                 source: dialog.source,
                 page: Some(dialog.current_page),
             });
+            let saved_enemies = enemies
+                .iter()
+                .filter(|(.., enemy, _)| !enemy.is_dead)
+                .map(|(game_object, transform, enemy, constitution)| SavedEnemy {
+                    game_object: *game_object,
+                    transform: *transform,
+                    constitution: *constitution,
+                    choreography: enemy.progress(),
+                    is_aggroed: enemy.is_aggroed,
+                })
+                .collect();
+            let room = rooms.iter().find(|(room, _)| room.is_room());
+            let mut player_transform = player.compute_transform();
+            if let Some(room_transition) = room_transition.as_ref() {
+                // The previous room is not saved, so the player continues from the entrance of the next one
+                player_transform.translation = room_transition.anchor.translation;
+            }
             let save_model = SaveModel {
+                version: CURRENT_SAVE_VERSION,
                 scene: current_level.scene.clone(),
-                conditions: conditions.clone(),
+                conditions: run.conditions.clone(),
+                counters: run.counters.clone(),
                 dialog_event,
-                player_transform: player.compute_transform(),
+                player_transform,
+                room: room.map(|(room, _)| *room),
+                room_transform: room.map(|(_, transform)| *transform),
+                current_room: run.current_room.clone(),
+                run_layout: run.run_layout.as_deref().cloned(),
+                encounter: run.encounter.as_deref().cloned(),
+                side_effects: run.side_effects.clone(),
+                player: Some(SavedPlayer {
+                    constitution: *constitution,
+                    combat_state: *combat_state,
+                }),
+                enemies: Some(saved_enemies),
             };
             let serialized = match ron::to_string(&save_model) {
                 Ok(string) => string,
@@ -148,23 +402,41 @@ fn handle_save_requests(
                     continue;
                 }
             };
+            let metadata = SaveSlotMetadata::new(run.current_room.number, *playtime);
+            let serialized_metadata = match ron::to_string(&metadata) {
+                Ok(string) => string,
+                Err(e) => {
+                    error!("Failed to save metadata: {}", e);
+                    continue;
+                }
+            };
             let filename = save
                 .filename
                 .clone()
                 .unwrap_or_else(|| Local::now().to_rfc2822().replace(':', "-"));
-            let path = get_save_path(filename.clone());
-            let dir = path.parent().context("Failed to get save directory")?;
-            fs::create_dir_all(dir).context("Failed to create save directory")?;
-            fs::write(&path, serialized)
-                .unwrap_or_else(|e| error!("Failed to write save {filename}: {e}"));
+            if let Err(e) = validate_slot_name(&filename) {
+                error!("Failed to save game {filename}: {e}");
+                continue;
+            }
+            let path = get_save_path(&filename);
+            save_slots.mark_outdated();
+            if let Err(e) = storage.write_atomically(&path, serialized.as_bytes(), true) {
+                error!("Failed to write save {filename}: {e:?}");
+                continue;
+            }
+            storage
+                .write_atomically(
+                    &get_metadata_path(&filename),
+                    serialized_metadata.as_bytes(),
+                    false,
+                )
+                .unwrap_or_else(|e| error!("Failed to write metadata of save {filename}: {e:?}"));
+            thumbnail_requests.send(ThumbnailRequest {
+                path: get_thumbnail_path(&filename),
+            });
 
-            info!("Successfully saved game at {}", path.to_string_lossy());
+            info!("Successfully saved game at {}", storage.describe(&path));
         }
     }
     Ok(())
 }
-
-fn get_save_path(filename: impl Into<Cow<'static, str>>) -> PathBuf {
-    let filename = filename.into().to_string();
-    Path::new("saves").join(filename).with_extension("sav.ron")
-}
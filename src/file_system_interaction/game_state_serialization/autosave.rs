@@ -0,0 +1,70 @@
+use crate::file_system_interaction::config::GameConfig;
+use crate::file_system_interaction::game_state_serialization::GameSaveRequest;
+use crate::level_instantiation::spawning::GameObject;
+use crate::world_interaction::room::{CurrentRoom, EnterRoomEvent, Room, RoomClearEvent};
+use bevy::prelude::*;
+use bevy::scene::SceneInstance;
+
+pub(crate) const AUTOSAVE_SLOT: &str = "Autosave";
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub(crate) struct AutosaveState {
+    /// Frames since the last room was entered, while waiting for it to be instantiated.
+    entering_room_since: Option<u32>,
+    cleared_room: Option<usize>,
+}
+
+/// Saves into [`AUTOSAVE_SLOT`] when entering or clearing a room, depending on [`GameConfig`].
+/// Saving on entering waits until the new room is instantiated so that the player is already placed at its entrance.
+pub(crate) fn autosave(
+    mut state: Local<AutosaveState>,
+    mut enter_room_events: EventReader<EnterRoomEvent>,
+    mut room_clear_events: EventReader<RoomClearEvent>,
+    config: Res<GameConfig>,
+    current_room: Res<CurrentRoom>,
+    rooms: Query<(&GameObject, Option<&SceneInstance>), With<Room>>,
+    scene_spawner: Res<SceneSpawner>,
+    mut save_requests: EventWriter<GameSaveRequest>,
+) {
+    if !enter_room_events.is_empty() {
+        enter_room_events.clear();
+        state.cleared_room = None;
+        if config.saves.autosave_on_room_enter {
+            state.entering_room_since = Some(0);
+        }
+    }
+    // Sent every frame while the room is cleared
+    let is_room_cleared = !room_clear_events.is_empty();
+    room_clear_events.clear();
+    if is_room_cleared && state.cleared_room != Some(current_room.number) {
+        state.cleared_room = Some(current_room.number);
+        if config.saves.autosave_on_room_clear {
+            save_requests.send(GameSaveRequest {
+                filename: Some(AUTOSAVE_SLOT.to_string()),
+            });
+        }
+    }
+
+    let Some(frames) = state.entering_room_since.as_mut() else {
+        return;
+    };
+    *frames += 1;
+    // The previous room is only despawned at the end of the frame it was left in
+    let mut rooms = rooms
+        .iter()
+        .filter(|(game_object, _)| game_object.is_room())
+        .peekable();
+    let is_room_ready = *frames > 1
+        && rooms.peek().is_some()
+        && rooms.all(|(_, instance)| {
+            instance.map_or(false, |instance| {
+                scene_spawner.instance_is_ready(**instance)
+            })
+        });
+    if is_room_ready {
+        state.entering_room_since = None;
+        save_requests.send(GameSaveRequest {
+            filename: Some(AUTOSAVE_SLOT.to_string()),
+        });
+    }
+}
@@ -0,0 +1,942 @@
+use crate::combat::{ChoreographyProgress, Constitution};
+use crate::file_system_interaction::game_state_serialization::{
+    SaveModel, SavedEnemy, SavedPlayer,
+};
+use crate::level_instantiation::spawning::GameObject;
+use crate::player_control::player_embodiment::combat::{
+    AttackCommitment, PlayerCombatKind, PlayerCombatState,
+};
+use crate::world_interaction::condition::{ActiveConditions, ConditionId, Counters};
+use crate::world_interaction::dialog::{DialogEvent, DialogId};
+use crate::world_interaction::room::{
+    CurrentEncounter, CurrentRoom, RoomId, RoomKind, RoomNode, RunLayout,
+};
+use crate::world_interaction::side_effects::{SideEffect, SideEffects};
+use anyhow::{bail, Context, Result};
+use bevy::prelude::*;
+use bevy::utils::{HashMap, HashSet};
+use serde::Deserialize;
+
+/// Saves are upgraded one version at a time until they reach this version.
+/// When changing the shape of [`SaveModel`] or of any type it contains, bump this,
+/// copy the old shape into a new `SaveModelV*` in this module and add a step from it to the next version.
+/// Also add a save of the old version to `saves/fixtures`, which the tests below migrate and compare against its expected contents.
+pub(super) const CURRENT_SAVE_VERSION: u32 = 7;
+
+/// Only reads the version of a save, ignoring everything else.
+/// Saves written before versioning was introduced have no version and are treated as version 0.
+#[derive(Debug, Clone, Copy, Deserialize)]
+struct SaveHeader {
+    #[serde(default)]
+    version: u32,
+}
+
+enum VersionedSave {
+    V0(SaveModelV0),
+    V1(SaveModelV1),
+    V2(SaveModelV2),
+    V3(SaveModelV3),
+    V4(SaveModelV4),
+    V5(SaveModelV5),
+    V6(SaveModelV6),
+    V7(SaveModel),
+}
+
+impl VersionedSave {
+    fn deserialize(version: u32, serialized: &str) -> Result<Self> {
+        let save = match version {
+            0 => VersionedSave::V0(ron::from_str(serialized)?),
+            1 => VersionedSave::V1(ron::from_str(serialized)?),
+            2 => VersionedSave::V2(ron::from_str(serialized)?),
+            3 => VersionedSave::V3(ron::from_str(serialized)?),
+            4 => VersionedSave::V4(ron::from_str(serialized)?),
+            5 => VersionedSave::V5(ron::from_str(serialized)?),
+            6 => VersionedSave::V6(ron::from_str(serialized)?),
+            7 => VersionedSave::V7(ron::from_str(serialized)?),
+            _ => bail!(
+                "Save version {version} is newer than the newest supported version {CURRENT_SAVE_VERSION}"
+            ),
+        };
+        Ok(save)
+    }
+}
+
+/// Deserializes a save of any known version and migrates it to the current one.
+pub(super) fn deserialize_save(serialized: &str) -> Result<SaveModel> {
+    let header: SaveHeader = ron::from_str(serialized).context("Failed to read save version")?;
+    let mut save = VersionedSave::deserialize(header.version, serialized)
+        .with_context(|| format!("Failed to deserialize save with version {}", header.version))?;
+    loop {
+        save = match save {
+            VersionedSave::V0(save) => VersionedSave::V1(migrate_v0_to_v1(save)),
+            VersionedSave::V1(save) => VersionedSave::V2(migrate_v1_to_v2(save)),
+            VersionedSave::V2(save) => VersionedSave::V3(migrate_v2_to_v3(save)),
+            VersionedSave::V3(save) => VersionedSave::V4(migrate_v3_to_v4(save)),
+            VersionedSave::V4(save) => VersionedSave::V5(migrate_v4_to_v5(save)),
+            VersionedSave::V5(save) => VersionedSave::V6(migrate_v5_to_v6(save)),
+            VersionedSave::V6(save) => VersionedSave::V7(migrate_v6_to_v7(save)),
+            VersionedSave::V7(save) => return Ok(save),
+        };
+    }
+}
+
+/// Saves written before versioning. The room, side effects, player and enemies were added over time,
+/// so they may be missing.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+struct SaveModelV0 {
+    scene: String,
+    #[serde(default)]
+    conditions: ActiveConditionsV6,
+    player_transform: Transform,
+    #[serde(default)]
+    dialog_event: Option<DialogEventV6>,
+    #[serde(default)]
+    room: Option<LegacyRoom>,
+    #[serde(default)]
+    current_room: CurrentRoomV6,
+    #[serde(default)]
+    side_effects: SideEffectsV6,
+    #[serde(default)]
+    player: Option<SavedPlayerV6>,
+    #[serde(default)]
+    enemies: Option<Vec<SavedEnemyV6>>,
+}
+
+fn migrate_v0_to_v1(save: SaveModelV0) -> SaveModelV1 {
+    SaveModelV1 {
+        scene: save.scene,
+        conditions: save.conditions,
+        player_transform: save.player_transform,
+        dialog_event: save.dialog_event,
+        room: save.room,
+        current_room: save.current_room,
+        side_effects: save.side_effects,
+        player: save.player,
+        enemies: save.enemies,
+    }
+}
+
+/// Saves written before rooms were attached to each other, when every room was spawned where the level placed it.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+struct SaveModelV1 {
+    scene: String,
+    #[serde(default)]
+    conditions: ActiveConditionsV6,
+    player_transform: Transform,
+    #[serde(default)]
+    dialog_event: Option<DialogEventV6>,
+    #[serde(default)]
+    room: Option<LegacyRoom>,
+    #[serde(default)]
+    current_room: CurrentRoomV6,
+    #[serde(default)]
+    side_effects: SideEffectsV6,
+    #[serde(default)]
+    player: Option<SavedPlayerV6>,
+    #[serde(default)]
+    enemies: Option<Vec<SavedEnemyV6>>,
+}
+
+fn migrate_v1_to_v2(save: SaveModelV1) -> SaveModelV2 {
+    SaveModelV2 {
+        scene: save.scene,
+        conditions: save.conditions,
+        player_transform: save.player_transform,
+        dialog_event: save.dialog_event,
+        room: save.room,
+        room_transform: None,
+        current_room: save.current_room,
+        side_effects: save.side_effects,
+        player: save.player,
+        enemies: save.enemies,
+    }
+}
+
+/// Saves written before runs were generated as a layout of rooms, when every next room was picked at random.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+struct SaveModelV2 {
+    scene: String,
+    #[serde(default)]
+    conditions: ActiveConditionsV6,
+    player_transform: Transform,
+    #[serde(default)]
+    dialog_event: Option<DialogEventV6>,
+    #[serde(default)]
+    room: Option<LegacyRoom>,
+    #[serde(default)]
+    room_transform: Option<Transform>,
+    #[serde(default)]
+    current_room: CurrentRoomV6,
+    #[serde(default)]
+    side_effects: SideEffectsV6,
+    #[serde(default)]
+    player: Option<SavedPlayerV6>,
+    #[serde(default)]
+    enemies: Option<Vec<SavedEnemyV6>>,
+}
+
+fn migrate_v2_to_v3(save: SaveModelV2) -> SaveModelV3 {
+    SaveModelV3 {
+        scene: save.scene,
+        conditions: save.conditions,
+        player_transform: save.player_transform,
+        dialog_event: save.dialog_event,
+        room: save.room,
+        room_transform: save.room_transform,
+        current_room: save.current_room,
+        run_layout: None,
+        side_effects: save.side_effects,
+        player: save.player,
+        enemies: save.enemies,
+    }
+}
+
+/// Saves written before rooms were defined by `*.room.ron` assets, when every room was its own [`GameObject`] variant.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+struct SaveModelV3 {
+    scene: String,
+    #[serde(default)]
+    conditions: ActiveConditionsV6,
+    player_transform: Transform,
+    #[serde(default)]
+    dialog_event: Option<DialogEventV6>,
+    #[serde(default)]
+    room: Option<LegacyRoom>,
+    #[serde(default)]
+    room_transform: Option<Transform>,
+    #[serde(default)]
+    current_room: CurrentRoomV6,
+    #[serde(default)]
+    run_layout: Option<RunLayoutV3>,
+    #[serde(default)]
+    side_effects: SideEffectsV6,
+    #[serde(default)]
+    player: Option<SavedPlayerV6>,
+    #[serde(default)]
+    enemies: Option<Vec<SavedEnemyV6>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+struct RunLayoutV3 {
+    seed: u64,
+    floor: usize,
+    nodes: Vec<RoomNodeV3>,
+    current: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+struct RoomNodeV3 {
+    room: LegacyRoom,
+    kind: RoomKindV6,
+    depth: usize,
+    next: Vec<usize>,
+}
+
+/// The [`GameObject`] variants rooms used to have.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Deserialize)]
+enum LegacyRoom {
+    IntroRoom,
+    RoomOne,
+    RoomTwo,
+    RoomThree,
+}
+
+impl LegacyRoom {
+    fn to_room_name(self) -> String {
+        match self {
+            LegacyRoom::IntroRoom => "intro_room",
+            LegacyRoom::RoomOne => "room_one",
+            LegacyRoom::RoomTwo => "room_two",
+            LegacyRoom::RoomThree => "room_three",
+        }
+        .to_string()
+    }
+}
+
+fn migrate_v3_to_v4(save: SaveModelV3) -> SaveModelV4 {
+    SaveModelV4 {
+        scene: save.scene,
+        conditions: save.conditions,
+        player_transform: save.player_transform,
+        dialog_event: save.dialog_event,
+        room: save
+            .room
+            .map(|room| GameObjectV6::Room(room.to_room_name())),
+        room_transform: save.room_transform,
+        current_room: save.current_room,
+        run_layout: save.run_layout.map(|run_layout| RunLayoutV6 {
+            seed: run_layout.seed,
+            floor: run_layout.floor,
+            nodes: run_layout
+                .nodes
+                .into_iter()
+                .map(|node| RoomNodeV6 {
+                    room: node.room.to_room_name(),
+                    kind: node.kind,
+                    depth: node.depth,
+                    next: node.next,
+                })
+                .collect(),
+            current: run_layout.current,
+        }),
+        side_effects: save.side_effects,
+        player: save.player,
+        enemies: save.enemies,
+    }
+}
+
+/// Saves written before rooms had encounters, when all enemies of a room spawned with it.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+struct SaveModelV4 {
+    scene: String,
+    #[serde(default)]
+    conditions: ActiveConditionsV6,
+    player_transform: Transform,
+    #[serde(default)]
+    dialog_event: Option<DialogEventV6>,
+    #[serde(default)]
+    room: Option<GameObjectV6>,
+    #[serde(default)]
+    room_transform: Option<Transform>,
+    #[serde(default)]
+    current_room: CurrentRoomV6,
+    #[serde(default)]
+    run_layout: Option<RunLayoutV6>,
+    #[serde(default)]
+    side_effects: SideEffectsV6,
+    #[serde(default)]
+    player: Option<SavedPlayerV6>,
+    #[serde(default)]
+    enemies: Option<Vec<SavedEnemyV6>>,
+}
+
+fn migrate_v4_to_v5(save: SaveModelV4) -> SaveModelV5 {
+    SaveModelV5 {
+        scene: save.scene,
+        conditions: save.conditions,
+        player_transform: save.player_transform,
+        dialog_event: save.dialog_event,
+        room: save.room,
+        room_transform: save.room_transform,
+        current_room: save.current_room,
+        run_layout: save.run_layout,
+        encounter: None,
+        side_effects: save.side_effects,
+        player: save.player,
+        enemies: save.enemies,
+    }
+}
+
+/// Saves written before dialogs had counters.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+struct SaveModelV5 {
+    scene: String,
+    #[serde(default)]
+    conditions: ActiveConditionsV6,
+    player_transform: Transform,
+    #[serde(default)]
+    dialog_event: Option<DialogEventV6>,
+    #[serde(default)]
+    room: Option<GameObjectV6>,
+    #[serde(default)]
+    room_transform: Option<Transform>,
+    #[serde(default)]
+    current_room: CurrentRoomV6,
+    #[serde(default)]
+    run_layout: Option<RunLayoutV6>,
+    #[serde(default)]
+    encounter: Option<CurrentEncounterV6>,
+    #[serde(default)]
+    side_effects: SideEffectsV6,
+    #[serde(default)]
+    player: Option<SavedPlayerV6>,
+    #[serde(default)]
+    enemies: Option<Vec<SavedEnemyV6>>,
+}
+
+fn migrate_v5_to_v6(save: SaveModelV5) -> SaveModelV6 {
+    SaveModelV6 {
+        scene: save.scene,
+        conditions: save.conditions,
+        counters: default(),
+        player_transform: save.player_transform,
+        dialog_event: save.dialog_event,
+        room: save.room,
+        room_transform: save.room_transform,
+        current_room: save.current_room,
+        run_layout: save.run_layout,
+        encounter: save.encounter,
+        side_effects: save.side_effects,
+        player: save.player,
+        enemies: save.enemies,
+    }
+}
+
+/// Saves written before enemies continued their choreography from the saved move, when they only remembered which one it was.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+struct SaveModelV6 {
+    scene: String,
+    #[serde(default)]
+    conditions: ActiveConditionsV6,
+    #[serde(default)]
+    counters: CountersV6,
+    player_transform: Transform,
+    #[serde(default)]
+    dialog_event: Option<DialogEventV6>,
+    #[serde(default)]
+    room: Option<GameObjectV6>,
+    #[serde(default)]
+    room_transform: Option<Transform>,
+    #[serde(default)]
+    current_room: CurrentRoomV6,
+    #[serde(default)]
+    run_layout: Option<RunLayoutV6>,
+    #[serde(default)]
+    encounter: Option<CurrentEncounterV6>,
+    #[serde(default)]
+    side_effects: SideEffectsV6,
+    #[serde(default)]
+    player: Option<SavedPlayerV6>,
+    #[serde(default)]
+    enemies: Option<Vec<SavedEnemyV6>>,
+}
+
+/// [`ActiveConditions`] up to version 6.
+#[derive(Debug, Clone, PartialEq, Deserialize, Default)]
+struct ActiveConditionsV6(HashSet<String>);
+
+impl From<ActiveConditionsV6> for ActiveConditions {
+    fn from(conditions: ActiveConditionsV6) -> Self {
+        ActiveConditions(conditions.0.into_iter().map(ConditionId).collect())
+    }
+}
+
+/// [`Counters`] up to version 6.
+#[derive(Debug, Clone, PartialEq, Deserialize, Default)]
+struct CountersV6(HashMap<String, i32>);
+
+impl From<CountersV6> for Counters {
+    fn from(counters: CountersV6) -> Self {
+        Counters(counters.0)
+    }
+}
+
+/// [`DialogEvent`] up to version 6.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+struct DialogEventV6 {
+    dialog: String,
+    source: Entity,
+    page: Option<String>,
+}
+
+impl From<DialogEventV6> for DialogEvent {
+    fn from(dialog_event: DialogEventV6) -> Self {
+        DialogEvent {
+            dialog: DialogId::new(&dialog_event.dialog),
+            source: dialog_event.source,
+            page: dialog_event.page.map(Into::into),
+        }
+    }
+}
+
+/// [`GameObject`] up to version 6. Saves before version 4 had [`LegacyRoom`] variants instead of `Room`,
+/// which only ever show up in their `room`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+enum GameObjectV6 {
+    Empty,
+    Box,
+    Triangle,
+    Sphere,
+    Capsule,
+    Sunlight,
+    PointLight,
+    Dummy,
+    Player,
+    Room(String),
+    Orb,
+    Camera,
+    Skydome,
+    Exit,
+}
+
+impl From<GameObjectV6> for GameObject {
+    fn from(game_object: GameObjectV6) -> Self {
+        match game_object {
+            GameObjectV6::Empty => GameObject::Empty,
+            GameObjectV6::Box => GameObject::Box,
+            GameObjectV6::Triangle => GameObject::Triangle,
+            GameObjectV6::Sphere => GameObject::Sphere,
+            GameObjectV6::Capsule => GameObject::Capsule,
+            GameObjectV6::Sunlight => GameObject::Sunlight,
+            GameObjectV6::PointLight => GameObject::PointLight,
+            GameObjectV6::Dummy => GameObject::Dummy,
+            GameObjectV6::Player => GameObject::Player,
+            GameObjectV6::Room(room) => GameObject::Room(RoomId::new(&room)),
+            GameObjectV6::Orb => GameObject::Orb,
+            GameObjectV6::Camera => GameObject::Camera,
+            GameObjectV6::Skydome => GameObject::Skydome,
+            GameObjectV6::Exit => GameObject::Exit,
+        }
+    }
+}
+
+/// [`CurrentRoom`] up to version 6.
+#[derive(Debug, Clone, PartialEq, Deserialize, Default)]
+struct CurrentRoomV6 {
+    cleared: bool,
+    number: usize,
+}
+
+impl From<CurrentRoomV6> for CurrentRoom {
+    fn from(current_room: CurrentRoomV6) -> Self {
+        CurrentRoom {
+            cleared: current_room.cleared,
+            number: current_room.number,
+        }
+    }
+}
+
+/// [`RunLayout`] up to version 6.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+struct RunLayoutV6 {
+    seed: u64,
+    floor: usize,
+    nodes: Vec<RoomNodeV6>,
+    current: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+struct RoomNodeV6 {
+    room: String,
+    kind: RoomKindV6,
+    depth: usize,
+    next: Vec<usize>,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Deserialize)]
+enum RoomKindV6 {
+    Combat,
+    Rest,
+    Shop,
+    Boss,
+}
+
+impl From<RunLayoutV6> for RunLayout {
+    fn from(run_layout: RunLayoutV6) -> Self {
+        RunLayout {
+            seed: run_layout.seed,
+            floor: run_layout.floor,
+            nodes: run_layout
+                .nodes
+                .into_iter()
+                .map(|node| RoomNode {
+                    room: RoomId::new(&node.room),
+                    kind: match node.kind {
+                        RoomKindV6::Combat => RoomKind::Combat,
+                        RoomKindV6::Rest => RoomKind::Rest,
+                        RoomKindV6::Shop => RoomKind::Shop,
+                        RoomKindV6::Boss => RoomKind::Boss,
+                    },
+                    depth: node.depth,
+                    next: node.next,
+                })
+                .collect(),
+            current: run_layout.current,
+        }
+    }
+}
+
+/// [`CurrentEncounter`] up to version 6.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+struct CurrentEncounterV6 {
+    next_wave: usize,
+    countdown: Option<f32>,
+    is_waiting_for_enemies: bool,
+}
+
+impl From<CurrentEncounterV6> for CurrentEncounter {
+    fn from(encounter: CurrentEncounterV6) -> Self {
+        CurrentEncounter::from_progress(
+            encounter.next_wave,
+            encounter.countdown,
+            encounter.is_waiting_for_enemies,
+        )
+    }
+}
+
+/// [`SideEffects`] up to version 6.
+#[derive(Debug, Clone, PartialEq, Deserialize, Default)]
+struct SideEffectsV6(HashMap<SideEffectV6, i32>);
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Deserialize)]
+enum SideEffectV6 {
+    Size,
+    BaseSpeed,
+    HealthDamage,
+    AttackPostureDamage,
+    AttackKnockback,
+    KnockbackResistance,
+    MaxPosture,
+    PostureRegenRate,
+    PostureRegenWait,
+    DeflectWindow,
+    DeflectPostureDamage,
+    BackwardsWalkingSpeed,
+    Health,
+}
+
+impl From<SideEffectsV6> for SideEffects {
+    fn from(side_effects: SideEffectsV6) -> Self {
+        let mut migrated = SideEffects::default();
+        for (side_effect, value) in side_effects.0 {
+            let side_effect = match side_effect {
+                SideEffectV6::Size => SideEffect::Size,
+                SideEffectV6::BaseSpeed => SideEffect::BaseSpeed,
+                SideEffectV6::HealthDamage => SideEffect::HealthDamage,
+                SideEffectV6::AttackPostureDamage => SideEffect::AttackPostureDamage,
+                SideEffectV6::AttackKnockback => SideEffect::AttackKnockback,
+                SideEffectV6::KnockbackResistance => SideEffect::KnockbackResistance,
+                SideEffectV6::MaxPosture => SideEffect::MaxPosture,
+                SideEffectV6::PostureRegenRate => SideEffect::PostureRegenRate,
+                SideEffectV6::PostureRegenWait => SideEffect::PostureRegenWait,
+                SideEffectV6::DeflectWindow => SideEffect::DeflectWindow,
+                SideEffectV6::DeflectPostureDamage => SideEffect::DeflectPostureDamage,
+                SideEffectV6::BackwardsWalkingSpeed => SideEffect::BackwardsWalkingSpeed,
+                SideEffectV6::Health => SideEffect::Health,
+            };
+            migrated.insert(side_effect, value);
+        }
+        migrated
+    }
+}
+
+/// [`Constitution`] up to version 6.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+struct ConstitutionV6 {
+    health: f32,
+    max_health: f32,
+    posture: f32,
+    vanilla_posture_recovery: f32,
+    vanilla_max_posture: f32,
+    vanilla_max_health: f32,
+    max_posture: f32,
+    base_posture_recovery: f32,
+    is_posture_broken: bool,
+    is_dead: bool,
+}
+
+impl From<ConstitutionV6> for Constitution {
+    fn from(constitution: ConstitutionV6) -> Self {
+        Constitution {
+            health: constitution.health,
+            max_health: constitution.max_health,
+            posture: constitution.posture,
+            vanilla_posture_recovery: constitution.vanilla_posture_recovery,
+            vanilla_max_posture: constitution.vanilla_max_posture,
+            vanilla_max_health: constitution.vanilla_max_health,
+            max_posture: constitution.max_posture,
+            base_posture_recovery: constitution.base_posture_recovery,
+            is_posture_broken: constitution.is_posture_broken,
+            is_dead: constitution.is_dead,
+        }
+    }
+}
+
+/// [`SavedPlayer`] up to version 6.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+struct SavedPlayerV6 {
+    constitution: ConstitutionV6,
+    combat_state: PlayerCombatStateV6,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+struct PlayerCombatStateV6 {
+    kind: PlayerCombatKindV6,
+    buffer: Option<PlayerCombatKindV6>,
+    commitment: AttackCommitmentV6,
+    time_in_state: f32,
+    time_since_hurt_or_block: f32,
+    time_since_sprint: f32,
+    started_animation: bool,
+    is_charging: bool,
+    charge_time: f32,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Deserialize)]
+enum PlayerCombatKindV6 {
+    Idle,
+    Attack(u16),
+    Block,
+    Deflected,
+    PostureBroken,
+    Hurt,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Deserialize)]
+enum AttackCommitmentV6 {
+    EarlyCancellable,
+    LateCancellable,
+    InBufferPeriod,
+    Committed,
+}
+
+impl From<PlayerCombatKindV6> for PlayerCombatKind {
+    fn from(kind: PlayerCombatKindV6) -> Self {
+        match kind {
+            PlayerCombatKindV6::Idle => PlayerCombatKind::Idle,
+            PlayerCombatKindV6::Attack(attack) => PlayerCombatKind::Attack(attack),
+            PlayerCombatKindV6::Block => PlayerCombatKind::Block,
+            PlayerCombatKindV6::Deflected => PlayerCombatKind::Deflected,
+            PlayerCombatKindV6::PostureBroken => PlayerCombatKind::PostureBroken,
+            PlayerCombatKindV6::Hurt => PlayerCombatKind::Hurt,
+        }
+    }
+}
+
+impl From<SavedPlayerV6> for SavedPlayer {
+    fn from(player: SavedPlayerV6) -> Self {
+        let combat_state = player.combat_state;
+        SavedPlayer {
+            constitution: player.constitution.into(),
+            combat_state: PlayerCombatState {
+                kind: combat_state.kind.into(),
+                buffer: combat_state.buffer.map(Into::into),
+                commitment: match combat_state.commitment {
+                    AttackCommitmentV6::EarlyCancellable => AttackCommitment::EarlyCancellable,
+                    AttackCommitmentV6::LateCancellable => AttackCommitment::LateCancellable,
+                    AttackCommitmentV6::InBufferPeriod => AttackCommitment::InBufferPeriod,
+                    AttackCommitmentV6::Committed => AttackCommitment::Committed,
+                },
+                time_in_state: combat_state.time_in_state,
+                time_since_hurt_or_block: combat_state.time_since_hurt_or_block,
+                time_since_sprint: combat_state.time_since_sprint,
+                started_animation: combat_state.started_animation,
+                is_charging: combat_state.is_charging,
+                charge_time: combat_state.charge_time,
+            },
+        }
+    }
+}
+
+/// [`SavedEnemy`] up to version 6.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+struct SavedEnemyV6 {
+    game_object: GameObjectV6,
+    transform: Transform,
+    constitution: ConstitutionV6,
+    choreography: Option<usize>,
+    is_aggroed: bool,
+}
+
+impl From<SavedEnemyV6> for SavedEnemy {
+    fn from(enemy: SavedEnemyV6) -> Self {
+        SavedEnemy {
+            game_object: enemy.game_object.into(),
+            transform: enemy.transform,
+            constitution: enemy.constitution.into(),
+            // Restarted from the first move, as these saves always did
+            choreography: enemy.choreography.map(|choreography| ChoreographyProgress {
+                choreography,
+                ..default()
+            }),
+            is_aggroed: enemy.is_aggroed,
+        }
+    }
+}
+
+fn migrate_v6_to_v7(save: SaveModelV6) -> SaveModel {
+    SaveModel {
+        version: 7,
+        scene: save.scene,
+        conditions: save.conditions.into(),
+        counters: save.counters.into(),
+        player_transform: save.player_transform,
+        dialog_event: save.dialog_event.map(Into::into),
+        room: save.room.map(Into::into),
+        room_transform: save.room_transform,
+        current_room: save.current_room.into(),
+        run_layout: save.run_layout.map(Into::into),
+        encounter: save.encounter.map(Into::into),
+        side_effects: save.side_effects.into(),
+        player: save.player.map(Into::into),
+        enemies: save
+            .enemies
+            .map(|enemies| enemies.into_iter().map(Into::into).collect()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::path::{Path, PathBuf};
+
+    fn get_fixture_paths() -> Result<Vec<PathBuf>> {
+        let pattern = Path::new(env!("CARGO_MANIFEST_DIR")).join("saves/fixtures/*.sav.ron");
+        let paths = glob::glob(&pattern.to_string_lossy())
+            .context("Failed to read glob pattern")?
+            .collect::<Result<_, _>>()
+            .context("Failed to read save fixture path")?;
+        Ok(paths)
+    }
+
+    /// Splits a fixture name like `v3_room_run.sav.ron` into its version and the save it contains.
+    fn parse_fixture_name(path: &Path) -> Result<(u32, String)> {
+        let name = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .with_context(|| format!("Save fixture at {path:?} has no name"))?;
+        let (version, save) = name
+            .strip_prefix('v')
+            .and_then(|name| name.split_once('_'))
+            .with_context(|| {
+                format!("Save fixture name \"{name}\" does not start with its version")
+            })?;
+        let version = version
+            .parse()
+            .with_context(|| format!("Failed to parse version of save fixture \"{name}\""))?;
+        Ok((version, save.to_string()))
+    }
+
+    #[test]
+    fn migrates_save_fixtures() -> Result<()> {
+        for path in get_fixture_paths()? {
+            let serialized = fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read save fixture at {path:?}"))?;
+            let save_model = deserialize_save(&serialized)
+                .with_context(|| format!("Failed to migrate save fixture at {path:?}"))?;
+            assert_eq!(save_model.version, CURRENT_SAVE_VERSION, "{path:?}");
+            let (version, save) = parse_fixture_name(&path)?;
+            match save.as_str() {
+                "intro_room.sav.ron" => assert_intro_room(&save_model),
+                "room_run.sav.ron" => assert_room_run(&save_model, version),
+                _ => panic!("No expected contents for save fixture at {path:?}"),
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn has_fixture_for_every_previous_version() -> Result<()> {
+        let versions = get_fixture_paths()?
+            .iter()
+            .map(|path| parse_fixture_name(path).map(|(version, _)| version))
+            .collect::<Result<Vec<_>>>()?;
+        for version in 0..CURRENT_SAVE_VERSION {
+            assert!(
+                versions.contains(&version),
+                "No save fixture for version {version}"
+            );
+        }
+        Ok(())
+    }
+
+    /// A save from before rooms existed, made after talking to the follower.
+    fn assert_intro_room(save_model: &SaveModel) {
+        assert_eq!(save_model.scene, "intro_room");
+        assert!(save_model
+            .conditions
+            .0
+            .contains(&ConditionId("choice:who".to_string())));
+        assert_eq!(
+            save_model.player_transform.translation,
+            Vec3::new(7.0, 1.0, -3.0)
+        );
+        assert_eq!(save_model.room, None);
+        assert_eq!(save_model.current_room, CurrentRoom::default());
+        assert_eq!(save_model.player, None);
+        assert_eq!(save_model.enemies, None);
+    }
+
+    /// A run in room one with an aggroed enemy. Later versions of it also contain what the save model gained in that version.
+    fn assert_room_run(save_model: &SaveModel, version: u32) {
+        assert_eq!(save_model.scene, "intro_room");
+        assert_eq!(
+            save_model.player_transform.translation,
+            Vec3::new(0.0, 1.0, 4.0)
+        );
+        assert_eq!(
+            save_model.room,
+            Some(GameObject::Room(RoomId::new("room_one")))
+        );
+        assert_eq!(
+            save_model.current_room,
+            CurrentRoom {
+                cleared: false,
+                number: 2,
+            }
+        );
+
+        let side_effects = &save_model.side_effects;
+        assert_eq!(side_effects.get(SideEffect::Size), 0);
+        assert_eq!(side_effects.get(SideEffect::BaseSpeed), 1);
+        assert_eq!(side_effects.get(SideEffect::AttackPostureDamage), -1);
+        assert_eq!(side_effects.get(SideEffect::Health), 2);
+
+        let player = save_model.player.as_ref().expect("Player was not migrated");
+        assert_eq!(player.constitution.health(), 80.0);
+        assert_eq!(player.constitution.max_health(), 140.0);
+        assert_eq!(player.constitution.posture(), 10.0);
+        assert_eq!(player.combat_state.kind, PlayerCombatKind::Idle);
+        assert_eq!(player.combat_state.buffer, None);
+        assert_eq!(
+            player.combat_state.commitment,
+            AttackCommitment::EarlyCancellable
+        );
+        assert_eq!(player.combat_state.time_in_state, 1.5);
+        assert!(player.combat_state.started_animation);
+
+        let enemies = save_model
+            .enemies
+            .as_ref()
+            .expect("Enemies were not migrated");
+        assert_eq!(enemies.len(), 1);
+        let enemy = &enemies[0];
+        assert_eq!(enemy.game_object, GameObject::Dummy);
+        assert_eq!(enemy.transform.translation, Vec3::new(2.0, 0.0, -5.0));
+        assert_eq!(enemy.constitution.health(), 40.0);
+        assert_eq!(enemy.constitution.posture(), 30.0);
+        assert_eq!(
+            enemy.choreography,
+            Some(ChoreographyProgress {
+                choreography: 1,
+                ..default()
+            })
+        );
+        assert!(enemy.is_aggroed);
+
+        assert_eq!(
+            save_model
+                .room_transform
+                .map(|transform| transform.translation),
+            (version >= 2).then_some(Vec3::new(12.0, 0.0, -30.0))
+        );
+        match &save_model.run_layout {
+            Some(run_layout) => {
+                assert!(version >= 3, "Run layout appeared out of nowhere");
+                assert_eq!(run_layout.seed, 4242);
+                assert_eq!(run_layout.current, 1);
+                assert_eq!(run_layout.nodes.len(), 4);
+                assert_eq!(run_layout.nodes[1].room, RoomId::new("room_one"));
+                assert_eq!(run_layout.nodes[1].next, vec![3]);
+                assert_eq!(run_layout.nodes[3].kind, RoomKind::Boss);
+            }
+            None => assert!(version < 3, "Run layout was not migrated"),
+        }
+        assert_eq!(
+            save_model.encounter,
+            (version >= 5).then(|| CurrentEncounter::from_progress(1, Some(2.5), false))
+        );
+        assert_eq!(
+            save_model
+                .conditions
+                .0
+                .contains(&ConditionId("choice:who".to_string())),
+            version >= 5
+        );
+        assert_eq!(
+            save_model.counters.get("talked_to_follower"),
+            if version >= 6 { 2 } else { 0 }
+        );
+    }
+}
@@ -0,0 +1,388 @@
+use crate::file_system_interaction::game_state_serialization::thumbnail::ThumbnailWritten;
+use crate::file_system_interaction::user_dirs::{get_backup_path, UserDirs, UserStorage};
+use crate::localization::Localization;
+use crate::GameState;
+use anyhow::{bail, Context, Result};
+use bevy::prelude::*;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use bevy_egui::{egui, EguiContexts};
+use bevy_mod_sysfail::macros::*;
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+pub(crate) fn save_slots_plugin(app: &mut App) {
+    app.init_resource::<SaveSlots>()
+        .init_resource::<Playtime>()
+        .add_system(refresh_save_slots)
+        .add_system(update_playtime.in_set(OnUpdate(GameState::Playing)));
+}
+
+const SAVE_DIRECTORY: &str = "saves";
+
+/// All saves in the save directory of [`UserDirs::data`], newest first.
+/// Call [`SaveSlots::mark_outdated`] after changing the files of a slot so that it is read again.
+#[derive(Debug, Clone, Resource, Default)]
+pub(crate) struct SaveSlots {
+    slots: Vec<SaveSlot>,
+    is_up_to_date: bool,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct SaveSlot {
+    pub(crate) name: String,
+    /// `None` for saves written before slots had metadata.
+    pub(crate) metadata: Option<SaveSlotMetadata>,
+    pub(crate) thumbnail: Option<Handle<Image>>,
+    /// The [`SaveSlot::thumbnail`] as registered with egui.
+    pub(crate) thumbnail_id: Option<egui::TextureId>,
+    modified: Option<SystemTime>,
+}
+
+/// Stored next to the save so that the slots can be listed without reading every save.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct SaveSlotMetadata {
+    /// RFC 3339 in local time.
+    pub(crate) timestamp: String,
+    pub(crate) room_number: usize,
+    /// In seconds.
+    pub(crate) playtime: f32,
+}
+
+/// Time spent in [`GameState::Playing`] while not paused, in seconds.
+#[derive(Debug, Clone, Copy, PartialEq, Resource, Default)]
+pub(crate) struct Playtime(pub(crate) f32);
+
+impl SaveSlotMetadata {
+    pub(crate) fn new(room_number: usize, playtime: Playtime) -> Self {
+        Self {
+            timestamp: Local::now().to_rfc3339(),
+            room_number,
+            playtime: playtime.0,
+        }
+    }
+
+    fn format_timestamp(&self) -> String {
+        DateTime::parse_from_rfc3339(&self.timestamp)
+            .map(|timestamp| timestamp.format("%Y-%m-%d %H:%M").to_string())
+            .unwrap_or_else(|_| self.timestamp.clone())
+    }
+
+    fn format_playtime(&self) -> String {
+        let seconds = self.playtime.max(0.0) as u64;
+        format!(
+            "{}:{:02}:{:02}",
+            seconds / 3600,
+            seconds / 60 % 60,
+            seconds % 60
+        )
+    }
+}
+
+impl SaveSlots {
+    pub(crate) fn iter(&self) -> impl Iterator<Item = &SaveSlot> {
+        self.slots.iter()
+    }
+
+    pub(crate) fn get(&self, name: &str) -> Option<&SaveSlot> {
+        self.slots.iter().find(|slot| slot.name == name)
+    }
+
+    pub(crate) fn newest(&self) -> Option<&SaveSlot> {
+        self.slots.first()
+    }
+
+    pub(crate) fn mark_outdated(&mut self) {
+        self.is_up_to_date = false;
+    }
+
+    pub(crate) fn delete(&mut self, name: &str, storage: &dyn UserStorage) -> Result<()> {
+        self.mark_outdated();
+        if !storage.exists(&get_save_path(name)) {
+            bail!("Failed to delete save \"{name}\": No such save");
+        }
+        storage.remove_if_exists(&get_save_path(name))?;
+        storage.remove_if_exists(&get_backup_path(&get_save_path(name)))?;
+        storage.remove_if_exists(&get_metadata_path(name))?;
+        storage.remove_if_exists(&get_thumbnail_path(name))?;
+        info!("Successfully deleted save \"{name}\"");
+        Ok(())
+    }
+
+    pub(crate) fn rename(&mut self, from: &str, to: &str, storage: &dyn UserStorage) -> Result<()> {
+        validate_slot_name(to)?;
+        if storage.exists(&get_save_path(to)) {
+            bail!("Failed to rename save \"{from}\": A save named \"{to}\" already exists");
+        }
+        if !storage.exists(&get_save_path(from)) {
+            bail!("Failed to rename save \"{from}\": No such save");
+        }
+        self.mark_outdated();
+        storage.rename_if_exists(&get_save_path(from), &get_save_path(to))?;
+        storage.rename_if_exists(
+            &get_backup_path(&get_save_path(from)),
+            &get_backup_path(&get_save_path(to)),
+        )?;
+        storage.rename_if_exists(&get_metadata_path(from), &get_metadata_path(to))?;
+        storage.rename_if_exists(&get_thumbnail_path(from), &get_thumbnail_path(to))?;
+        info!("Successfully renamed save \"{from}\" to \"{to}\"");
+        Ok(())
+    }
+}
+
+pub(crate) fn validate_slot_name(name: &str) -> Result<()> {
+    if name.trim().is_empty() {
+        bail!("Save names must not be empty");
+    }
+    if name.contains(['/', '\\', ':']) || name.starts_with('.') {
+        bail!("Save names must not contain '/', '\\', ':' or start with '.'");
+    }
+    Ok(())
+}
+
+pub(crate) fn get_save_path(name: &str) -> PathBuf {
+    Path::new(SAVE_DIRECTORY).join(format!("{name}.sav.ron"))
+}
+
+pub(crate) fn get_metadata_path(name: &str) -> PathBuf {
+    Path::new(SAVE_DIRECTORY).join(format!("{name}.meta.ron"))
+}
+
+pub(crate) fn get_thumbnail_path(name: &str) -> PathBuf {
+    Path::new(SAVE_DIRECTORY).join(format!("{name}.png"))
+}
+
+#[sysfail(log(level = "error"))]
+fn refresh_save_slots(
+    mut save_slots: ResMut<SaveSlots>,
+    mut thumbnail_writes: EventReader<ThumbnailWritten>,
+    mut images: ResMut<Assets<Image>>,
+    mut egui_contexts: EguiContexts,
+    user_dirs: Res<UserDirs>,
+) -> Result<()> {
+    if !thumbnail_writes.is_empty() {
+        thumbnail_writes.clear();
+        save_slots.mark_outdated();
+    }
+    if save_slots.is_up_to_date {
+        return Ok(());
+    }
+    save_slots.is_up_to_date = true;
+    // Every refresh reads the thumbnails again, so the old ones are no longer shown
+    for thumbnail in save_slots
+        .slots
+        .iter()
+        .filter_map(|slot| slot.thumbnail.as_ref())
+    {
+        egui_contexts.remove_image(thumbnail);
+    }
+
+    let storage = user_dirs.data.as_ref();
+    let mut slots = Vec::new();
+    for filename in storage.list(Path::new(SAVE_DIRECTORY), ".sav.ron")? {
+        let Some(name) = filename.strip_suffix(".sav.ron") else {
+            continue;
+        };
+        let metadata_path = get_metadata_path(name);
+        let metadata = storage
+            .exists(&metadata_path)
+            .then(|| read_metadata(storage, &metadata_path))
+            .and_then(|metadata| {
+                metadata
+                    .map_err(|e| warn!("Failed to read metadata of save \"{name}\": {e:?}"))
+                    .ok()
+            });
+        let thumbnail_path = get_thumbnail_path(name);
+        let thumbnail = storage
+            .exists(&thumbnail_path)
+            .then(|| read_thumbnail(storage, &thumbnail_path))
+            .and_then(|thumbnail| {
+                thumbnail
+                    .map_err(|e| warn!("Failed to read thumbnail of save \"{name}\": {e:?}"))
+                    .ok()
+            })
+            .map(|thumbnail| images.add(thumbnail));
+        let thumbnail_id = thumbnail
+            .as_ref()
+            .map(|thumbnail| egui_contexts.add_image(thumbnail.clone_weak()));
+        // Not every storage tracks modification times, e.g. localStorage
+        let modified = storage.modified(&get_save_path(name)).or_else(|| {
+            metadata.as_ref().and_then(|metadata| {
+                DateTime::parse_from_rfc3339(&metadata.timestamp)
+                    .ok()
+                    .map(SystemTime::from)
+            })
+        });
+        slots.push(SaveSlot {
+            name: name.to_string(),
+            metadata,
+            thumbnail,
+            thumbnail_id,
+            modified,
+        });
+    }
+    slots.sort_by_key(|slot| Reverse(slot.modified));
+    save_slots.slots = slots;
+    Ok(())
+}
+
+fn read_metadata(storage: &dyn UserStorage, path: &Path) -> Result<SaveSlotMetadata> {
+    let serialized = storage
+        .read_to_string(path)
+        .context("Failed to read metadata file")?;
+    ron::from_str(&serialized).context("Failed to deserialize metadata")
+}
+
+fn read_thumbnail(storage: &dyn UserStorage, path: &Path) -> Result<Image> {
+    let bytes = storage
+        .read(path)
+        .context("Failed to read thumbnail file")?;
+    let image = image::load_from_memory_with_format(&bytes, image::ImageFormat::Png)
+        .context("Failed to decode thumbnail")?
+        .to_rgba8();
+    let size = Extent3d {
+        width: image.width(),
+        height: image.height(),
+        ..default()
+    };
+    Ok(Image::new(
+        size,
+        TextureDimension::D2,
+        image.into_raw(),
+        TextureFormat::Rgba8UnormSrgb,
+    ))
+}
+
+fn update_playtime(time: Res<Time>, mut playtime: ResMut<Playtime>) {
+    playtime.0 += time.delta_seconds();
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum SaveSlotAction {
+    Load(String),
+    Overwrite(String),
+    Delete(String),
+    Rename { from: String, to: String },
+}
+
+/// State of the save slot list shown in the menu and the pause screen.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub(crate) struct SaveSlotsUi {
+    /// The slot being renamed and its new name.
+    renaming: Option<(String, String)>,
+    error: Option<String>,
+}
+
+impl SaveSlotsUi {
+    pub(crate) fn show(
+        &mut self,
+        ui: &mut egui::Ui,
+        save_slots: &SaveSlots,
+        can_overwrite: bool,
+        localization: &Localization,
+    ) -> Option<SaveSlotAction> {
+        let mut action = None;
+        if let Some(error) = &self.error {
+            ui.colored_label(egui::Color32::LIGHT_RED, error);
+        }
+        if save_slots.slots.is_empty() {
+            ui.label(localization.get("save-slots.empty"));
+        }
+        egui::ScrollArea::vertical()
+            .max_height(400.0)
+            .show(ui, |ui| {
+                for slot in save_slots.iter() {
+                    ui.horizontal(|ui| {
+                        let thumbnail_size = egui::vec2(128.0, 72.0);
+                        match slot.thumbnail_id {
+                            Some(thumbnail_id) => {
+                                ui.image(thumbnail_id, thumbnail_size);
+                            }
+                            None => {
+                                ui.allocate_space(thumbnail_size);
+                            }
+                        }
+                        ui.vertical(|ui| {
+                            ui.strong(&slot.name);
+                            match &slot.metadata {
+                                Some(metadata) => {
+                                    ui.label(localization.format(
+                                        "save-slots.details",
+                                        &[
+                                            ("timestamp", &metadata.format_timestamp()),
+                                            ("room-number", &metadata.room_number),
+                                            ("playtime", &metadata.format_playtime()),
+                                        ],
+                                    ));
+                                }
+                                None => {
+                                    ui.label(localization.get("save-slots.no-details"));
+                                }
+                            }
+                            ui.horizontal(|ui| {
+                                if let Some((renamed, new_name)) = &mut self.renaming && *renamed == slot.name {
+                                    ui.text_edit_singleline(new_name);
+                                    if ui.button(localization.get("save-slots.confirm")).clicked() {
+                                        action = Some(SaveSlotAction::Rename {
+                                            from: slot.name.clone(),
+                                            to: new_name.clone(),
+                                        });
+                                    }
+                                    if ui.button(localization.get("save-slots.cancel")).clicked() {
+                                        self.renaming = None;
+                                    }
+                                    return;
+                                }
+                                if ui.button(localization.get("save-slots.load")).clicked() {
+                                    action = Some(SaveSlotAction::Load(slot.name.clone()));
+                                }
+                                if can_overwrite && ui.button(localization.get("save-slots.overwrite")).clicked() {
+                                    action = Some(SaveSlotAction::Overwrite(slot.name.clone()));
+                                }
+                                if ui.button(localization.get("save-slots.rename")).clicked() {
+                                    self.renaming = Some((slot.name.clone(), slot.name.clone()));
+                                }
+                                if ui.button(localization.get("save-slots.delete")).clicked() {
+                                    action = Some(SaveSlotAction::Delete(slot.name.clone()));
+                                }
+                            });
+                        });
+                    });
+                    ui.separator();
+                }
+            });
+        action
+    }
+
+    /// Deletes or renames the slot, reporting errors in the UI.
+    /// Loading and overwriting depend on the caller, so they are returned unchanged.
+    pub(crate) fn apply(
+        &mut self,
+        action: SaveSlotAction,
+        save_slots: &mut SaveSlots,
+        user_dirs: &UserDirs,
+    ) -> Option<SaveSlotAction> {
+        let storage = user_dirs.data.as_ref();
+        let result = match &action {
+            SaveSlotAction::Delete(name) => save_slots.delete(name, storage),
+            SaveSlotAction::Rename { from, to } => save_slots.rename(from, to, storage),
+            SaveSlotAction::Load(_) | SaveSlotAction::Overwrite(_) => {
+                self.error = None;
+                return Some(action);
+            }
+        };
+        match result {
+            Ok(()) => {
+                self.error = None;
+                self.renaming = None;
+            }
+            Err(e) => {
+                error!("{e:?}");
+                self.error = Some(format!("{e:#}"));
+            }
+        }
+        None
+    }
+}
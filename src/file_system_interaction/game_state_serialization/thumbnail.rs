@@ -0,0 +1,342 @@
+use crate::file_system_interaction::user_dirs::{UserDirs, UserStorage};
+use crate::player_control::camera::IngameCamera;
+use anyhow::{Context, Result};
+use bevy::prelude::*;
+use bevy::render::camera::RenderTarget;
+use bevy::render::render_asset::RenderAssets;
+use bevy::render::render_resource::{
+    Buffer, BufferDescriptor, BufferUsages, CommandEncoderDescriptor, Extent3d, ImageCopyBuffer,
+    ImageDataLayout, MapMode, TextureDescriptor, TextureDimension, TextureFormat, TextureUsages,
+};
+use bevy::render::renderer::{render_system, RenderDevice, RenderQueue};
+use bevy::render::{Extract, ExtractSchedule, RenderApp, RenderSet};
+use bevy::utils::HashSet;
+use bevy_mod_sysfail::macros::*;
+use std::io::Cursor;
+use std::num::NonZeroU32;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// Renders the view of the [`IngameCamera`] into a PNG for the save slot menus.
+/// A temporary camera renders into an image for a few frames, after which the render world copies the image
+/// back to the CPU and hands it to the main world through [`CapturedThumbnails`].
+pub(crate) fn thumbnail_plugin(app: &mut App) {
+    let captured_thumbnails = CapturedThumbnails::default();
+    app.add_event::<ThumbnailRequest>()
+        .add_event::<ThumbnailWritten>()
+        .insert_resource(captured_thumbnails.clone())
+        .add_systems((spawn_thumbnail_cameras, save_thumbnails).chain());
+
+    if let Ok(render_app) = app.get_sub_app_mut(RenderApp) {
+        render_app
+            .insert_resource(captured_thumbnails)
+            .init_resource::<ThumbnailReadbacks>()
+            .add_system(extract_thumbnail_captures.in_schedule(ExtractSchedule))
+            .add_system(
+                copy_thumbnails_to_buffers
+                    .in_set(RenderSet::Render)
+                    .after(render_system),
+            )
+            .add_system(read_thumbnail_buffers.in_set(RenderSet::Cleanup));
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ThumbnailRequest {
+    pub(crate) path: PathBuf,
+}
+
+/// Sent once a requested thumbnail has been written to [`UserDirs::data`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct ThumbnailWritten;
+
+const THUMBNAIL_WIDTH: u32 = 256;
+const THUMBNAIL_HEIGHT: u32 = 144;
+/// Frames the camera renders before its image is copied, so that the image is guaranteed to exist on the GPU.
+const FRAMES_BEFORE_CAPTURE: u32 = 3;
+const FRAMES_BEFORE_TIMEOUT: u32 = 60;
+
+#[derive(Debug, Clone, Component)]
+struct ThumbnailCapture {
+    path: PathBuf,
+    image: Handle<Image>,
+    frames: u32,
+}
+
+#[derive(Debug, Clone)]
+struct CapturedThumbnail {
+    entity: Entity,
+    width: u32,
+    height: u32,
+    /// BGRA, as rendered.
+    data: Vec<u8>,
+}
+
+/// Shared between the main world and the render world.
+#[derive(Debug, Clone, Resource, Default)]
+struct CapturedThumbnails(Arc<Mutex<Vec<CapturedThumbnail>>>);
+
+fn spawn_thumbnail_cameras(
+    mut commands: Commands,
+    mut thumbnail_requests: EventReader<ThumbnailRequest>,
+    mut images: ResMut<Assets<Image>>,
+    cameras: Query<(&GlobalTransform, &Projection), With<IngameCamera>>,
+) {
+    for request in thumbnail_requests.iter() {
+        let Ok((camera_transform, projection)) = cameras.get_single() else {
+            warn!("Failed to render thumbnail {:?}: No camera found", request.path);
+            continue;
+        };
+        let size = Extent3d {
+            width: THUMBNAIL_WIDTH,
+            height: THUMBNAIL_HEIGHT,
+            ..default()
+        };
+        let mut image = Image {
+            texture_descriptor: TextureDescriptor {
+                label: None,
+                size,
+                dimension: TextureDimension::D2,
+                format: TextureFormat::Bgra8UnormSrgb,
+                mip_level_count: 1,
+                sample_count: 1,
+                usage: TextureUsages::TEXTURE_BINDING
+                    | TextureUsages::COPY_DST
+                    | TextureUsages::COPY_SRC
+                    | TextureUsages::RENDER_ATTACHMENT,
+                view_formats: &[],
+            },
+            ..default()
+        };
+        image.resize(size);
+        let image = images.add(image);
+
+        commands.spawn((
+            Camera3dBundle {
+                camera: Camera {
+                    target: RenderTarget::Image(image.clone()),
+                    order: -1,
+                    ..default()
+                },
+                transform: camera_transform.compute_transform(),
+                projection: projection.clone(),
+                ..default()
+            },
+            ThumbnailCapture {
+                path: request.path.clone(),
+                image,
+                frames: 0,
+            },
+            Name::new("Thumbnail Camera"),
+        ));
+    }
+}
+
+#[sysfail(log(level = "error"))]
+fn save_thumbnails(
+    mut commands: Commands,
+    captured_thumbnails: Res<CapturedThumbnails>,
+    mut captures: Query<(Entity, &mut ThumbnailCapture)>,
+    mut images: ResMut<Assets<Image>>,
+    mut thumbnail_writes: EventWriter<ThumbnailWritten>,
+    user_dirs: Res<UserDirs>,
+) -> Result<()> {
+    let captured_thumbnails: Vec<_> = captured_thumbnails
+        .0
+        .lock()
+        .map_err(|e| anyhow::anyhow!("Failed to lock captured thumbnails: {e}"))?
+        .drain(..)
+        .collect();
+    let mut finished = HashSet::new();
+    for thumbnail in captured_thumbnails {
+        let Ok((entity, capture)) = captures.get(thumbnail.entity) else {
+            continue;
+        };
+        if let Err(e) = write_thumbnail(user_dirs.data.as_ref(), &capture.path, thumbnail) {
+            error!("Failed to save thumbnail {:?}: {e:?}", capture.path);
+        } else {
+            info!("Successfully saved thumbnail at {:?}", capture.path);
+            thumbnail_writes.send(ThumbnailWritten);
+        }
+        finished.insert(entity);
+    }
+    for (entity, mut capture) in captures.iter_mut() {
+        capture.frames += 1;
+        if capture.frames > FRAMES_BEFORE_TIMEOUT {
+            error!(
+                "Failed to save thumbnail {:?}: Timed out while waiting for the GPU",
+                capture.path
+            );
+            finished.insert(entity);
+        }
+        if finished.contains(&entity) {
+            images.remove(&capture.image);
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+    Ok(())
+}
+
+fn write_thumbnail(
+    storage: &dyn UserStorage,
+    path: &Path,
+    thumbnail: CapturedThumbnail,
+) -> Result<()> {
+    let mut data = thumbnail.data;
+    for pixel in data.chunks_exact_mut(4) {
+        pixel.swap(0, 2);
+    }
+    let image = image::RgbaImage::from_raw(thumbnail.width, thumbnail.height, data)
+        .context("Captured thumbnail does not match its size")?;
+    let mut png = Vec::new();
+    image::DynamicImage::ImageRgba8(image)
+        .write_to(&mut Cursor::new(&mut png), image::ImageOutputFormat::Png)
+        .context("Failed to encode PNG")?;
+    storage.write_atomically(path, &png, false)
+}
+
+#[derive(Debug, Clone, Resource, Default)]
+struct ExtractedThumbnailCaptures(Vec<(Entity, Handle<Image>)>);
+
+fn extract_thumbnail_captures(
+    mut commands: Commands,
+    captures: Extract<Query<(Entity, &ThumbnailCapture)>>,
+) {
+    let captures = captures
+        .iter()
+        .filter(|(_, capture)| capture.frames >= FRAMES_BEFORE_CAPTURE)
+        .map(|(entity, capture)| (entity, capture.image.clone_weak()))
+        .collect();
+    commands.insert_resource(ExtractedThumbnailCaptures(captures));
+}
+
+#[derive(Debug, Resource, Default)]
+struct ThumbnailReadbacks {
+    pending: Vec<ThumbnailReadback>,
+    /// Captures that were already copied, but might still be extracted until the main world despawns them.
+    copied: HashSet<Entity>,
+}
+
+#[derive(Debug)]
+struct ThumbnailReadback {
+    entity: Entity,
+    buffer: Buffer,
+    is_mapped: Arc<AtomicBool>,
+    width: u32,
+    height: u32,
+    padded_bytes_per_row: usize,
+}
+
+fn copy_thumbnails_to_buffers(
+    captures: Option<Res<ExtractedThumbnailCaptures>>,
+    mut readbacks: ResMut<ThumbnailReadbacks>,
+    gpu_images: Res<RenderAssets<Image>>,
+    render_device: Res<RenderDevice>,
+    render_queue: Res<RenderQueue>,
+) {
+    let Some(captures) = captures else {
+        return;
+    };
+    let extracted: HashSet<_> = captures.0.iter().map(|(entity, _)| *entity).collect();
+    readbacks.copied.retain(|entity| extracted.contains(entity));
+
+    for (entity, image) in captures.0.iter() {
+        if readbacks.copied.contains(entity) {
+            continue;
+        }
+        let Some(gpu_image) = gpu_images.get(image) else {
+            continue;
+        };
+        let width = gpu_image.size.x as u32;
+        let height = gpu_image.size.y as u32;
+        let padded_bytes_per_row = RenderDevice::align_copy_bytes_per_row(width as usize * 4);
+        let buffer = render_device.create_buffer(&BufferDescriptor {
+            label: Some("thumbnail_readback_buffer"),
+            size: (padded_bytes_per_row * height as usize) as u64,
+            usage: BufferUsages::MAP_READ | BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let mut encoder = render_device.create_command_encoder(&CommandEncoderDescriptor {
+            label: Some("thumbnail_readback_encoder"),
+        });
+        encoder.copy_texture_to_buffer(
+            gpu_image.texture.as_image_copy(),
+            ImageCopyBuffer {
+                buffer: &buffer,
+                layout: ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: NonZeroU32::new(padded_bytes_per_row as u32),
+                    rows_per_image: None,
+                },
+            },
+            Extent3d {
+                width,
+                height,
+                ..default()
+            },
+        );
+        render_queue.submit([encoder.finish()]);
+
+        // The callback fires during a later submission, after which the buffer is read in `read_thumbnail_buffers`
+        let is_mapped = Arc::new(AtomicBool::new(false));
+        let is_mapped_callback = is_mapped.clone();
+        buffer
+            .slice(..)
+            .map_async(MapMode::Read, move |result| match result {
+                Ok(()) => is_mapped_callback.store(true, Ordering::Release),
+                Err(e) => error!("Failed to map thumbnail buffer: {e}"),
+            });
+        readbacks.copied.insert(*entity);
+        readbacks.pending.push(ThumbnailReadback {
+            entity: *entity,
+            buffer,
+            is_mapped,
+            width,
+            height,
+            padded_bytes_per_row,
+        });
+    }
+}
+
+#[sysfail(log(level = "error"))]
+fn read_thumbnail_buffers(
+    mut readbacks: ResMut<ThumbnailReadbacks>,
+    captured_thumbnails: Res<CapturedThumbnails>,
+) -> Result<()> {
+    let (mapped, pending): (Vec<_>, Vec<_>) = readbacks
+        .pending
+        .drain(..)
+        .partition(|readback| readback.is_mapped.load(Ordering::Acquire));
+    readbacks.pending = pending;
+    let mut captured_thumbnails = captured_thumbnails
+        .0
+        .lock()
+        .map_err(|e| anyhow::anyhow!("Failed to lock captured thumbnails: {e}"))?;
+    for readback in mapped {
+        let ThumbnailReadback {
+            entity,
+            buffer,
+            width,
+            height,
+            padded_bytes_per_row,
+            ..
+        } = readback;
+        let unpadded_bytes_per_row = width as usize * 4;
+        let data = buffer
+            .slice(..)
+            .get_mapped_range()
+            .chunks(padded_bytes_per_row)
+            .flat_map(|row| &row[..unpadded_bytes_per_row])
+            .copied()
+            .collect();
+        buffer.unmap();
+        captured_thumbnails.push(CapturedThumbnail {
+            entity,
+            width,
+            height,
+            data,
+        });
+    }
+    Ok(())
+}
@@ -1,25 +1,36 @@
 use crate::file_system_interaction::asset_loading::LevelAssets;
-use crate::level_instantiation::spawning::GameObject;
-use crate::world_interaction::condition::ActiveConditions;
+use crate::file_system_interaction::level_serialization::payload::{
+    apply_level_components, get_level_components, PendingLevelComponent, SerializedLevelSerializer,
+};
+use crate::level_instantiation::spawning::{GameObject, RoomSpawner};
+use crate::world_interaction::condition::{ActiveConditions, Counters};
 use crate::world_interaction::dialog::CurrentDialog;
-use crate::world_interaction::room::CurrentRoom;
+use crate::world_interaction::room::{
+    CurrentEncounter, CurrentRoom, RoomId, RoomTransition, RunLayout, TriggerState,
+};
 use crate::world_interaction::side_effects::SideEffects;
 use anyhow::{Context, Result};
 use bevy::prelude::*;
 use bevy::reflect::TypeUuid;
 use bevy_mod_sysfail::macros::*;
+pub(crate) use payload::{LevelLoader, PendingLevelComponents, ReflectLevelComponent};
 use serde::{Deserialize, Serialize};
 use spew::prelude::*;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::{fs, iter};
 
+mod payload;
+
 pub(crate) fn level_serialization_plugin(app: &mut App) {
     app.add_event::<WorldSaveRequest>()
         .add_event::<WorldLoadRequest>()
+        .init_resource::<PendingLevelComponents>()
+        .register_type_data::<PointLight, ReflectLevelComponent>()
         .add_systems(
             (
                 save_world,
                 load_world.run_if(resource_exists::<LevelAssets>()),
+                apply_level_components,
             )
                 .in_base_set(CoreSet::PostUpdate),
         );
@@ -31,10 +42,14 @@ pub(crate) struct WorldSaveRequest {
     pub(crate) filename: String,
 }
 
-#[derive(Debug, Clone, Eq, PartialEq, Reflect, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, PartialEq, Reflect, Serialize, Deserialize, Default)]
 #[reflect(Serialize, Deserialize)]
 pub(crate) struct WorldLoadRequest {
     pub(crate) filename: String,
+    /// Spawned instead of the room the level contains, if any.
+    pub(crate) room: Option<GameObject>,
+    /// Where the room is spawned instead of where the level places it, since rooms are attached to each other while playing.
+    pub(crate) room_transform: Option<Transform>,
 }
 
 #[derive(Debug, Clone, PartialEq, Resource, Reflect, Serialize, Deserialize, Default)]
@@ -46,18 +61,16 @@ pub(crate) struct CurrentLevel {
 #[sysfail(log(level = "error"))]
 fn save_world(
     mut save_requests: EventReader<WorldSaveRequest>,
-    spawn_query: Query<(&GameObject, Option<&Transform>)>,
+    spawn_query: Query<(Entity, &GameObject, Option<&Transform>)>,
+    type_registry: Res<AppTypeRegistry>,
+    world: &World,
 ) -> Result<()> {
     for save in save_requests.iter() {
         let scene = save.filename.clone();
+        let levels_directory = get_levels_directory()?;
         let valid_candidates: Vec<_> = iter::once(scene.clone())
             .chain((1..).map(|n| format!("{0}-{n}", scene.clone())))
-            .map(|filename| {
-                Path::new("assets")
-                    .join("levels")
-                    .join(filename)
-                    .with_extension("lvl.ron")
-            })
+            .map(|filename| levels_directory.join(filename).with_extension("lvl.ron"))
             .map(|path| (path.clone(), fs::try_exists(path).ok()))
             .take(10)
             .filter_map(|(path, maybe_exists)| maybe_exists.map(|exists| (path, exists)))
@@ -69,7 +82,7 @@ fn save_world(
             .filter_map(|(path, exists)| (!exists).then_some(path))
             .next()
         {
-            let serialized_world = serialize_world(&spawn_query)?;
+            let serialized_world = serialize_world(&spawn_query, &type_registry, world)?;
             let dir = path.parent().context("Failed to get level directory")?;
             fs::create_dir_all(dir).context("Failed to create level directory")?;
             fs::write(path, serialized_world)
@@ -89,6 +102,19 @@ fn save_world(
     Ok(())
 }
 
+/// Levels are assets, so they are saved into the directory the asset server reads from instead of the working directory.
+#[cfg(not(feature = "wasm"))]
+fn get_levels_directory() -> Result<PathBuf> {
+    Ok(bevy::asset::FileAssetIo::get_base_path()
+        .join("assets")
+        .join("levels"))
+}
+
+#[cfg(feature = "wasm")]
+fn get_levels_directory() -> Result<PathBuf> {
+    anyhow::bail!("Failed to save level: Levels can only be saved on native")
+}
+
 #[derive(Debug, Component, Clone, PartialEq, Default, Reflect, Serialize, Deserialize)]
 #[reflect(Component, Serialize, Deserialize)]
 pub(crate) struct Protected;
@@ -99,6 +125,8 @@ fn load_world(
     mut load_requests: EventReader<WorldLoadRequest>,
     current_spawn_query: Query<Entity, With<GameObject>>,
     mut spawn_requests: EventWriter<SpawnEvent<GameObject, Transform>>,
+    mut room_spawn_requests: EventWriter<SpawnEvent<RoomSpawner, (RoomId, Transform)>>,
+    mut pending_level_components: ResMut<PendingLevelComponents>,
     levels: Res<Assets<SerializedLevel>>,
     level_handles: Res<LevelAssets>,
 ) -> Result<()> {
@@ -125,25 +153,55 @@ fn load_world(
                 continue;
             }
         };
-        let spawn_events = &levels
+        let level = levels
             .get(handle)
             .context("Failed to get level from handle in level assets")?;
-        let spawn_events = Vec::<SpawnEvent<GameObject, Transform>>::from(*spawn_events);
         for entity in &current_spawn_query {
             commands
                 .get_entity(entity)
                 .context("Failed to get entity while loading")?
                 .despawn_recursive();
         }
-        for event in spawn_events.into_iter() {
-            spawn_requests.send(event);
+        pending_level_components.0.clear();
+        for object in level.iter() {
+            let (game_object, transform) = if object.game_object.is_room() {
+                (
+                    load.room.unwrap_or(object.game_object),
+                    load.room_transform.unwrap_or(object.transform),
+                )
+            } else {
+                (object.game_object, object.transform)
+            };
+            if let GameObject::Room(room) = game_object {
+                room_spawn_requests
+                    .send(SpawnEvent::with_data(RoomSpawner::Room, (room, transform)));
+            } else {
+                spawn_requests.send(SpawnEvent::with_data(game_object, transform));
+            }
+            if !object.components.is_empty() {
+                pending_level_components.0.push(PendingLevelComponent {
+                    game_object,
+                    translation: transform.translation,
+                    components: object
+                        .components
+                        .iter()
+                        .map(|component| component.clone_value())
+                        .collect(),
+                });
+            }
         }
         commands.insert_resource(CurrentLevel {
             scene: load.filename.clone(),
         });
         commands.insert_resource(ActiveConditions::default());
+        commands.insert_resource(Counters::default());
         commands.insert_resource(SideEffects::default());
         commands.insert_resource(CurrentRoom::default());
+        commands.insert_resource(TriggerState::default());
+        commands.remove_resource::<RoomTransition>();
+        commands.remove_resource::<CurrentEncounter>();
+        // Loading a level starts a new run, unless a saved one is restored afterwards
+        commands.remove_resource::<RunLayout>();
         commands.remove_resource::<CurrentDialog>();
 
         info!("Successfully loaded scene \"{}\"", load.filename,)
@@ -151,42 +209,40 @@ fn load_world(
     Ok(())
 }
 
-fn serialize_world(spawn_query: &Query<(&GameObject, Option<&Transform>)>) -> Result<String> {
-    let objects: Vec<_> = spawn_query
-        .iter()
-        .filter(|(game_object, _)| **game_object != GameObject::Player)
-        .map(|(game_object, transform)| {
-            SpawnEvent::with_data(
-                *game_object,
-                transform.map(Clone::clone).unwrap_or_default(),
-            )
-        })
-        .collect();
-    let serialized_level = SerializedLevel::from(objects);
-    ron::ser::to_string_pretty(&serialized_level, default()).context("Failed to serialize world")
+fn serialize_world(
+    spawn_query: &Query<(Entity, &GameObject, Option<&Transform>)>,
+    type_registry: &AppTypeRegistry,
+    world: &World,
+) -> Result<String> {
+    let objects = {
+        let type_registry = type_registry.read();
+        spawn_query
+            .iter()
+            .filter(|(_, game_object, _)| **game_object != GameObject::Player)
+            .map(|(entity, game_object, transform)| LevelObject {
+                game_object: *game_object,
+                transform: transform.map(Clone::clone).unwrap_or_default(),
+                components: get_level_components(world.entity(entity), &type_registry),
+            })
+            .collect()
+    };
+    let serialized_level = SerializedLevel(objects);
+    let serializer = SerializedLevelSerializer {
+        level: &serialized_level,
+        type_registry: &type_registry.0,
+    };
+    ron::ser::to_string_pretty(&serializer, default()).context("Failed to serialize world")
 }
 
-#[derive(Debug, Clone, PartialEq, Reflect, Serialize, Deserialize, TypeUuid, Deref, DerefMut)]
+/// Read by [`LevelLoader`], since the components of the objects can only be deserialized through reflection.
+#[derive(Debug, TypeUuid, Deref, DerefMut)]
 #[uuid = "eb7cc7bc-5a97-41ed-b0c3-0d4e2137b73b"]
-#[reflect(Serialize, Deserialize)]
-pub(crate) struct SerializedLevel(pub(crate) Vec<(GameObject, Transform)>);
-
-impl From<Vec<SpawnEvent<GameObject, Transform>>> for SerializedLevel {
-    fn from(events: Vec<SpawnEvent<GameObject, Transform>>) -> Self {
-        Self(
-            events
-                .into_iter()
-                .map(|event| (event.object, event.data))
-                .collect(),
-        )
-    }
-}
+pub(crate) struct SerializedLevel(pub(crate) Vec<LevelObject>);
 
-impl From<&SerializedLevel> for Vec<SpawnEvent<GameObject, Transform>> {
-    fn from(level: &SerializedLevel) -> Self {
-        level
-            .iter()
-            .map(|(object, transform)| SpawnEvent::with_data(*object, *transform))
-            .collect()
-    }
+#[derive(Debug)]
+pub(crate) struct LevelObject {
+    pub(crate) game_object: GameObject,
+    pub(crate) transform: Transform,
+    /// Components registered with [`ReflectLevelComponent`], applied after the object was spawned.
+    pub(crate) components: Vec<Box<dyn Reflect>>,
 }
@@ -0,0 +1,308 @@
+use crate::file_system_interaction::level_serialization::{LevelObject, SerializedLevel};
+use crate::level_instantiation::spawning::GameObject;
+use anyhow::anyhow;
+use bevy::asset::{AssetLoader, LoadContext, LoadedAsset};
+use bevy::ecs::world::EntityRef;
+use bevy::prelude::*;
+use bevy::reflect::{FromType, TypeRegistry, TypeRegistryArc};
+use bevy::scene::serde::{ComponentDeserializer, ComponentsSerializer};
+use bevy::utils::BoxedFuture;
+use serde::de::{self, DeserializeSeed, SeqAccess, Visitor};
+use serde::ser::SerializeTuple;
+use serde::{Deserializer, Serialize, Serializer};
+use std::fmt;
+
+/// Marks components that are saved with the object they are on and applied again after it is spawned from the level.
+/// Use `#[reflect(LevelComponent)]` for our own components and [`App::register_type_data`] for foreign ones.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ReflectLevelComponent;
+
+impl<T: Component> FromType<T> for ReflectLevelComponent {
+    fn from_type() -> Self {
+        Self
+    }
+}
+
+/// Maximum distance between the saved and the spawned translation of an object for its components to be applied.
+const MAX_SPAWN_DISTANCE_SQUARED: f32 = 0.01 * 0.01;
+
+/// Components of objects that were requested to spawn, but have not spawned yet.
+#[derive(Debug, Resource, Default)]
+pub(crate) struct PendingLevelComponents(pub(super) Vec<PendingLevelComponent>);
+
+impl PendingLevelComponents {
+    /// Applies `components` to the next `game_object` that spawns at `translation`.
+    pub(crate) fn push(
+        &mut self,
+        game_object: GameObject,
+        translation: Vec3,
+        components: Vec<Box<dyn Reflect>>,
+    ) {
+        self.0.push(PendingLevelComponent {
+            game_object,
+            translation,
+            components,
+        });
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct PendingLevelComponent {
+    pub(super) game_object: GameObject,
+    pub(super) translation: Vec3,
+    pub(super) components: Vec<Box<dyn Reflect>>,
+}
+
+pub(super) fn get_level_components(
+    entity: EntityRef,
+    type_registry: &TypeRegistry,
+) -> Vec<Box<dyn Reflect>> {
+    let mut components: Vec<_> = type_registry
+        .iter()
+        .filter(|registration| registration.data::<ReflectLevelComponent>().is_some())
+        .filter_map(|registration| registration.data::<ReflectComponent>())
+        .filter_map(|reflect_component| reflect_component.reflect(entity))
+        .map(|component| component.clone_value())
+        .collect();
+    // The registry is unordered, so this keeps saving the same level from producing a different file
+    components.sort_by(|a, b| a.type_name().cmp(b.type_name()));
+    components
+}
+
+/// Spawners only receive a [`Transform`], so the components are applied once an object of the same kind
+/// shows up at the saved position.
+pub(super) fn apply_level_components(
+    mut commands: Commands,
+    mut pending: ResMut<PendingLevelComponents>,
+    spawned: Query<(Entity, &GameObject, Option<&Transform>), Added<GameObject>>,
+    type_registry: Res<AppTypeRegistry>,
+) {
+    if pending.0.is_empty() {
+        return;
+    }
+    for (entity, game_object, transform) in spawned.iter() {
+        let translation = transform
+            .map(|transform| transform.translation)
+            .unwrap_or_default();
+        let closest_pending = pending
+            .0
+            .iter()
+            .enumerate()
+            .filter(|(_, pending)| pending.game_object == *game_object)
+            .map(|(index, pending)| (index, pending.translation.distance_squared(translation)))
+            .filter(|(_, distance)| *distance <= MAX_SPAWN_DISTANCE_SQUARED)
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(index, _)| index);
+        let Some(index) = closest_pending else {
+            continue;
+        };
+        let components = pending.0.swap_remove(index).components;
+        let type_registry = type_registry.clone();
+        commands.add(move |world: &mut World| {
+            let type_registry = type_registry.read();
+            let Some(mut entity) = world.get_entity_mut(entity) else {
+                return;
+            };
+            for component in components {
+                match type_registry
+                    .get_with_name(component.type_name())
+                    .and_then(|registration| registration.data::<ReflectComponent>())
+                {
+                    Some(reflect_component) => {
+                        reflect_component.apply_or_insert(&mut entity, &*component)
+                    }
+                    None => error!(
+                        "Failed to apply level component {}: Not registered as a component",
+                        component.type_name()
+                    ),
+                }
+            }
+        });
+    }
+}
+
+/// Loads `*.lvl.ron` files.
+/// Unlike the other RON assets, levels need the type registry to read the reflected components of their objects.
+#[derive(Debug)]
+pub(crate) struct LevelLoader {
+    type_registry: TypeRegistryArc,
+}
+
+impl FromWorld for LevelLoader {
+    fn from_world(world: &mut World) -> Self {
+        Self {
+            type_registry: world.resource::<AppTypeRegistry>().0.clone(),
+        }
+    }
+}
+
+impl AssetLoader for LevelLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+        Box::pin(async move {
+            let mut deserializer = ron::de::Deserializer::from_bytes(bytes)?;
+            let level = SerializedLevelDeserializer {
+                type_registry: &self.type_registry.read(),
+            }
+            .deserialize(&mut deserializer)
+            .map_err(|e| {
+                let span_error = deserializer.span_error(e);
+                anyhow!(
+                    "{} at {}:{}",
+                    span_error.code,
+                    load_context.path().to_string_lossy(),
+                    span_error.position
+                )
+            })?;
+            load_context.set_default_asset(LoadedAsset::new(level));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["lvl.ron"]
+    }
+}
+
+/// Writes every object as `(game_object, transform)`, followed by a map of its components if it has any.
+pub(super) struct SerializedLevelSerializer<'a> {
+    pub(super) level: &'a SerializedLevel,
+    pub(super) type_registry: &'a TypeRegistryArc,
+}
+
+impl Serialize for SerializedLevelSerializer<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let objects = LevelObjectsSerializer {
+            objects: &self.level.0,
+            type_registry: self.type_registry,
+        };
+        serializer.serialize_newtype_struct("SerializedLevel", &objects)
+    }
+}
+
+struct LevelObjectsSerializer<'a> {
+    objects: &'a [LevelObject],
+    type_registry: &'a TypeRegistryArc,
+}
+
+impl Serialize for LevelObjectsSerializer<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.objects.iter().map(|object| LevelObjectSerializer {
+            object,
+            type_registry: self.type_registry,
+        }))
+    }
+}
+
+struct LevelObjectSerializer<'a> {
+    object: &'a LevelObject,
+    type_registry: &'a TypeRegistryArc,
+}
+
+impl Serialize for LevelObjectSerializer<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let has_components = !self.object.components.is_empty();
+        let mut tuple = serializer.serialize_tuple(if has_components { 3 } else { 2 })?;
+        tuple.serialize_element(&self.object.game_object)?;
+        tuple.serialize_element(&self.object.transform)?;
+        if has_components {
+            tuple.serialize_element(&ComponentsSerializer {
+                components: &self.object.components,
+                registry: self.type_registry,
+            })?;
+        }
+        tuple.end()
+    }
+}
+
+struct SerializedLevelDeserializer<'a> {
+    type_registry: &'a TypeRegistry,
+}
+
+impl<'de> DeserializeSeed<'de> for SerializedLevelDeserializer<'_> {
+    type Value = SerializedLevel;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_newtype_struct(
+            "SerializedLevel",
+            SerializedLevelVisitor {
+                type_registry: self.type_registry,
+            },
+        )
+    }
+}
+
+#[derive(Clone, Copy)]
+struct SerializedLevelVisitor<'a> {
+    type_registry: &'a TypeRegistry,
+}
+
+impl<'de> Visitor<'de> for SerializedLevelVisitor<'_> {
+    type Value = SerializedLevel;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a list of level objects")
+    }
+
+    fn visit_newtype_struct<D: Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_seq(self)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut objects = Vec::new();
+        while let Some(object) = seq.next_element_seed(LevelObjectDeserializer {
+            type_registry: self.type_registry,
+        })? {
+            objects.push(object);
+        }
+        Ok(SerializedLevel(objects))
+    }
+}
+
+#[derive(Clone, Copy)]
+struct LevelObjectDeserializer<'a> {
+    type_registry: &'a TypeRegistry,
+}
+
+impl<'de> DeserializeSeed<'de> for LevelObjectDeserializer<'_> {
+    type Value = LevelObject;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_tuple(3, self)
+    }
+}
+
+impl<'de> Visitor<'de> for LevelObjectDeserializer<'_> {
+    type Value = LevelObject;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter
+            .write_str("a level object of a game object, a transform and optionally its components")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let game_object = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+        let transform = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(1, &self))?;
+        // Levels saved before objects had components only contain the first two elements
+        let components = seq
+            .next_element_seed(ComponentDeserializer {
+                registry: self.type_registry,
+            })?
+            .unwrap_or_default();
+        Ok(LevelObject {
+            game_object,
+            transform,
+            components,
+        })
+    }
+}
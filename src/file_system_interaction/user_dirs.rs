@@ -0,0 +1,471 @@
+use anyhow::{Context, Result};
+use bevy::prelude::*;
+use std::fmt::Debug;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::SystemTime;
+
+/// Resolves the [`UserDirs`] once at startup.
+pub(crate) fn user_dirs_plugin(app: &mut App) {
+    let user_dirs = UserDirs::resolve();
+    info!(
+        "Using {} for data and {} for config",
+        user_dirs.data.describe(Path::new("")),
+        user_dirs.config.describe(Path::new(""))
+    );
+    app.insert_resource(user_dirs);
+}
+
+const APP_NAME: &str = "egg_slash";
+
+/// Per-user storage for data like saves and for configuration, independent of the working directory.
+///
+/// On native, the directories are resolved in this order:
+/// - The `--data-dir <path>` and `--config-dir <path>` command line flags
+/// - The `EGG_SLASH_DATA_DIR` and `EGG_SLASH_CONFIG_DIR` environment variables
+/// - The platform's conventions, i.e. the XDG base directories on Linux
+/// - The working directory
+///
+/// Saves from before this existed, i.e. in `./saves`, are moved into the data directory on startup.
+/// A `config.game.toml` in the config directory replaces the bundled one.
+///
+/// On wasm, both are stored in the browser's `localStorage`.
+#[derive(Debug, Clone, Resource)]
+pub(crate) struct UserDirs {
+    pub(crate) data: Arc<dyn UserStorage>,
+    pub(crate) config: Arc<dyn UserStorage>,
+}
+
+/// Files addressed by paths relative to the storage's root.
+pub(crate) trait UserStorage: Debug + Send + Sync {
+    fn read(&self, path: &Path) -> Result<Vec<u8>>;
+    /// Either writes all of `contents` or leaves the previous contents untouched, even if the game crashes.
+    /// With `keep_backup`, the previous contents are kept at [`get_backup_path`].
+    fn write_atomically(&self, path: &Path, contents: &[u8], keep_backup: bool) -> Result<()>;
+    fn exists(&self, path: &Path) -> bool;
+    fn remove_if_exists(&self, path: &Path) -> Result<()>;
+    fn rename_if_exists(&self, from: &Path, to: &Path) -> Result<()>;
+    /// Names of the files directly inside `directory` that end with `suffix`.
+    fn list(&self, directory: &Path, suffix: &str) -> Result<Vec<String>>;
+    fn modified(&self, path: &Path) -> Option<SystemTime>;
+    /// Human-readable location of the path for logging.
+    fn describe(&self, path: &Path) -> String;
+
+    fn read_to_string(&self, path: &Path) -> Result<String> {
+        String::from_utf8(self.read(path)?).context("File is not valid UTF-8")
+    }
+}
+
+pub(crate) fn get_backup_path(path: &Path) -> PathBuf {
+    append_extension(path, "bak")
+}
+
+fn append_extension(path: &Path, extension: &str) -> PathBuf {
+    let mut path = path.as_os_str().to_owned();
+    path.push(".");
+    path.push(extension);
+    path.into()
+}
+
+#[cfg(not(feature = "wasm"))]
+impl UserDirs {
+    fn resolve() -> Self {
+        let data = native::resolve_directory(native::DirectoryKind::Data);
+        let config = native::resolve_directory(native::DirectoryKind::Config);
+        let data = native::FileStorage::new(data);
+        if let Err(e) = native::move_legacy_saves(&data) {
+            error!("Failed to move saves from the working directory: {e:?}");
+        }
+        Self {
+            data: Arc::new(data),
+            config: Arc::new(native::FileStorage::new(config)),
+        }
+    }
+}
+
+#[cfg(feature = "wasm")]
+impl UserDirs {
+    fn resolve() -> Self {
+        Self {
+            data: Arc::new(wasm::LocalStorage::new(format!("{APP_NAME}/data"))),
+            config: Arc::new(wasm::LocalStorage::new(format!("{APP_NAME}/config"))),
+        }
+    }
+}
+
+#[cfg(not(feature = "wasm"))]
+mod native {
+    use super::*;
+    use std::env;
+    use std::fs::{self, File};
+    use std::io::{ErrorKind, Write};
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub(super) enum DirectoryKind {
+        Data,
+        Config,
+    }
+
+    impl DirectoryKind {
+        fn flag(self) -> &'static str {
+            match self {
+                DirectoryKind::Data => "--data-dir",
+                DirectoryKind::Config => "--config-dir",
+            }
+        }
+
+        fn environment_variable(self) -> &'static str {
+            match self {
+                DirectoryKind::Data => "EGG_SLASH_DATA_DIR",
+                DirectoryKind::Config => "EGG_SLASH_CONFIG_DIR",
+            }
+        }
+
+        fn platform_directory(self) -> Option<PathBuf> {
+            let home = env::var_os("HOME").map(PathBuf::from);
+            let directory = if cfg!(target_os = "windows") {
+                env::var_os("APPDATA").map(PathBuf::from)
+            } else if cfg!(target_os = "macos") {
+                home.map(|home| home.join("Library").join("Application Support"))
+            } else {
+                let (xdg_variable, fallback): (_, &[&str]) = match self {
+                    DirectoryKind::Data => ("XDG_DATA_HOME", &[".local", "share"]),
+                    DirectoryKind::Config => ("XDG_CONFIG_HOME", &[".config"]),
+                };
+                // The XDG spec says to ignore relative paths
+                env::var_os(xdg_variable)
+                    .map(PathBuf::from)
+                    .filter(|directory| directory.is_absolute())
+                    .or_else(|| {
+                        home.map(|home| fallback.iter().fold(home, |path, part| path.join(part)))
+                    })
+            };
+            directory.map(|directory| directory.join(APP_NAME))
+        }
+    }
+
+    pub(super) fn resolve_directory(kind: DirectoryKind) -> PathBuf {
+        get_flag(kind.flag())
+            .or_else(|| env::var_os(kind.environment_variable()).map(PathBuf::from))
+            .or_else(|| kind.platform_directory())
+            .unwrap_or_else(|| PathBuf::from("."))
+    }
+
+    /// Supports both `--flag value` and `--flag=value`.
+    fn get_flag(flag: &str) -> Option<PathBuf> {
+        let mut args = env::args_os().skip(1);
+        while let Some(arg) = args.next() {
+            if arg == flag {
+                return args.next().map(PathBuf::from);
+            }
+            if let Some(value) = arg
+                .to_str()
+                .and_then(|arg| arg.strip_prefix(flag))
+                .and_then(|rest| rest.strip_prefix('='))
+            {
+                return Some(PathBuf::from(value));
+            }
+        }
+        None
+    }
+
+    /// Where saves were written before they lived in the data directory, relative to the working directory.
+    const LEGACY_SAVE_DIRECTORY: &str = "saves";
+    /// The save itself, its backup, metadata and thumbnail.
+    const LEGACY_SAVE_SUFFIXES: [&str; 4] = [".sav.ron", ".sav.ron.bak", ".meta.ron", ".png"];
+
+    /// Files already present in the data directory are never overwritten, their legacy copy stays where it is.
+    pub(super) fn move_legacy_saves(data: &FileStorage) -> Result<()> {
+        let legacy = FileStorage::new(PathBuf::from("."));
+        let directory = Path::new(LEGACY_SAVE_DIRECTORY);
+        if is_same_directory(&legacy.root.join(directory), &data.root.join(directory)) {
+            return Ok(());
+        }
+        for suffix in LEGACY_SAVE_SUFFIXES {
+            for name in legacy.list(directory, suffix)? {
+                let path = directory.join(name);
+                if data.exists(&path) {
+                    warn!(
+                        "Not moving {} because {} already exists",
+                        legacy.describe(&path),
+                        data.describe(&path)
+                    );
+                    continue;
+                }
+                // Copying instead of renaming also works across file systems
+                let contents = legacy.read(&path)?;
+                data.write_atomically(&path, &contents, false)?;
+                legacy.remove_if_exists(&path)?;
+                info!(
+                    "Moved {} to {}",
+                    legacy.describe(&path),
+                    data.describe(&path)
+                );
+            }
+        }
+        Ok(())
+    }
+
+    fn is_same_directory(a: &Path, b: &Path) -> bool {
+        match (fs::canonicalize(a), fs::canonicalize(b)) {
+            (Ok(a), Ok(b)) => a == b,
+            _ => false,
+        }
+    }
+
+    #[derive(Debug, Clone)]
+    pub(super) struct FileStorage {
+        root: PathBuf,
+    }
+
+    impl FileStorage {
+        pub(super) fn new(root: PathBuf) -> Self {
+            Self { root }
+        }
+    }
+
+    impl UserStorage for FileStorage {
+        fn read(&self, path: &Path) -> Result<Vec<u8>> {
+            let path = self.root.join(path);
+            fs::read(&path).with_context(|| format!("Failed to read {path:?}"))
+        }
+
+        fn write_atomically(&self, path: &Path, contents: &[u8], keep_backup: bool) -> Result<()> {
+            let path = self.root.join(path);
+            if let Some(directory) = path.parent() {
+                fs::create_dir_all(directory)
+                    .with_context(|| format!("Failed to create directory {directory:?}"))?;
+            }
+            let temporary_path = append_extension(&path, "tmp");
+            let mut file = File::create(&temporary_path)
+                .with_context(|| format!("Failed to create temporary file {temporary_path:?}"))?;
+            file.write_all(contents)
+                .with_context(|| format!("Failed to write temporary file {temporary_path:?}"))?;
+            file.sync_all()
+                .with_context(|| format!("Failed to sync temporary file {temporary_path:?}"))?;
+            drop(file);
+
+            if keep_backup && path.exists() {
+                let backup_path = get_backup_path(&path);
+                fs::copy(&path, &backup_path)
+                    .with_context(|| format!("Failed to back up {path:?} to {backup_path:?}"))?;
+            }
+            fs::rename(&temporary_path, &path)
+                .with_context(|| format!("Failed to move {temporary_path:?} to {path:?}"))?;
+            sync_parent_directory(&path);
+            Ok(())
+        }
+
+        fn exists(&self, path: &Path) -> bool {
+            self.root.join(path).exists()
+        }
+
+        fn remove_if_exists(&self, path: &Path) -> Result<()> {
+            let path = self.root.join(path);
+            match fs::remove_file(&path) {
+                Err(e) if e.kind() != ErrorKind::NotFound => {
+                    Err(e).with_context(|| format!("Failed to delete {path:?}"))
+                }
+                _ => Ok(()),
+            }
+        }
+
+        fn rename_if_exists(&self, from: &Path, to: &Path) -> Result<()> {
+            let from = self.root.join(from);
+            let to = self.root.join(to);
+            match fs::rename(&from, &to) {
+                Err(e) if e.kind() != ErrorKind::NotFound => {
+                    Err(e).with_context(|| format!("Failed to move {from:?} to {to:?}"))
+                }
+                _ => Ok(()),
+            }
+        }
+
+        fn list(&self, directory: &Path, suffix: &str) -> Result<Vec<String>> {
+            let directory = self.root.join(directory);
+            let entries = match fs::read_dir(&directory) {
+                Ok(entries) => entries,
+                Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+                Err(e) => {
+                    return Err(e)
+                        .with_context(|| format!("Failed to read directory {directory:?}"))
+                }
+            };
+            let mut names = Vec::new();
+            for entry in entries {
+                let entry =
+                    entry.with_context(|| format!("Failed to read entry in {directory:?}"))?;
+                let is_file = entry
+                    .file_type()
+                    .map_or(false, |file_type| file_type.is_file());
+                if let Some(name) = entry.file_name().to_str() && is_file && name.ends_with(suffix) {
+                    names.push(name.to_string());
+                }
+            }
+            Ok(names)
+        }
+
+        fn modified(&self, path: &Path) -> Option<SystemTime> {
+            fs::metadata(self.root.join(path))
+                .and_then(|metadata| metadata.modified())
+                .ok()
+        }
+
+        fn describe(&self, path: &Path) -> String {
+            self.root.join(path).to_string_lossy().to_string()
+        }
+    }
+
+    /// Makes the rename itself durable. Only possible on Unix, other platforms cannot open directories.
+    #[cfg(unix)]
+    fn sync_parent_directory(path: &Path) {
+        if let Some(directory) = path.parent() && let Ok(directory) = File::open(directory) {
+            let _ = directory.sync_all();
+        }
+    }
+
+    #[cfg(not(unix))]
+    fn sync_parent_directory(_path: &Path) {}
+}
+
+#[cfg(feature = "wasm")]
+mod wasm {
+    use super::*;
+    use anyhow::anyhow;
+    use std::fmt::Write;
+    use wasm_bindgen::prelude::*;
+
+    #[wasm_bindgen(inline_js = "
+        export function local_storage_get(key) {
+            return window.localStorage.getItem(key);
+        }
+        export function local_storage_set(key, value) {
+            window.localStorage.setItem(key, value);
+        }
+        export function local_storage_remove(key) {
+            window.localStorage.removeItem(key);
+        }
+        export function local_storage_keys() {
+            return Object.keys(window.localStorage);
+        }")]
+    extern "C" {
+        fn local_storage_get(key: &str) -> Option<String>;
+
+        #[wasm_bindgen(catch)]
+        fn local_storage_set(key: &str, value: &str) -> Result<(), JsValue>;
+
+        fn local_storage_remove(key: &str);
+
+        fn local_storage_keys() -> Vec<JsValue>;
+    }
+
+    /// Stores every file under the key `<prefix>/<path>`.
+    /// `localStorage` only holds strings, so files that are not valid UTF-8 are stored as hex.
+    #[derive(Debug, Clone)]
+    pub(super) struct LocalStorage {
+        prefix: String,
+    }
+
+    impl LocalStorage {
+        pub(super) fn new(prefix: String) -> Self {
+            Self { prefix }
+        }
+
+        fn get_key(&self, path: &Path) -> String {
+            let path = path.to_string_lossy().replace('\\', "/");
+            format!("{}/{}", self.prefix, path)
+        }
+
+        fn set(&self, key: &str, value: &str) -> Result<()> {
+            local_storage_set(key, value)
+                .map_err(|e| anyhow!("Failed to write {key} to localStorage: {e:?}"))
+        }
+    }
+
+    fn encode(contents: &[u8]) -> String {
+        match std::str::from_utf8(contents) {
+            Ok(text) => format!("t{text}"),
+            Err(_) => contents
+                .iter()
+                .fold(String::from("h"), |mut encoded, byte| {
+                    let _ = write!(encoded, "{byte:02x}");
+                    encoded
+                }),
+        }
+    }
+
+    fn decode(value: &str) -> Result<Vec<u8>> {
+        if let Some(text) = value.strip_prefix('t') {
+            Ok(text.as_bytes().to_vec())
+        } else if let Some(hex) = value.strip_prefix('h') {
+            (0..hex.len())
+                .step_by(2)
+                .map(|index| {
+                    hex.get(index..index + 2)
+                        .and_then(|byte| u8::from_str_radix(byte, 16).ok())
+                        .context("Invalid hex in localStorage value")
+                })
+                .collect()
+        } else {
+            Err(anyhow!("Unknown encoding of localStorage value"))
+        }
+    }
+
+    impl UserStorage for LocalStorage {
+        fn read(&self, path: &Path) -> Result<Vec<u8>> {
+            let key = self.get_key(path);
+            let value = local_storage_get(&key)
+                .with_context(|| format!("Failed to read {key} from localStorage: No such key"))?;
+            decode(&value)
+        }
+
+        fn write_atomically(&self, path: &Path, contents: &[u8], keep_backup: bool) -> Result<()> {
+            // Writing a single key to localStorage is atomic already
+            let key = self.get_key(path);
+            if keep_backup && let Some(previous) = local_storage_get(&key) {
+                self.set(&self.get_key(&get_backup_path(path)), &previous)?;
+            }
+            self.set(&key, &encode(contents))
+        }
+
+        fn exists(&self, path: &Path) -> bool {
+            local_storage_get(&self.get_key(path)).is_some()
+        }
+
+        fn remove_if_exists(&self, path: &Path) -> Result<()> {
+            local_storage_remove(&self.get_key(path));
+            Ok(())
+        }
+
+        fn rename_if_exists(&self, from: &Path, to: &Path) -> Result<()> {
+            let from = self.get_key(from);
+            if let Some(value) = local_storage_get(&from) {
+                self.set(&self.get_key(to), &value)?;
+                local_storage_remove(&from);
+            }
+            Ok(())
+        }
+
+        fn list(&self, directory: &Path, suffix: &str) -> Result<Vec<String>> {
+            let directory = format!("{}/", self.get_key(directory).trim_end_matches('/'));
+            let names = local_storage_keys()
+                .into_iter()
+                .filter_map(|key| key.as_string())
+                .filter_map(|key| {
+                    key.strip_prefix(&directory)
+                        .filter(|name| !name.contains('/') && name.ends_with(suffix))
+                        .map(|name| name.to_string())
+                })
+                .collect();
+            Ok(names)
+        }
+
+        fn modified(&self, _path: &Path) -> Option<SystemTime> {
+            None
+        }
+
+        fn describe(&self, path: &Path) -> String {
+            format!("localStorage:{}", self.get_key(path))
+        }
+    }
+}
@@ -1,3 +1,8 @@
+use crate::file_system_interaction::game_state_serialization::{
+    GameLoadRequest, GameSaveRequest, SaveSlotAction, SaveSlots, SaveSlotsUi,
+};
+use crate::file_system_interaction::user_dirs::UserDirs;
+use crate::localization::{show_language_selection, Localization};
 use crate::player_control::actions::{ActionsFrozen, UiAction};
 use crate::GameState;
 use bevy::prelude::*;
@@ -10,11 +15,18 @@ pub(crate) fn ingame_menu_plugin(app: &mut App) {
 }
 
 fn handle_pause(
+    mut commands: Commands,
     mut time: ResMut<Time>,
     actions: Query<&ActionState<UiAction>>,
     mut actions_frozen: ResMut<ActionsFrozen>,
     mut egui_contexts: EguiContexts,
     mut paused: Local<bool>,
+    mut save_slots: ResMut<SaveSlots>,
+    mut save_slots_ui: Local<SaveSlotsUi>,
+    user_dirs: Res<UserDirs>,
+    mut save_requests: EventWriter<GameSaveRequest>,
+    mut load_requests: EventWriter<GameLoadRequest>,
+    localization: Localization,
 ) {
     for action in actions.iter() {
         let toggled = action.just_pressed(UiAction::TogglePause);
@@ -24,6 +36,7 @@ fn handle_pause(
                 time.unpause();
                 actions_frozen.unfreeze();
             } else {
+                let mut save_slot_action = None;
                 egui::CentralPanel::default()
                     .frame(egui::Frame {
                         fill: egui::Color32::from_black_alpha(240),
@@ -34,11 +47,38 @@ fn handle_pause(
                             ui.visuals_mut().override_text_color =
                                 Some(egui::Color32::from_gray(240));
                             ui.add_space(100.0);
-                            ui.heading("Game Paused");
+                            ui.heading(localization.get("pause.title"));
                             ui.separator();
-                            ui.label("Press ESC to resume");
+                            ui.label(localization.get("pause.resume"));
+                            ui.add_space(20.0);
+                            if ui.button(localization.get("pause.save")).clicked() {
+                                save_requests.send(GameSaveRequest { filename: None });
+                            }
+                            ui.add_space(20.0);
+                            show_language_selection(ui, &mut commands, &localization);
+                            ui.add_space(20.0);
+                            save_slot_action =
+                                save_slots_ui.show(ui, &save_slots, true, &localization);
                         });
                     });
+                match save_slot_action
+                    .and_then(|action| save_slots_ui.apply(action, &mut save_slots, &user_dirs))
+                {
+                    Some(SaveSlotAction::Load(name)) => {
+                        load_requests.send(GameLoadRequest {
+                            filename: Some(name),
+                        });
+                        *paused = false;
+                        time.unpause();
+                        actions_frozen.unfreeze();
+                    }
+                    Some(SaveSlotAction::Overwrite(name)) => {
+                        save_requests.send(GameSaveRequest {
+                            filename: Some(name),
+                        });
+                    }
+                    _ => {}
+                }
             }
         } else if toggled {
             *paused = true;
@@ -1,3 +1,4 @@
+use crate::level_instantiation::spawning::scene_tags::SceneTags;
 use crate::util::trait_extension::MeshExt;
 use anyhow::{bail, Context, Result};
 use bevy::prelude::*;
@@ -15,17 +16,19 @@ pub(crate) fn grass_plugin(app: &mut App) {
     );
 }
 
+const DEFAULT_BLADES_PER_SQUARE_METER: f32 = 10.0;
+
 #[sysfail(log(level = "error"))]
 pub(crate) fn add_grass(
     mut commands: Commands,
-    added_name: Query<(Entity, &Name), Added<Name>>,
+    added_tags: Query<(Entity, &SceneTags), Added<SceneTags>>,
     meshes: Res<Assets<Mesh>>,
     children_query: Query<&Children>,
     mesh_handles: Query<&Handle<Mesh>>,
     global_transforms: Query<&GlobalTransform>,
 ) -> Result<()> {
-    for (parent_entity, name) in added_name.iter() {
-        if name.contains("[grass]") {
+    for (parent_entity, tags) in added_tags.iter() {
+        if let Some(density) = tags.grass() {
             for (child_entity, mesh) in
                 Mesh::search_in_children(parent_entity, &children_query, &meshes, &mesh_handles)
             {
@@ -38,11 +41,11 @@ pub(crate) fn add_grass(
                     .map(|triangle| triangle.map(|position| transform.transform_point(position)));
 
                 let rng = SmallRng::from_entropy();
-                const BLADES_PER_SQUARE_METER: f32 = 10.0;
+                let blades_per_square_meter = density.unwrap_or(DEFAULT_BLADES_PER_SQUARE_METER);
                 let positions = triangles
                     .flat_map(|triangle| {
                         let area = area_of_triangle(&triangle);
-                        let blade_count = (area * BLADES_PER_SQUARE_METER) as usize;
+                        let blade_count = (area * blades_per_square_meter) as usize;
                         let mut rng = rng.clone();
                         (0..blade_count).map(move |_| {
                             pick_uniform_random_point_in_triangle(&mut rng, &triangle)
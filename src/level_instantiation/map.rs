@@ -1,14 +1,23 @@
-use crate::file_system_interaction::level_serialization::{CurrentLevel, WorldLoadRequest};
+use crate::combat::archetype::EnemySpawner;
+use crate::file_system_interaction::game_state_serialization::{GameLoadRequest, RunRestore};
+use crate::file_system_interaction::level_serialization::{
+    CurrentLevel, PendingLevelComponents, WorldLoadRequest,
+};
+use crate::level_instantiation::spawning::objects::point_light::create_point_light;
+use crate::level_instantiation::spawning::objects::room::EnemyBudget;
+use crate::level_instantiation::spawning::scene_tags::{SceneTag, SceneTagSystemSet, SceneTags};
 use crate::level_instantiation::spawning::GameObject;
 use crate::player_control::player_embodiment::Player;
+use crate::world_interaction::room::{PreviousRoom, Room, RoomTransition};
 use crate::world_interaction::side_effects::{SideEffect, SideEffects};
 use crate::GameState;
+use anyhow::{Context, Result};
 use bevy::prelude::*;
+use bevy::scene::SceneInstance;
 use bevy::transform::TransformSystem;
 use bevy_egui::{egui, EguiContexts};
-use regex::Regex;
+use bevy_mod_sysfail::macros::*;
 use spew::prelude::*;
-use std::sync::LazyLock;
 
 pub(crate) fn map_plugin(app: &mut App) {
     app.add_system(
@@ -16,6 +25,12 @@ pub(crate) fn map_plugin(app: &mut App) {
             .run_if(not(resource_exists::<CurrentLevel>()))
             .in_schedule(OnEnter(GameState::Playing)),
     )
+    .add_system(
+        align_next_room
+            .after(SceneTagSystemSet)
+            .run_if(resource_exists::<RoomTransition>())
+            .in_set(OnUpdate(GameState::Playing)),
+    )
     .add_system(
         show_loading_screen
             .run_if(not(any_with_component::<Player>()))
@@ -27,6 +42,9 @@ pub(crate) fn map_plugin(app: &mut App) {
             spawn_lights.run_if(in_state(GameState::Playing)),
             place_player.run_if(in_state(GameState::Playing)),
             spawn_exit.run_if(in_state(GameState::Playing)),
+            mark_restored_room_as_instantiated
+                .run_if(in_state(GameState::Playing))
+                .run_if(resource_exists::<RunRestore>()),
         )
             .chain()
             .after(TransformSystem::TransformPropagate)
@@ -36,27 +54,98 @@ pub(crate) fn map_plugin(app: &mut App) {
     app.add_system(show_wasm_loader.in_set(OnUpdate(GameState::Playing)));
 }
 
-fn setup(mut commands: Commands, mut loader: EventWriter<WorldLoadRequest>) {
+fn setup(
+    mut commands: Commands,
+    mut loader: EventWriter<WorldLoadRequest>,
+    game_load_requests: EventReader<GameLoadRequest>,
+) {
     commands.insert_resource(AmbientLight {
         color: Color::WHITE,
         brightness: 0.05,
     });
 
+    // A save loaded from the menu brings its own level
+    if !game_load_requests.is_empty() {
+        return;
+    }
     loader.send(WorldLoadRequest {
         filename: "intro_room".to_string(),
+        ..default()
     });
 }
 
+/// Moves the room that was spawned at the exit of the previous room so that its `[entrance]` lines up with that exit.
+/// Runs as soon as the room's scene is instantiated and before transforms are propagated,
+/// so that everything spawned from the room's tags already ends up in the right place.
+#[sysfail(log(level = "error"))]
+fn align_next_room(
+    mut commands: Commands,
+    mut room_transition: ResMut<RoomTransition>,
+    tags: Query<(Entity, &SceneTags), Added<SceneTags>>,
+    rooms: Query<(Entity, &GameObject), (With<Room>, Without<PreviousRoom>)>,
+    parents: Query<&Parent>,
+    transforms: Query<&Transform>,
+) -> Result<()> {
+    if room_transition.is_next_room_aligned {
+        return Ok(());
+    }
+    let Some(room) = rooms
+        .iter()
+        .find(|(_, game_object)| game_object.is_room())
+        .map(|(entity, _)| entity) else {
+        return Ok(());
+    };
+    for (entity, tags) in tags.iter() {
+        if !tags.contains(&SceneTag::Entrance)
+            || !parents
+                .iter_ancestors(entity)
+                .any(|ancestor| ancestor == room)
+        {
+            continue;
+        }
+        let mut entrance = transforms
+            .get(entity)
+            .context("Failed to get transform of entrance")?
+            .compute_matrix();
+        for ancestor in parents
+            .iter_ancestors(entity)
+            .take_while(|ancestor| *ancestor != room)
+        {
+            let ancestor_transform = transforms
+                .get(ancestor)
+                .context("Failed to get transform of entrance ancestor")?;
+            entrance = ancestor_transform.compute_matrix() * entrance;
+        }
+        let room_transform = room_transition.anchor.compute_matrix() * entrance.inverse();
+        commands
+            .entity(room)
+            .insert(Transform::from_matrix(room_transform));
+        room_transition.is_next_room_aligned = true;
+        return Ok(());
+    }
+    Ok(())
+}
+
 fn place_player(
     mut commands: Commands,
-    names: Query<(Entity, &GlobalTransform, &Name), (Added<Name>, Without<Player>)>,
+    tags: Query<(Entity, &GlobalTransform, &SceneTags), (Added<SceneTags>, Without<Player>)>,
     mut player_query: Query<&mut Transform, With<Player>>,
     mut spawn_events: EventWriter<SpawnEvent<GameObject, Transform>>,
     side_effects: Res<SideEffects>,
+    run_restore: Option<Res<RunRestore>>,
+    room_transition: Option<Res<RoomTransition>>,
 ) {
-    for (entity, global_transform, name) in names.iter() {
-        if name.contains("[entrance]") {
+    for (entity, global_transform, tags) in tags.iter() {
+        if tags.contains(&SceneTag::Entrance) {
             commands.entity(entity).despawn_recursive();
+            if let Some(run_restore) = run_restore.as_ref() && run_restore.is_waiting_for_room() {
+                // The player of a loaded save keeps their saved position
+                continue;
+            }
+            if room_transition.is_some() {
+                // The next room was attached to the previous one, so the player walks into it instead
+                continue;
+            }
             let side_effect = side_effects.get_factored(SideEffect::Size, 0.3);
             let transform = global_transform
                 .compute_transform()
@@ -71,53 +160,105 @@ fn place_player(
     }
 }
 
-static ENEMY_REGEX: LazyLock<Regex> =
-    LazyLock::new(|| Regex::new(r"\[enemy:\s*(\w+)\]").expect("Failed to compile enemy regex"));
-
 fn spawn_enemies(
     mut commands: Commands,
-    names: Query<(Entity, &GlobalTransform, &Name), Added<Name>>,
-    mut spawn_events: EventWriter<SpawnEvent<GameObject, Transform>>,
+    tags: Query<(Entity, &GlobalTransform, &SceneTags), Added<SceneTags>>,
+    mut enemy_spawner: EnemySpawner,
+    run_restore: Option<Res<RunRestore>>,
+    parents: Query<&Parent>,
+    mut enemy_budgets: Query<&mut EnemyBudget>,
 ) {
-    for (entity, global_transform, name) in names.iter() {
-        if let Some(captures) = ENEMY_REGEX.captures(&name.to_lowercase()) {
+    let is_restoring_enemies = run_restore
+        .map(|run_restore| run_restore.is_waiting_for_room() && run_restore.restores_enemies())
+        .unwrap_or_default();
+    for (entity, global_transform, tags) in tags.iter() {
+        if let Some(parameters) = tags.enemy() {
             commands.entity(entity).despawn_recursive();
-            let enemy_name = captures.get(1).unwrap().as_str();
-            match enemy_name {
-                "dummy" => {
-                    let transform = global_transform
-                        .compute_transform()
-                        .with_scale(Vec3::splat(1.));
-                    spawn_events.send(SpawnEvent::with_data(GameObject::Dummy, transform));
-                }
-                _ => {
-                    error!("Tried to spawn invalid enemy type: {}", enemy_name);
+            if is_restoring_enemies {
+                continue;
+            }
+            if let Some(mut enemy_budget) = parents
+                .iter_ancestors(entity)
+                .find(|ancestor| enemy_budgets.contains(*ancestor))
+                .and_then(|room| enemy_budgets.get_mut(room).ok())
+            {
+                if enemy_budget.0 == 0 {
+                    continue;
                 }
+                enemy_budget.0 -= 1;
+            }
+            let transform = global_transform
+                .compute_transform()
+                .with_scale(Vec3::splat(1.));
+            if let Err(e) = enemy_spawner.spawn(parameters.clone(), transform) {
+                error!("{e:?}");
             }
         }
     }
 }
 
+/// Lets the [`RunRestore`] of a loaded save spawn its enemies once the room's scene has been instantiated.
+/// Runs after the room's tags were handled so that the enemies tagged in the room are already skipped.
+fn mark_restored_room_as_instantiated(
+    mut run_restore: ResMut<RunRestore>,
+    rooms: Query<(&GameObject, Option<&SceneInstance>), (With<Room>, Without<PreviousRoom>)>,
+    players: Query<(), With<Player>>,
+    scene_spawner: Res<SceneSpawner>,
+) {
+    if !run_restore.is_waiting_for_room() {
+        return;
+    }
+    let mut rooms = rooms
+        .iter()
+        .filter(|(game_object, _)| game_object.is_room())
+        .peekable();
+    let is_room_ready = if rooms.peek().is_some() {
+        rooms.all(|(_, instance)| {
+            instance.map_or(false, |instance| {
+                scene_spawner.instance_is_ready(**instance)
+            })
+        })
+    } else {
+        // The room is spawned together with the level, which is done by the time the player shows up
+        !players.is_empty()
+    };
+    if is_room_ready {
+        run_restore.mark_room_as_instantiated();
+    }
+}
+
 fn spawn_lights(
-    names: Query<(&GlobalTransform, &Name), Added<Name>>,
+    tags: Query<(&GlobalTransform, &SceneTags), Added<SceneTags>>,
     mut spawn_events: EventWriter<SpawnEvent<GameObject, Transform>>,
+    mut pending_components: ResMut<PendingLevelComponents>,
 ) {
-    for (global_transform, name) in names.iter() {
-        if name.contains("[light]") {
+    for (global_transform, tags) in tags.iter() {
+        if let Some(intensity) = tags.light() {
             let transform = global_transform
                 .compute_transform()
                 .with_scale(Vec3::splat(1.));
+            if let Some(intensity) = intensity {
+                let point_light = PointLight {
+                    intensity,
+                    ..create_point_light()
+                };
+                pending_components.push(
+                    GameObject::PointLight,
+                    transform.translation,
+                    vec![Box::new(point_light)],
+                );
+            }
             spawn_events.send(SpawnEvent::with_data(GameObject::PointLight, transform));
         }
     }
 }
 
 fn spawn_exit(
-    names: Query<(&GlobalTransform, &Name), Added<Name>>,
+    tags: Query<(&GlobalTransform, &SceneTags), Added<SceneTags>>,
     mut spawn_events: EventWriter<SpawnEvent<GameObject, Transform>>,
 ) {
-    for (global_transform, name) in names.iter() {
-        if name.contains("[exit]") {
+    for (global_transform, tags) in tags.iter() {
+        if tags.contains(&SceneTag::Exit) {
             let transform = global_transform
                 .compute_transform()
                 .with_scale(Vec3::splat(1.));
@@ -1,11 +1,15 @@
 use crate::level_instantiation::spawning::animation_link::link_animations;
 use crate::level_instantiation::spawning::despawn::{despawn, Despawn};
 use crate::level_instantiation::spawning::post_spawn_modification::{
-    despawn_removed, set_color, set_hidden, set_shadows,
+    apply_color_override, apply_repeat_override, despawn_removed, set_color, set_hidden,
+    set_shadows, ColorOverride, RepeatOverride,
 };
+use crate::level_instantiation::spawning::scene_tags::{scene_tags_plugin, SceneTagSystemSet};
+use crate::world_interaction::room::RoomId;
 use crate::GameState;
 pub(crate) use animation_link::AnimationEntityLink;
 use bevy::prelude::*;
+use seldom_fn_plugin::FnPluginExt;
 use serde::{Deserialize, Serialize};
 use spew::prelude::*;
 use strum_macros::EnumIter;
@@ -14,11 +18,15 @@ pub(crate) mod animation_link;
 mod despawn;
 pub(crate) mod objects;
 pub(crate) mod post_spawn_modification;
+pub(crate) mod scene_tags;
 
 pub(crate) fn spawning_plugin(app: &mut App) {
     app.add_plugin(SpewPlugin::<GameObject, Transform>::default())
         .register_type::<Despawn>()
         .register_type::<AnimationEntityLink>()
+        .register_type::<ColorOverride>()
+        .register_type::<RepeatOverride>()
+        .fn_plugin(scene_tags_plugin)
         .add_spawners((
             (GameObject::Empty, objects::primitives::spawn_empty),
             (GameObject::Box, objects::primitives::spawn_box),
@@ -34,15 +42,20 @@ pub(crate) fn spawning_plugin(app: &mut App) {
             (GameObject::Skydome, objects::skydome::spawn),
             (GameObject::Exit, objects::exit::spawn),
         ))
-        .add_spawners((
-            (GameObject::IntroRoom, objects::intro_room::spawn_intro),
-            (GameObject::RoomOne, objects::intro_room::spawn_one),
-            (GameObject::RoomTwo, objects::intro_room::spawn_two),
-            (GameObject::RoomThree, objects::intro_room::spawn_three),
-        ))
+        .add_plugin(SpewPlugin::<RoomSpawner, (RoomId, Transform)>::default())
+        .add_spawner((RoomSpawner::Room, objects::room::spawn))
+        .add_system(objects::room::load_room_scenes.in_schedule(OnExit(GameState::Loading)))
         .add_systems((despawn, link_animations).in_set(OnUpdate(GameState::Playing)))
         .add_systems(
-            (set_hidden, despawn_removed, set_color, set_shadows)
+            (
+                set_hidden,
+                despawn_removed,
+                set_color,
+                set_shadows,
+                apply_color_override,
+                apply_repeat_override,
+            )
+                .after(SceneTagSystemSet)
                 .in_set(OnUpdate(GameState::Playing)),
         );
 }
@@ -74,12 +87,24 @@ pub(crate) enum GameObject {
     PointLight,
     Dummy,
     Player,
-    IntroRoom,
-    RoomOne,
-    RoomTwo,
-    RoomThree,
+    /// Spawned through [`RoomSpawner`], since rooms are defined by assets instead of variants.
+    #[strum(disabled)]
+    Room(RoomId),
     Orb,
     Camera,
     Skydome,
     Exit,
 }
+
+impl GameObject {
+    pub(crate) fn is_room(self) -> bool {
+        matches!(self, GameObject::Room(_))
+    }
+}
+
+/// Spawns any room of the pool as a [`GameObject::Room`].
+/// Spawners are looked up by value, so rooms share this key and pass their [`RoomId`] along with the transform.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub(crate) enum RoomSpawner {
+    Room,
+}
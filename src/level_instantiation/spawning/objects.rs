@@ -3,12 +3,12 @@ use bitflags::bitflags;
 
 pub(crate) mod camera;
 pub(crate) mod exit;
-pub(crate) mod intro_room;
 pub(crate) mod npc;
 pub(crate) mod orb;
 pub(crate) mod player;
 pub(crate) mod point_light;
 pub(crate) mod primitives;
+pub(crate) mod room;
 pub(crate) mod skydome;
 pub(crate) mod sunlight;
 mod util;
@@ -1,4 +1,5 @@
 use crate::level_instantiation::spawning::GameObject;
+use crate::world_interaction::interaction::{Interactable, InteractionAction};
 use crate::world_interaction::room::{Exit, Room};
 use bevy::prelude::*;
 
@@ -6,6 +7,7 @@ pub(crate) fn spawn(In(transform): In<Transform>, mut commands: Commands) {
     commands.spawn((
         Name::new("Exit"),
         Exit,
+        Interactable::new(InteractionAction::LeaveRoom),
         SpatialBundle::from_transform(transform),
         GameObject::Exit,
         Room,
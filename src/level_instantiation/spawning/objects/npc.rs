@@ -173,6 +173,7 @@ pub(crate) fn spawn(
                                                 speed: 10.0,
                                                 tracking: 0.5,
                                                 max_lifetime: 3.0,
+                                                ..default()
                                             },
                                         ),
                                     ),
@@ -334,7 +335,59 @@ pub(crate) fn spawn(
                                 ..default()
                             }
                         ]
-                    }
+                    },
+                    Choreography {
+                        name: "Unaware".to_string(),
+                        moves: vec![
+                            Move {
+                                metadata: MoveMetadata {
+                                    duration: MoveDuration::Fixed(0.5),
+                                    animation: Some(animations.idle.clone()),
+                                    state: EnemyCombatState::OnGuard
+                                },
+                                ..default()
+                            }
+                        ]
+                    },
+                    create_ranged_choreography(
+                        "Kunai Lob",
+                        &animations,
+                        ai::generic::projectile::spawn_projectile(
+                            ProjectileKind::Arcing,
+                            ProjectileSpawnInput {
+                                gravity: 9.81,
+                                is_piercing: false,
+                                ..create_kunai_spawn_input(&scene_handles, 8.0)
+                            },
+                        ),
+                    ),
+                    create_ranged_choreography(
+                        "Kunai Fan",
+                        &animations,
+                        ai::generic::projectile::spawn_projectile_spread(
+                            ProjectileKind::Simple,
+                            ProjectileSpawnInput {
+                                tracking: 0.0,
+                                ..create_kunai_spawn_input(&scene_handles, 6.0)
+                            },
+                            3,
+                            30.0,
+                        ),
+                    ),
+                    create_ranged_choreography(
+                        "Homing Kunai",
+                        &animations,
+                        ai::generic::projectile::spawn_projectile(
+                            ProjectileKind::DelayedHoming,
+                            ProjectileSpawnInput {
+                                yaw_offset: 60.0,
+                                tracking: 0.8,
+                                homing_delay: 0.4,
+                                max_lifetime: 4.0,
+                                ..create_kunai_spawn_input(&scene_handles, 8.0)
+                            },
+                        ),
+                    ),
                 ], vec![
                     Tendency {
                         // Walk toward player
@@ -368,12 +421,31 @@ pub(crate) fn spawn(
                         choreography: 4,
                         weight: 0.2,
                         condition: CombatCondition::True
+                    },
+                    Tendency {
+                        // Kunai lob
+                        choreography: 10,
+                        weight: 0.4,
+                        condition: CombatCondition::PlayerDistanceOver(5.0),
+                    },
+                    Tendency {
+                        // Kunai fan
+                        choreography: 11,
+                        weight: 0.4,
+                        condition: CombatCondition::PlayerDistanceOver(3.0),
+                    },
+                    Tendency {
+                        // Homing kunai
+                        choreography: 12,
+                        weight: 0.3,
+                        condition: CombatCondition::PlayerDistanceOver(6.0),
                     }
                 ], HashMap::new(), SpecialChoreographies {
                     block: 5,
                     hurt: 6,
                     posture_broken: 7,
                     death: 8,
+                    unaware: 9,
                 }),
                 constitution: Constitution::default().with_max_health(100.0).with_max_posture(50.0).with_base_posture_recovery(10.0),
                 ..default()
@@ -403,3 +475,61 @@ pub(crate) fn spawn(
             },));
         });
 }
+
+fn create_ranged_choreography(
+    name: &str,
+    animations: &DummyAnimationAssets,
+    projectile_attack_fn: Box<dyn ProjectileAttackFn>,
+) -> Choreography {
+    Choreography {
+        name: name.to_string(),
+        moves: vec![
+            Move {
+                name: Some("Wind up".to_string()),
+                metadata: MoveMetadata {
+                    duration: MoveDuration::Fixed(0.3),
+                    animation: Some(animations.attack.clone()),
+                    state: EnemyCombatState::OnGuard,
+                },
+                functions: MoveFunctions {
+                    motion_fn: Some(ai::generic::motion::continuous::face_player()),
+                    ..default()
+                },
+            },
+            Move {
+                name: Some("Throw".to_string()),
+                metadata: MoveMetadata {
+                    duration: MoveDuration::Instant,
+                    state: EnemyCombatState::Vulnerable,
+                    ..default()
+                },
+                functions: MoveFunctions {
+                    projectile_attack_fn: Some(projectile_attack_fn),
+                    ..default()
+                },
+            },
+            Move {
+                name: Some("Recover".to_string()),
+                metadata: MoveMetadata {
+                    duration: MoveDuration::Fixed(0.5),
+                    animation: Some(animations.idle.clone()),
+                    state: EnemyCombatState::Vulnerable,
+                },
+                ..default()
+            },
+        ],
+    }
+}
+
+fn create_kunai_spawn_input(scene_handles: &SceneAssets, speed: f32) -> ProjectileSpawnInput {
+    ProjectileSpawnInput {
+        model: scene_handles.kunai.clone(),
+        attack: AttackHitbox::from_attack(
+            Attack::new("Kunai Throw").with_health_damage_scaling_rest(10.0),
+        ),
+        speed,
+        tracking: 0.5,
+        max_lifetime: 3.0,
+        ..default()
+    }
+}
@@ -1,5 +1,9 @@
-use crate::combat::{Attack, Constitution, HitboxParentModel};
-use crate::file_system_interaction::asset_loading::{FpsDummyAnimationAssets, SceneAssets};
+use crate::combat::{
+    Attack, AttackHitbox, Constitution, HitboxParentModel, ProjectileKind, ProjectileSpawnInput,
+};
+use crate::file_system_interaction::asset_loading::{
+    ComboAssets, FpsDummyAnimationAssets, SceneAssets,
+};
 use crate::level_instantiation::spawning::objects::GameCollisionGroup;
 use crate::level_instantiation::spawning::GameObject;
 use crate::movement::general_movement::{CharacterControllerBundle, ManualRotation, Model};
@@ -8,10 +12,11 @@ use crate::player_control::actions::{
 };
 use crate::player_control::camera::IngameCamera;
 use crate::player_control::player_embodiment::combat::{
-    BlockHistory, CancellationTimes, PeriodicCancellationTimes, PlayerAttacks,
-    PlayerCombatAnimation, PlayerCombatAnimations, PlayerCombatBundle,
+    BlockHistory, CancellationTimes, ComboDefinition, PeriodicCancellationTimes, PlayerAttacks,
+    PlayerCombatAnimation, PlayerCombatAnimations, PlayerCombatBundle, PlayerTool,
 };
 use crate::player_control::player_embodiment::Player;
+use anyhow::{Context, Result};
 use bevy::prelude::*;
 use bevy_rapier3d::prelude::*;
 use std::f32::consts::TAU;
@@ -24,8 +29,15 @@ pub(crate) fn spawn(
     mut commands: Commands,
     scene_handles: Res<SceneAssets>,
     animations: Res<FpsDummyAnimationAssets>,
+    combo_assets: Res<ComboAssets>,
+    combos: Res<Assets<ComboDefinition>>,
     cameras: Query<Entity, With<IngameCamera>>,
 ) {
+    let player_attacks =
+        create_player_attacks(&combo_assets, &combos, &animations).unwrap_or_else(|e| {
+            error!("Failed to create player attacks: {e:#}");
+            PlayerAttacks::default()
+        });
     let player_entity = commands
         .spawn((
             PbrBundle {
@@ -47,38 +59,6 @@ pub(crate) fn spawn(
                 player_combat: default(),
                 player_combat_animations: PlayerCombatAnimations {
                     idle: PlayerCombatAnimation::always_cancellable(animations.idle.clone()),
-                    attacks: [
-                        PlayerCombatAnimation {
-                            handle: animations.attack_one.clone(),
-                            cancellation_times: CancellationTimes::Periodic(
-                                PeriodicCancellationTimes {
-                                    early_cancel_end: 0.2,
-                                    late_cancel_start: 0.7,
-                                    buffer_start: 0.5,
-                                },
-                            ),
-                        },
-                        PlayerCombatAnimation {
-                            handle: animations.attack_two.clone(),
-                            cancellation_times: CancellationTimes::Periodic(
-                                PeriodicCancellationTimes {
-                                    early_cancel_end: 0.1,
-                                    late_cancel_start: 0.6,
-                                    buffer_start: 0.4,
-                                },
-                            ),
-                        },
-                        PlayerCombatAnimation {
-                            handle: animations.attack_three.clone(),
-                            cancellation_times: CancellationTimes::Periodic(
-                                PeriodicCancellationTimes {
-                                    early_cancel_end: 0.07,
-                                    late_cancel_start: 0.7,
-                                    buffer_start: 0.5,
-                                },
-                            ),
-                        },
-                    ],
                     block: PlayerCombatAnimation::always_cancellable(animations.block.clone()),
                     hurt: PlayerCombatAnimation {
                         handle: animations.hurt.clone(),
@@ -111,18 +91,28 @@ pub(crate) fn spawn(
                         ),
                     },
                 },
-                player_attacks: PlayerAttacks {
-                    attacks: [
-                        Attack::new("Attack 1").with_health_damage_scaling_rest(20.0),
-                        Attack::new("Attack 2").with_health_damage_scaling_rest(15.0),
-                        Attack::new("Attack 3").with_health_damage_scaling_rest(30.0),
-                    ],
-                },
+                player_attacks,
                 constitution: Constitution::default()
                     .with_max_health(100.0)
                     .with_max_posture(50.0)
                     .with_base_posture_recovery(8.0),
                 block_history: BlockHistory::default(),
+                tool: PlayerTool::new(
+                    "tool.kunai",
+                    ProjectileKind::Simple,
+                    ProjectileSpawnInput {
+                        model: scene_handles.kunai.clone(),
+                        attack: AttackHitbox::from_attack(
+                            Attack::new("Player Kunai Throw").with_health_damage_scaling_rest(10.0),
+                        ),
+                        speed: 12.0,
+                        tracking: 0.6,
+                        max_lifetime: 2.0,
+                        ..default()
+                    },
+                )
+                .with_max_uses(5)
+                .with_cooldown(0.8),
             },
             GameObject::Player,
         ))
@@ -153,3 +143,14 @@ pub(crate) fn spawn(
             ));
         });
 }
+
+fn create_player_attacks(
+    combo_assets: &ComboAssets,
+    combos: &Assets<ComboDefinition>,
+    animations: &FpsDummyAnimationAssets,
+) -> Result<PlayerAttacks> {
+    combos
+        .get(&combo_assets.player)
+        .context("Failed to get player combo from handle")?
+        .build(animations)
+}
@@ -3,17 +3,21 @@ use crate::level_instantiation::spawning::GameObject;
 use crate::world_interaction::room::Room;
 use bevy::prelude::*;
 
+pub(crate) fn create_point_light() -> PointLight {
+    PointLight {
+        color: Color::hex("FD5D2C").unwrap(),
+        range: 40.0,
+        radius: 0.1,
+        intensity: 100.0,
+        shadows_enabled: true,
+        ..default()
+    }
+}
+
 pub(crate) fn spawn(In(transform): In<Transform>, mut commands: Commands) {
     commands.spawn((
         PointLightBundle {
-            point_light: PointLight {
-                color: Color::hex("FD5D2C").unwrap(),
-                range: 40.0,
-                radius: 0.1,
-                intensity: 100.0,
-                shadows_enabled: true,
-                ..default()
-            },
+            point_light: create_point_light(),
             transform,
             ..default()
         },
@@ -0,0 +1,63 @@
+use crate::file_system_interaction::asset_loading::RoomAssets;
+use crate::level_instantiation::spawning::GameObject;
+use crate::world_interaction::room::{Room, RoomDefinition, RoomId};
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+
+/// Scenes of all rooms in [`RoomAssets`], loaded up front so that entering a room does not wait for its glTF.
+#[derive(Debug, Clone, Resource, Default)]
+pub(crate) struct RoomScenes(HashMap<RoomId, Handle<Scene>>);
+
+/// How many more enemies the room may spawn, see [`RoomDefinition::enemy_budget`].
+#[derive(Debug, Clone, Copy, Component)]
+pub(crate) struct EnemyBudget(pub(crate) u32);
+
+pub(crate) fn load_room_scenes(
+    mut commands: Commands,
+    room_assets: Res<RoomAssets>,
+    definitions: Res<Assets<RoomDefinition>>,
+    asset_server: Res<AssetServer>,
+) {
+    let scenes = room_assets
+        .rooms
+        .iter()
+        .filter_map(|(path, handle)| Some((RoomId::from_path(path)?, definitions.get(handle)?)))
+        .map(|(room, definition)| (room, asset_server.load(&definition.scene)))
+        .collect();
+    commands.insert_resource(RoomScenes(scenes));
+}
+
+pub(crate) fn spawn(
+    In((room, transform)): In<(RoomId, Transform)>,
+    mut commands: Commands,
+    room_assets: Res<RoomAssets>,
+    definitions: Res<Assets<RoomDefinition>>,
+    room_scenes: Res<RoomScenes>,
+    asset_server: Res<AssetServer>,
+) {
+    let Some(definition) = room.get_definition(&room_assets, &definitions) else {
+        return;
+    };
+    let scene = room_scenes
+        .0
+        .get(&room)
+        .cloned()
+        .unwrap_or_else(|| asset_server.load(&definition.scene));
+    let mut entity_commands = commands.spawn((
+        SceneBundle {
+            scene,
+            transform,
+            ..default()
+        },
+        Name::new(format!("Room {room}")),
+        Imported,
+        GameObject::Room(room),
+        Room,
+    ));
+    if let Some(enemy_budget) = definition.enemy_budget {
+        entity_commands.insert(EnemyBudget(enemy_budget));
+    }
+}
+
+#[derive(Component)]
+pub(crate) struct Imported;
@@ -1,17 +1,48 @@
+use crate::file_system_interaction::level_serialization::ReflectLevelComponent;
 use crate::level_instantiation::spawning::despawn::Despawn;
-use crate::level_instantiation::spawning::objects::intro_room::Imported;
+use crate::level_instantiation::spawning::objects::room::Imported;
+use crate::level_instantiation::spawning::scene_tags::{SceneTag, SceneTags};
+use crate::shader::{Materials, RepeatedMaterial, Repeats};
 use anyhow::{Context, Result};
 use bevy::pbr::NotShadowCaster;
 use bevy::prelude::*;
 use bevy_mod_sysfail::macros::*;
-use regex::Regex;
-use std::sync::LazyLock;
+use serde::{Deserialize, Serialize};
+use std::iter;
 
-pub(crate) fn set_hidden(mut added_name: Query<(&Name, &mut Visibility), Added<Name>>) {
+/// Colors the meshes of a single spawned object like the `[color]` tag does for every instance of a scene.
+/// Saved with the level.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Component, Reflect, FromReflect, Serialize, Deserialize, Default,
+)]
+#[reflect(Component, Serialize, Deserialize, LevelComponent)]
+pub(crate) struct ColorOverride(pub(crate) Color);
+
+/// Repeats the texture of the meshes of a single spawned object like the `[repeat]` tag does for every instance of a scene.
+/// Saved with the level.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Component, Reflect, FromReflect, Serialize, Deserialize,
+)]
+#[reflect(Component, Serialize, Deserialize, LevelComponent)]
+pub(crate) struct RepeatOverride {
+    pub(crate) horizontal: u32,
+    pub(crate) vertical: u32,
+}
+
+impl Default for RepeatOverride {
+    fn default() -> Self {
+        Self {
+            horizontal: 1,
+            vertical: 1,
+        }
+    }
+}
+
+pub(crate) fn set_hidden(mut added_tags: Query<(&SceneTags, &mut Visibility), Added<SceneTags>>) {
     #[cfg(feature = "tracing")]
     let _span = info_span!("set_hidden").entered();
-    for (name, mut visibility) in added_name.iter_mut() {
-        if name.to_lowercase().contains("[hidden]") {
+    for (tags, mut visibility) in added_tags.iter_mut() {
+        if tags.contains(&SceneTag::Hidden) {
             *visibility = Visibility::Hidden;
         }
     }
@@ -19,46 +50,27 @@ pub(crate) fn set_hidden(mut added_name: Query<(&Name, &mut Visibility), Added<N
 
 pub(crate) fn despawn_removed(
     mut commands: Commands,
-    mut added_name: Query<(Entity, &Name), Added<Name>>,
+    added_tags: Query<(Entity, &SceneTags), Added<SceneTags>>,
 ) {
     #[cfg(feature = "tracing")]
     let _span = info_span!("despawn_removed").entered();
-    for (entity, name) in added_name.iter_mut() {
-        if name.to_lowercase().contains("[remove]") {
+    for (entity, tags) in added_tags.iter() {
+        if tags.contains(&SceneTag::Remove) {
             commands.entity(entity).insert(Despawn { recursive: true });
         }
     }
 }
 
-static COLOR_REGEX: LazyLock<Regex> = LazyLock::new(|| {
-    Regex::new(r"\[color:\s*(\d+),\s*(\d+),\s*(\d+),\s*(\d+)\]")
-        .expect("Failed to compile color regex")
-});
-
 #[sysfail(log(level = "error"))]
 pub(crate) fn set_color(
-    added_name: Query<(&Name, &Children), Added<Name>>,
+    added_tags: Query<(&Name, &SceneTags, &Children), Added<SceneTags>>,
     material_handles: Query<&Handle<StandardMaterial>>,
     mut standard_materials: ResMut<Assets<StandardMaterial>>,
 ) -> Result<()> {
     #[cfg(feature = "tracing")]
     let _span = info_span!("set_color").entered();
-    for (name, children) in added_name.iter() {
-        if let Some(captures) = COLOR_REGEX.captures(&name.to_lowercase()) {
-            let color = Color::rgba_u8(
-                captures[1]
-                    .parse()
-                    .with_context(|| format!("Failed to parse r component in color: {}", name))?,
-                captures[2]
-                    .parse()
-                    .with_context(|| format!("Failed to parse g component in color: {}", name))?,
-                captures[3]
-                    .parse()
-                    .with_context(|| format!("Failed to parse b component in color: {}", name))?,
-                captures[4]
-                    .parse()
-                    .with_context(|| format!("Failed to parse a component in color: {}", name))?,
-            );
+    for (name, tags, children) in added_tags.iter() {
+        if let Some(color) = tags.color() {
             let material_handle = children
                 .iter()
                 .filter_map(|entity| material_handles.get(*entity).ok())
@@ -84,7 +96,7 @@ pub(crate) fn set_shadows(
     added_mesh: Query<Entity, Added<Handle<Mesh>>>,
     parent_query: Query<&Parent>,
     imported: Query<&Imported>,
-    names: Query<&Name>,
+    tags: Query<&SceneTags>,
 ) -> Result<()> {
     #[cfg(feature = "tracing")]
     let _span = info_span!("set_shadows").entered();
@@ -99,13 +111,132 @@ pub(crate) fn set_shadows(
         let parent = parent_query
             .get(entity)
             .context("Failed to get parent of added mesh")?;
-        let name = names
+        let casts_shadow = tags
             .get(parent.get())
-            .context("Failed to get name of parent of added mesh")?;
-
-        if !name.contains("[shadow]") {
+            .map(|tags| tags.contains(&SceneTag::Shadow))
+            .unwrap_or_default();
+        if !casts_shadow {
             commands.entity(entity).insert(NotShadowCaster);
         }
     }
     Ok(())
 }
+
+/// The meshes whose material an override of type `T` has to replace, along with the override.
+/// These are the meshes below an object that just got the override, e.g. from its level,
+/// and the meshes that just spawned below an object with the override, e.g. once its scene is instantiated.
+fn get_override_targets<T: Component + Copy>(
+    added_overrides: &Query<Entity, Added<T>>,
+    added_materials: &Query<Entity, Added<Handle<StandardMaterial>>>,
+    overrides: &Query<&T>,
+    children: &Query<&Children>,
+    parents: &Query<&Parent>,
+) -> Vec<(Entity, T)> {
+    let below_added_overrides = added_overrides
+        .iter()
+        .filter_map(|entity| overrides.get(entity).ok().map(|value| (entity, *value)))
+        .flat_map(|(entity, value)| {
+            iter::once(entity)
+                .chain(children.iter_descendants(entity))
+                .map(move |descendant| (descendant, value))
+        });
+    let added_below_overrides = added_materials.iter().filter_map(|entity| {
+        iter::once(entity)
+            .chain(parents.iter_ancestors(entity))
+            .find_map(|ancestor| overrides.get(ancestor).ok())
+            .map(|value| (entity, *value))
+    });
+    below_added_overrides.chain(added_below_overrides).collect()
+}
+
+/// Gives the meshes their own copy of their material, so that other instances of the same scene keep their color.
+#[sysfail(log(level = "error"))]
+pub(crate) fn apply_color_override(
+    mut commands: Commands,
+    added_overrides: Query<Entity, Added<ColorOverride>>,
+    added_materials: Query<Entity, Added<Handle<StandardMaterial>>>,
+    overrides: Query<&ColorOverride>,
+    children: Query<&Children>,
+    parents: Query<&Parent>,
+    material_handles: Query<&Handle<StandardMaterial>>,
+    mut standard_materials: ResMut<Assets<StandardMaterial>>,
+) -> Result<()> {
+    #[cfg(feature = "tracing")]
+    let _span = info_span!("apply_color_override").entered();
+    let targets = get_override_targets(
+        &added_overrides,
+        &added_materials,
+        &overrides,
+        &children,
+        &parents,
+    );
+    for (entity, ColorOverride(color)) in targets {
+        let Ok(material_handle) = material_handles.get(entity) else {
+            continue;
+        };
+        let mut material = standard_materials
+            .get(material_handle)
+            .context("Failed to get standard material from handle")?
+            .clone();
+        material.base_color = color;
+        commands
+            .entity(entity)
+            .insert(standard_materials.add(material));
+    }
+    Ok(())
+}
+
+#[sysfail(log(level = "error"))]
+pub(crate) fn apply_repeat_override(
+    mut commands: Commands,
+    added_overrides: Query<Entity, Added<RepeatOverride>>,
+    added_materials: Query<Entity, Added<Handle<StandardMaterial>>>,
+    overrides: Query<&RepeatOverride>,
+    children: Query<&Children>,
+    parents: Query<&Parent>,
+    material_handles: Query<&Handle<StandardMaterial>>,
+    standard_materials: Res<Assets<StandardMaterial>>,
+    mut materials: ResMut<Materials>,
+    mut repeated_materials: ResMut<Assets<RepeatedMaterial>>,
+) -> Result<()> {
+    #[cfg(feature = "tracing")]
+    let _span = info_span!("apply_repeat_override").entered();
+    let targets = get_override_targets(
+        &added_overrides,
+        &added_materials,
+        &overrides,
+        &children,
+        &parents,
+    );
+    for (entity, repeat) in targets {
+        let Ok(material_handle) = material_handles.get(entity) else {
+            continue;
+        };
+        let standard_material = standard_materials
+            .get(material_handle)
+            .context("Failed to get standard material from handle")?;
+        let texture = standard_material
+            .base_color_texture
+            .as_ref()
+            .context("Failed to get texture from standard material. Is the texture missing?")?;
+        let repeats = Repeats {
+            horizontal: repeat.horizontal,
+            vertical: repeat.vertical,
+            ..default()
+        };
+        let repeated_material = materials
+            .repeated
+            .entry((texture.id(), repeats))
+            .or_insert_with(|| {
+                repeated_materials.add(RepeatedMaterial {
+                    texture: texture.clone(),
+                    repeats,
+                })
+            });
+        commands
+            .entity(entity)
+            .remove::<Handle<StandardMaterial>>()
+            .insert(repeated_material.clone());
+    }
+    Ok(())
+}
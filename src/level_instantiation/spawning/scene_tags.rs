@@ -0,0 +1,377 @@
+use crate::combat::archetype::EnemyParameters;
+use anyhow::{anyhow, bail, Context, Result};
+use bevy::gltf::GltfExtras;
+use bevy::prelude::*;
+use bevy::scene::scene_spawner_system;
+use bevy_rapier3d::prelude::*;
+use regex::Regex;
+use serde_json::Value;
+use std::sync::LazyLock;
+
+/// Reads the tags in node names and glTF extras once they are spawned, so that later systems can rely on [`SceneTags`] being there.
+pub(crate) fn scene_tags_plugin(app: &mut App) {
+    app.add_systems(
+        (read_scene_tags, apply_system_buffers)
+            .chain()
+            .after(scene_spawner_system)
+            .in_set(SceneTagSystemSet),
+    );
+}
+
+#[derive(Debug, Hash, PartialEq, Eq, Clone, SystemSet)]
+pub(crate) struct SceneTagSystemSet;
+
+/// The tags in square brackets in the name of a glTF node, e.g. `Wall [collider] [color: 255, 0, 0, 255]`.
+/// The same tags can be set as custom properties in Blender, which are exported as glTF extras:
+/// `collider: "convex"`, `light: 800`, `color: [255, 0, 0, 255]` or `hidden: true`.
+/// `enemy` also takes an object like `{"name": "dummy", "level": 3}`.
+/// Only present on entities with at least one valid tag.
+#[derive(Debug, Clone, PartialEq, Component, Default)]
+pub(crate) struct SceneTags(Vec<SceneTag>);
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum SceneTag {
+    /// `[collider]` or `[collider: <kind>]`: The meshes below get a collider.
+    Collider(ColliderKind),
+    /// `[grass]` or `[grass: <blades per square meter>]`: Grass grows on the meshes below.
+    Grass(Option<f32>),
+    /// `[hidden]`
+    Hidden,
+    /// `[remove]`: Despawned right after spawning, for helpers that only exist in the editor.
+    Remove,
+    /// `[shadow]`: The meshes below cast shadows, which imported meshes do not by default.
+    Shadow,
+    /// `[color: r, g, b, a]` with every component in 0-255.
+    Color(Color),
+    /// `[repeat: horizontal, vertical]`: How often the texture repeats across the mesh.
+    Repeat { horizontal: u32, vertical: u32 },
+    /// `[entrance]`: Where the player enters the room.
+    Entrance,
+    /// `[exit]`: Where the player leaves the room.
+    Exit,
+    /// `[light]` or `[light: <intensity>]`: Spawns a point light.
+    Light(Option<f32>),
+    /// `[enemy: <name>, <key>: <value>, ...]`
+    Enemy(EnemyParameters),
+    /// `[hitbox]`: The mesh whose bounding box is used as the hitbox of a model's attacks.
+    Hitbox,
+    /// `[hitbox-bone]`: The bone the hitbox follows, if not the hitbox mesh itself.
+    HitboxBone,
+    /// `[spawn: <id>]`: Where waves can spawn enemies.
+    Spawn(String),
+    /// `[trigger: <id>]`: A sensor built from the meshes below, or spanning -1 to 1 on every local axis if there are none.
+    Trigger(String),
+    /// `[dialog: <id>]`: The node gets a [`DialogTarget`](crate::world_interaction::dialog::DialogTarget) for `dialogs/<id>.dlg.ron`.
+    Dialog(String),
+    /// `[sign: <text>]`: The player can read the text.
+    Sign(String),
+    /// `[item: <condition>]`: The player can pick the node up, which adds the condition.
+    Item(String),
+    /// `[door]`: The player can open the door, which removes it.
+    Door,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) enum ColliderKind {
+    /// `trimesh`: Exactly the shape of the mesh, only for static geometry.
+    #[default]
+    TriMesh,
+    /// `convex`: The convex hull of the mesh.
+    ConvexHull,
+    /// `decomposed`: Several convex hulls approximating the mesh.
+    ConvexDecomposition,
+}
+
+impl ColliderKind {
+    pub(crate) fn shape(self) -> ComputedColliderShape {
+        match self {
+            ColliderKind::TriMesh => ComputedColliderShape::TriMesh,
+            ColliderKind::ConvexHull => ComputedColliderShape::ConvexHull,
+            ColliderKind::ConvexDecomposition => {
+                ComputedColliderShape::ConvexDecomposition(default())
+            }
+        }
+    }
+}
+
+impl SceneTags {
+    pub(crate) fn contains(&self, tag: &SceneTag) -> bool {
+        self.0.contains(tag)
+    }
+
+    pub(crate) fn collider(&self) -> Option<ColliderKind> {
+        self.0.iter().find_map(|tag| match tag {
+            SceneTag::Collider(kind) => Some(*kind),
+            _ => None,
+        })
+    }
+
+    /// The density of the grass, if one was given.
+    pub(crate) fn grass(&self) -> Option<Option<f32>> {
+        self.0.iter().find_map(|tag| match tag {
+            SceneTag::Grass(density) => Some(*density),
+            _ => None,
+        })
+    }
+
+    /// The intensity of the light, if one was given.
+    pub(crate) fn light(&self) -> Option<Option<f32>> {
+        self.0.iter().find_map(|tag| match tag {
+            SceneTag::Light(intensity) => Some(*intensity),
+            _ => None,
+        })
+    }
+
+    pub(crate) fn color(&self) -> Option<Color> {
+        self.0.iter().find_map(|tag| match tag {
+            SceneTag::Color(color) => Some(*color),
+            _ => None,
+        })
+    }
+
+    pub(crate) fn repeat(&self) -> Option<(u32, u32)> {
+        self.0.iter().find_map(|tag| match tag {
+            SceneTag::Repeat {
+                horizontal,
+                vertical,
+            } => Some((*horizontal, *vertical)),
+            _ => None,
+        })
+    }
+
+    pub(crate) fn enemy(&self) -> Option<&EnemyParameters> {
+        self.0.iter().find_map(|tag| match tag {
+            SceneTag::Enemy(parameters) => Some(parameters),
+            _ => None,
+        })
+    }
+
+    pub(crate) fn spawn_point(&self) -> Option<&str> {
+        self.0.iter().find_map(|tag| match tag {
+            SceneTag::Spawn(id) => Some(id.as_str()),
+            _ => None,
+        })
+    }
+
+    pub(crate) fn trigger(&self) -> Option<&str> {
+        self.0.iter().find_map(|tag| match tag {
+            SceneTag::Trigger(id) => Some(id.as_str()),
+            _ => None,
+        })
+    }
+
+    pub(crate) fn dialog(&self) -> Option<&str> {
+        self.0.iter().find_map(|tag| match tag {
+            SceneTag::Dialog(id) => Some(id.as_str()),
+            _ => None,
+        })
+    }
+
+    pub(crate) fn sign(&self) -> Option<&str> {
+        self.0.iter().find_map(|tag| match tag {
+            SceneTag::Sign(text) => Some(text.as_str()),
+            _ => None,
+        })
+    }
+
+    pub(crate) fn item(&self) -> Option<&str> {
+        self.0.iter().find_map(|tag| match tag {
+            SceneTag::Item(condition) => Some(condition.as_str()),
+            _ => None,
+        })
+    }
+
+    pub(crate) fn is_door(&self) -> bool {
+        self.contains(&SceneTag::Door)
+    }
+}
+
+static TAG_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\[([^\[\]]*)\]").expect("Failed to compile tag regex"));
+
+static KEY_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^\s*([\w-]+)\s*(?::(.*))?$").expect("Failed to compile tag key regex")
+});
+
+static ID_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^[\w-]+$").expect("Failed to compile tag id regex"));
+
+fn read_scene_tags(
+    mut commands: Commands,
+    added: Query<
+        (Entity, Option<&Name>, Option<&GltfExtras>),
+        Or<(Added<Name>, Added<GltfExtras>)>,
+    >,
+    names: Query<&Name>,
+    parents: Query<&Parent>,
+) {
+    #[cfg(feature = "tracing")]
+    let _span = info_span!("read_scene_tags").entered();
+    for (entity, name, extras) in added.iter() {
+        let mut tags = Vec::new();
+        for captures in name
+            .into_iter()
+            .flat_map(|name| TAG_REGEX.captures_iter(name))
+        {
+            match parse_tag(&captures[1]) {
+                Ok(tag) => tags.push(tag),
+                Err(e) => error!(
+                    "Invalid tag \"{}\" in node \"{}\": {e:#}",
+                    &captures[0],
+                    get_node_path(entity, &names, &parents)
+                ),
+            }
+        }
+        let extras = extras.map(|extras| read_extras(&extras.value));
+        for extra in extras.into_iter().flatten() {
+            match extra {
+                Ok(Some(tag)) => tags.push(tag),
+                Ok(None) => {}
+                Err(e) => error!(
+                    "Invalid custom property in node \"{}\": {e:#}",
+                    get_node_path(entity, &names, &parents)
+                ),
+            }
+        }
+        if !tags.is_empty() {
+            commands.entity(entity).insert(SceneTags(tags));
+        }
+    }
+}
+
+fn parse_tag(tag: &str) -> Result<SceneTag> {
+    let captures = KEY_REGEX
+        .captures(tag)
+        .context("Expected `[<name>]` or `[<name>: <arguments>]`")?;
+    let key = captures[1].to_lowercase();
+    let arguments = captures.get(2).map(|arguments| arguments.as_str().trim());
+    let unit = |tag: SceneTag| match arguments {
+        None => Ok(tag),
+        Some(_) => bail!("`[{key}]` takes no arguments"),
+    };
+    let get_arguments = || arguments.with_context(|| format!("`[{key}]` needs arguments"));
+    match key.as_str() {
+        "collider" => Ok(SceneTag::Collider(match arguments {
+            None | Some("trimesh") => ColliderKind::TriMesh,
+            Some("convex") => ColliderKind::ConvexHull,
+            Some("decomposed") => ColliderKind::ConvexDecomposition,
+            Some(kind) => {
+                bail!("Unknown collider kind \"{kind}\", use `trimesh`, `convex` or `decomposed`")
+            }
+        })),
+        "grass" => Ok(SceneTag::Grass(arguments.map(parse_number).transpose()?)),
+        "hidden" => unit(SceneTag::Hidden),
+        "remove" => unit(SceneTag::Remove),
+        "shadow" => unit(SceneTag::Shadow),
+        "entrance" => unit(SceneTag::Entrance),
+        "exit" => unit(SceneTag::Exit),
+        "light" => Ok(SceneTag::Light(arguments.map(parse_number).transpose()?)),
+        "hitbox" => unit(SceneTag::Hitbox),
+        "hitbox-bone" => unit(SceneTag::HitboxBone),
+        "color" => {
+            let [r, g, b, a] = parse_numbers(get_arguments()?)?;
+            Ok(SceneTag::Color(Color::rgba_u8(r, g, b, a)))
+        }
+        "repeat" => {
+            let [horizontal, vertical] = parse_numbers(get_arguments()?)?;
+            Ok(SceneTag::Repeat {
+                horizontal,
+                vertical,
+            })
+        }
+        "enemy" => Ok(SceneTag::Enemy(EnemyParameters::parse(get_arguments()?)?)),
+        "spawn" => Ok(SceneTag::Spawn(parse_id(get_arguments()?)?)),
+        "trigger" => Ok(SceneTag::Trigger(parse_id(get_arguments()?)?)),
+        "dialog" => Ok(SceneTag::Dialog(parse_id(get_arguments()?)?)),
+        "sign" => Ok(SceneTag::Sign(get_arguments()?.to_string())),
+        "item" => Ok(SceneTag::Item(parse_id(get_arguments()?)?)),
+        "door" => unit(SceneTag::Door),
+        _ => bail!("Unknown tag"),
+    }
+}
+
+/// Turns every glTF extra into the tag it stands for, e.g. `"color": [255, 0, 0, 255]` into `[color: 255, 0, 0, 255]`.
+/// Returns `None` for extras that are switched off, like `"hidden": false`.
+fn read_extras(extras: &str) -> Vec<Result<Option<SceneTag>>> {
+    let extras = match serde_json::from_str::<serde_json::Map<String, Value>>(extras) {
+        Ok(extras) => extras,
+        Err(e) => return vec![Err(e).context("Failed to read glTF extras as a JSON object")],
+    };
+    extras
+        .iter()
+        .map(|(key, value)| {
+            let tag = match value {
+                Value::Bool(false) | Value::Null => return Ok(None),
+                Value::Bool(true) => key.clone(),
+                Value::Object(object) => {
+                    let name = object
+                        .get("name")
+                        .map(format_extra)
+                        .with_context(|| format!("`{key}` needs a `name`"))?;
+                    let parameters = object
+                        .iter()
+                        .filter(|(parameter, _)| parameter.as_str() != "name")
+                        .map(|(parameter, value)| format!("{parameter}: {}", format_extra(value)));
+                    let arguments: Vec<_> = std::iter::once(name).chain(parameters).collect();
+                    format!("{key}: {}", arguments.join(", "))
+                }
+                _ => format!("{key}: {}", format_extra(value)),
+            };
+            parse_tag(&tag)
+                .map(Some)
+                .with_context(|| format!("Failed to read `{key}: {value}`"))
+        })
+        .collect()
+}
+
+fn format_extra(value: &Value) -> String {
+    match value {
+        Value::String(string) => string.clone(),
+        Value::Array(values) => values
+            .iter()
+            .map(format_extra)
+            .collect::<Vec<_>>()
+            .join(", "),
+        _ => value.to_string(),
+    }
+}
+
+fn parse_number<T: std::str::FromStr>(number: &str) -> Result<T> {
+    let number = number.trim();
+    number
+        .parse()
+        .map_err(|_| anyhow!("Failed to parse \"{number}\" as a number"))
+}
+
+fn parse_numbers<T: std::str::FromStr, const N: usize>(arguments: &str) -> Result<[T; N]> {
+    let numbers = arguments
+        .split(',')
+        .map(parse_number)
+        .collect::<Result<Vec<_>>>()?;
+    let count = numbers.len();
+    numbers
+        .try_into()
+        .map_err(|_| anyhow!("Expected {N} numbers, got {count}"))
+}
+
+fn parse_id(arguments: &str) -> Result<String> {
+    if !ID_REGEX.is_match(arguments) {
+        bail!("\"{arguments}\" is not a valid id, use only letters, digits, `_` and `-`");
+    }
+    Ok(arguments.to_string())
+}
+
+/// Names of the entity and its ancestors, e.g. `Room room_one/Walls/Wall [collider]`.
+pub(crate) fn get_node_path(
+    entity: Entity,
+    names: &Query<&Name>,
+    parents: &Query<&Parent>,
+) -> String {
+    let mut path: Vec<_> = std::iter::once(entity)
+        .chain(parents.iter_ancestors(entity))
+        .filter_map(|entity| names.get(entity).ok())
+        .map(|name| name.as_str())
+        .collect();
+    path.reverse();
+    path.join("/")
+}
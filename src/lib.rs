@@ -23,6 +23,7 @@ pub(crate) mod dev;
 pub(crate) mod file_system_interaction;
 pub(crate) mod ingame_menu;
 pub(crate) mod level_instantiation;
+pub(crate) mod localization;
 pub(crate) mod menu;
 pub(crate) mod movement;
 #[cfg(feature = "native")]
@@ -40,6 +41,7 @@ use crate::dev::dev_plugin;
 use crate::file_system_interaction::file_system_interaction_plugin;
 use crate::ingame_menu::ingame_menu_plugin;
 use crate::level_instantiation::level_instantiation_plugin;
+use crate::localization::localization_plugin;
 use crate::menu::menu_plugin;
 use crate::movement::movement_plugin;
 #[cfg(feature = "native")]
@@ -71,6 +73,7 @@ enum GameState {
 /// - [`world_interaction_plugin`]: Handles the interaction of entities with the world.
 /// - [`level_instantiation_plugin`]: Handles the creation of levels and objects.
 /// - [`file_system_interaction_plugin`]: Handles the loading and saving of games.
+/// - [`localization_plugin`]: Handles the translation of text shown to the player.
 /// - [`shader_plugin`]: Handles the shaders.
 /// - [`dev_plugin`]: Handles the dev tools.
 /// - [`ingame_menu_plugin`]: Handles the ingame menu accessed via ESC.
@@ -99,6 +102,7 @@ impl Plugin for GamePlugin {
             .fn_plugin(world_interaction_plugin)
             .fn_plugin(level_instantiation_plugin)
             .fn_plugin(file_system_interaction_plugin)
+            .fn_plugin(localization_plugin)
             .fn_plugin(shader_plugin)
             .fn_plugin(ai_plugin)
             .fn_plugin(ingame_menu_plugin);
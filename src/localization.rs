@@ -0,0 +1,280 @@
+use crate::file_system_interaction::asset_loading::LocaleAssets;
+use crate::GameState;
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use bevy::reflect::TypeUuid;
+use bevy::utils::{HashMap, HashSet};
+use bevy_egui::egui;
+use serde::de::{self, MapAccess, SeqAccess, Visitor};
+use serde::ser::SerializeMap;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::sync::{LazyLock, Mutex};
+
+/// Handles the translation of all text shown to the player.
+/// The strings of every language live in `assets/locales/<language>.strings.ron`,
+/// the language in use is set by [`Locale`] and can be changed at any time.
+pub(crate) fn localization_plugin(app: &mut App) {
+    app.register_type::<Locale>().init_resource::<Locale>();
+    #[cfg(feature = "dev")]
+    app.add_system(report_missing_translations.in_schedule(OnExit(GameState::Loading)));
+}
+
+/// Used for every key the current language does not translate.
+pub(crate) const FALLBACK_LANGUAGE: &str = "en";
+
+#[derive(Debug, Clone, Eq, PartialEq, Resource, Reflect, Serialize, Deserialize)]
+#[reflect(Resource, Serialize, Deserialize)]
+pub(crate) struct Locale {
+    /// The name of a file in `assets/locales`, e.g. `de` for `de.strings.ron`.
+    pub(crate) language: String,
+}
+
+impl Default for Locale {
+    fn default() -> Self {
+        Self {
+            language: FALLBACK_LANGUAGE.to_string(),
+        }
+    }
+}
+
+/// Maps keys like `menu.play` to the text of one language.
+/// Text can contain placeholders like `{room-number}`, see [`Localization::format`].
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, TypeUuid, Default)]
+#[uuid = "8b1c6e0a-4f2d-4c7b-9a3e-51d7f0c2b6e4"]
+#[serde(transparent)]
+pub(crate) struct StringTable(pub(crate) HashMap<String, String>);
+
+/// Keys that were already reported as missing, so that UI drawn every frame does not flood the log.
+static REPORTED_KEYS: LazyLock<Mutex<HashSet<String>>> = LazyLock::new(default);
+
+/// Looks up text in the [`StringTable`] of the current [`Locale`].
+#[derive(SystemParam)]
+pub(crate) struct Localization<'w> {
+    locale: Res<'w, Locale>,
+    locale_assets: Res<'w, LocaleAssets>,
+    string_tables: Res<'w, Assets<StringTable>>,
+}
+
+impl<'w> Localization<'w> {
+    fn get_table(&self, language: &str) -> Option<&StringTable> {
+        let handle = self.locale_assets.locales.get(&get_locale_path(language))?;
+        self.string_tables.get(handle)
+    }
+
+    /// The text for the key in the current language, falling back to [`FALLBACK_LANGUAGE`] and then to the key itself.
+    pub(crate) fn get(&self, key: &str) -> String {
+        let text = [self.locale.language.as_str(), FALLBACK_LANGUAGE]
+            .into_iter()
+            .filter_map(|language| self.get_table(language))
+            .find_map(|table| table.0.get(key));
+        match text {
+            Some(text) => text.clone(),
+            None => {
+                report_missing_key(key);
+                key.to_string()
+            }
+        }
+    }
+
+    /// Like [`Localization::get`], but replaces placeholders like `{name}` with the given arguments.
+    pub(crate) fn format(&self, key: &str, arguments: &[(&str, &dyn fmt::Display)]) -> String {
+        arguments.iter().fold(self.get(key), |text, (name, value)| {
+            text.replace(&format!("{{{name}}}"), &value.to_string())
+        })
+    }
+
+    pub(crate) fn text(&self, text: &LocalizedText) -> String {
+        match text {
+            LocalizedText::Literal(text) => text.clone(),
+            LocalizedText::Key(key) => self.get(key),
+            LocalizedText::Translations(translations) => translations
+                .get(&self.locale.language)
+                .or_else(|| translations.get(FALLBACK_LANGUAGE))
+                .cloned()
+                .unwrap_or_else(|| {
+                    report_missing_key(&format!("{translations:?}"));
+                    String::new()
+                }),
+        }
+    }
+
+    /// The available languages and their names in that language, sorted by name.
+    pub(crate) fn languages(&self) -> Vec<(String, String)> {
+        let mut languages: Vec<_> = self
+            .locale_assets
+            .locales
+            .keys()
+            .filter_map(|path| get_language(path))
+            .map(|language| {
+                let name = self
+                    .get_table(language)
+                    .and_then(|table| table.0.get("language.name"))
+                    .cloned()
+                    .unwrap_or_else(|| language.to_string());
+                (language.to_string(), name)
+            })
+            .collect();
+        languages.sort_by(|(_, a), (_, b)| a.cmp(b));
+        languages
+    }
+}
+
+/// Shows a dropdown to change the [`Locale`].
+/// The new locale is inserted through `commands`, since [`Localization`] already borrows the current one.
+pub(crate) fn show_language_selection(
+    ui: &mut egui::Ui,
+    commands: &mut Commands,
+    localization: &Localization,
+) {
+    let languages = localization.languages();
+    let current_language = &localization.locale.language;
+    let current_name = languages
+        .iter()
+        .find(|(language, _)| language == current_language)
+        .map_or(current_language.as_str(), |(_, name)| name.as_str());
+    let mut selected_language = current_language.clone();
+    egui::ComboBox::from_label(localization.get("menu.language"))
+        .selected_text(current_name)
+        .show_ui(ui, |ui| {
+            for (language, name) in languages.iter() {
+                ui.selectable_value(&mut selected_language, language.clone(), name);
+            }
+        });
+    if selected_language != *current_language {
+        commands.insert_resource(Locale {
+            language: selected_language,
+        });
+    }
+}
+
+fn get_locale_path(language: &str) -> String {
+    format!("locales/{language}.strings.ron")
+}
+
+fn get_language(path: &str) -> Option<&str> {
+    path.strip_prefix("locales/")?.strip_suffix(".strings.ron")
+}
+
+fn report_missing_key(key: &str) {
+    let mut reported_keys = REPORTED_KEYS.lock().expect("Failed to lock reported keys");
+    if reported_keys.insert(key.to_string()) {
+        error!("Missing text for {key} in the string tables");
+    }
+}
+
+/// Reports keys of the fallback language that another language does not translate.
+#[cfg(feature = "dev")]
+fn report_missing_translations(
+    locale_assets: Res<LocaleAssets>,
+    string_tables: Res<Assets<StringTable>>,
+) {
+    let Some(fallback) = locale_assets
+        .locales
+        .get(&get_locale_path(FALLBACK_LANGUAGE))
+        .and_then(|handle| string_tables.get(handle)) else {
+        error!("Missing string table for the fallback language \"{FALLBACK_LANGUAGE}\"");
+        return;
+    };
+    for (path, handle) in locale_assets.locales.iter() {
+        let Some(table) = string_tables.get(handle) else {
+            continue;
+        };
+        let mut missing_keys: Vec<_> = fallback
+            .0
+            .keys()
+            .filter(|key| !table.0.contains_key(*key))
+            .collect();
+        missing_keys.sort();
+        for key in missing_keys {
+            warn!("Missing translation for \"{key}\" in {path}");
+        }
+    }
+}
+
+/// Text in a `.ron` asset. Either written as is, `Key("<key>")` to look it up in the [`StringTable`]s,
+/// or `Translations({"<language>": "<text>", ...})` to keep the text of every language right there.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum LocalizedText {
+    Literal(String),
+    Key(String),
+    Translations(HashMap<String, String>),
+}
+
+impl Default for LocalizedText {
+    fn default() -> Self {
+        Self::Literal(String::new())
+    }
+}
+
+impl Serialize for LocalizedText {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            LocalizedText::Literal(text) => serializer.serialize_str(text),
+            LocalizedText::Key(key) => {
+                serializer.serialize_newtype_variant("LocalizedText", 1, "Key", key)
+            }
+            LocalizedText::Translations(translations) => serializer.serialize_newtype_variant(
+                "LocalizedText",
+                2,
+                "Translations",
+                &SortedTranslations(translations),
+            ),
+        }
+    }
+}
+
+/// Keeps serialized translations in a stable order.
+struct SortedTranslations<'a>(&'a HashMap<String, String>);
+
+impl<'a> Serialize for SortedTranslations<'a> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut translations: Vec<_> = self.0.iter().collect();
+        translations.sort();
+        let mut map = serializer.serialize_map(Some(translations.len()))?;
+        for (language, text) in translations {
+            map.serialize_entry(language, text)?;
+        }
+        map.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for LocalizedText {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(LocalizedTextVisitor)
+    }
+}
+
+/// RON hands `Key(...)` and `Translations(...)` over as a tuple without the name,
+/// so they are told apart by whether they contain a string or a map.
+struct LocalizedTextVisitor;
+
+impl<'de> Visitor<'de> for LocalizedTextVisitor {
+    type Value = LocalizedText;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter
+            .write_str("a string, `Key(\"<key>\")` or `Translations({\"<language>\": \"<text>\"})`")
+    }
+
+    fn visit_str<E: de::Error>(self, text: &str) -> Result<Self::Value, E> {
+        Ok(LocalizedText::Literal(text.to_string()))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        seq.next_element::<LocalizedText>()?
+            .map(|text| match text {
+                LocalizedText::Literal(key) => LocalizedText::Key(key),
+                translations => translations,
+            })
+            .ok_or_else(|| de::Error::invalid_length(0, &self))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut translations = HashMap::new();
+        while let Some((language, text)) = map.next_entry()? {
+            translations.insert(language, text);
+        }
+        Ok(LocalizedText::Translations(translations))
+    }
+}
@@ -1,3 +1,8 @@
+use crate::file_system_interaction::game_state_serialization::{
+    GameLoadRequest, SaveSlotAction, SaveSlots, SaveSlotsUi,
+};
+use crate::file_system_interaction::user_dirs::UserDirs;
+use crate::localization::{show_language_selection, Localization};
 use crate::GameState;
 use bevy::prelude::*;
 use bevy_egui::egui::FontFamily::Proportional;
@@ -12,12 +17,20 @@ pub(crate) fn menu_plugin(app: &mut App) {
 }
 
 fn setup_menu(
+    mut commands: Commands,
     mut egui_contexts: EguiContexts,
     mut next_state: ResMut<NextState<GameState>>,
+    mut save_slots: ResMut<SaveSlots>,
+    mut save_slots_ui: Local<SaveSlotsUi>,
+    user_dirs: Res<UserDirs>,
+    mut show_save_slots: Local<bool>,
+    mut load_requests: EventWriter<GameLoadRequest>,
     _asset_server: ResMut<AssetServer>,
     _audio: Res<Audio>,
     _audio_sinks: Res<Assets<AudioSink>>,
+    localization: Localization,
 ) {
+    let mut save_slot_action = None;
     get_menu_panel().show(egui_contexts.ctx_mut(), |ui| {
         set_menu_style(ui.style_mut());
         ui.vertical_centered_justified(|ui| {
@@ -25,11 +38,34 @@ fn setup_menu(
             ui.heading("egg slash");
             ui.separator();
             ui.add_space(50.);
-            if ui.button("Play").clicked() {
+            if ui.button(localization.get("menu.play")).clicked() {
                 next_state.set(GameState::Playing);
             }
+            if save_slots.newest().is_some()
+                && ui.button(localization.get("menu.continue")).clicked()
+            {
+                load_requests.send(GameLoadRequest { filename: None });
+                next_state.set(GameState::Playing);
+            }
+            if ui.button(localization.get("menu.load")).clicked() {
+                *show_save_slots = !*show_save_slots;
+            }
+            if *show_save_slots {
+                ui.add_space(20.);
+                save_slot_action = save_slots_ui.show(ui, &save_slots, false, &localization);
+            }
+            ui.add_space(20.);
+            show_language_selection(ui, &mut commands, &localization);
         })
     });
+    if let Some(action) = save_slot_action
+        && let Some(SaveSlotAction::Load(name)) = save_slots_ui.apply(action, &mut save_slots, &user_dirs)
+    {
+        load_requests.send(GameLoadRequest {
+            filename: Some(name),
+        });
+        next_state.set(GameState::Playing);
+    }
     //let music = asset_server.load("audio/menu.ogg");
     //audio_sinks.han
     //audio.play(music);
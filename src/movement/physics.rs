@@ -1,3 +1,4 @@
+use crate::level_instantiation::spawning::scene_tags::SceneTags;
 use crate::util::trait_extension::MeshExt;
 use crate::GameState;
 use anyhow::{Context, Result};
@@ -23,20 +24,20 @@ pub(crate) fn physics_plugin(app: &mut App) {
 #[sysfail(log(level = "error"))]
 pub(crate) fn read_colliders(
     mut commands: Commands,
-    added_name: Query<(Entity, &Name), Added<Name>>,
+    added_tags: Query<(Entity, &SceneTags), Added<SceneTags>>,
     children: Query<&Children>,
     meshes: Res<Assets<Mesh>>,
     mesh_handles: Query<&Handle<Mesh>>,
 ) -> Result<()> {
     #[cfg(feature = "tracing")]
     let _span = info_span!("read_colliders").entered();
-    for (entity, name) in &added_name {
-        if name.to_lowercase().contains("[collider]") {
+    for (entity, tags) in &added_tags {
+        if let Some(collider_kind) = tags.collider() {
             for (collider_entity, collider_mesh) in
                 Mesh::search_in_children(entity, &children, &meshes, &mesh_handles)
             {
                 let rapier_collider =
-                    Collider::from_bevy_mesh(collider_mesh, &ComputedColliderShape::TriMesh)
+                    Collider::from_bevy_mesh(collider_mesh, &collider_kind.shape())
                         .context("Failed to create collider from mesh")?;
 
                 commands
@@ -1,9 +1,12 @@
+use crate::ai::generic::projectile::behavior::ProjectileImpactEvent;
 use crate::file_system_interaction::config::GameConfig;
 use crate::level_instantiation::spawning::objects::player;
 use crate::movement::general_movement::Grounded;
 use crate::particles::init::init_effects;
 use crate::util::trait_extension::{F32Ext, Vec3Ext};
+use crate::world_interaction::room::WaveSpawnEvent;
 use crate::GameState;
+use bevy::pbr::NotShadowReceiver;
 use bevy::prelude::*;
 use bevy_hanabi::prelude::*;
 use bevy_rapier3d::prelude::*;
@@ -13,15 +16,37 @@ mod init;
 /// Handles particle effects instantiation and playing.
 pub(crate) fn particle_plugin(app: &mut App) {
     app.register_type::<SprintingParticle>()
+        .register_type::<ProjectileImpactParticle>()
+        .register_type::<WaveSpawnParticle>()
         .add_plugin(HanabiPlugin)
         .add_system(init_effects.in_schedule(OnExit(GameState::Loading)))
-        .add_system(play_sprinting_effect.in_set(OnUpdate(GameState::Playing)));
+        .add_systems(
+            (
+                play_sprinting_effect,
+                play_projectile_impact_effect,
+                play_wave_spawn_effect,
+                despawn_finished_wave_spawn_effects,
+            )
+                .in_set(OnUpdate(GameState::Playing)),
+        );
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, Component, Reflect, Default)]
 #[reflect(Component)]
 struct SprintingParticle;
 
+#[derive(Debug, Clone, Eq, PartialEq, Component, Reflect, Default)]
+#[reflect(Component)]
+struct ProjectileImpactParticle;
+
+/// Several enemies can spawn at once, so every spawn gets its own effect that is despawned once the timer finished.
+#[derive(Debug, Clone, PartialEq, Component, Reflect, Default)]
+#[reflect(Component)]
+struct WaveSpawnParticle(Timer);
+
+#[derive(Debug, Clone, Resource)]
+struct WaveSpawnEffect(Handle<EffectAsset>);
+
 fn play_sprinting_effect(
     with_player: Query<(&Transform, &Grounded, &Velocity), Without<SprintingParticle>>,
     mut with_particle: Query<(&mut Transform, &mut ParticleEffect), With<SprintingParticle>>,
@@ -46,3 +71,47 @@ fn play_sprinting_effect(
         }
     }
 }
+
+fn play_projectile_impact_effect(
+    mut impact_events: EventReader<ProjectileImpactEvent>,
+    mut with_particle: Query<(&mut Transform, &mut ParticleEffect), With<ProjectileImpactParticle>>,
+) {
+    for event in impact_events.iter() {
+        for (mut particle_transform, mut effect) in with_particle.iter_mut() {
+            *particle_transform = Transform::from_translation(event.location)
+                .looking_to(event.normal.any_orthonormal_vector(), event.normal);
+            effect.maybe_spawner().unwrap().reset();
+        }
+    }
+}
+
+fn play_wave_spawn_effect(
+    mut commands: Commands,
+    mut wave_spawn_events: EventReader<WaveSpawnEvent>,
+    wave_spawn_effect: Res<WaveSpawnEffect>,
+) {
+    for event in wave_spawn_events.iter() {
+        commands.spawn((
+            Name::new("Wave spawn particle"),
+            WaveSpawnParticle(Timer::from_seconds(1.5, TimerMode::Once)),
+            ParticleEffectBundle {
+                effect: ParticleEffect::new(wave_spawn_effect.0.clone()),
+                transform: Transform::from_translation(event.location),
+                ..default()
+            },
+            NotShadowReceiver,
+        ));
+    }
+}
+
+fn despawn_finished_wave_spawn_effects(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut particles: Query<(Entity, &mut WaveSpawnParticle)>,
+) {
+    for (entity, mut particle) in particles.iter_mut() {
+        if particle.0.tick(time.delta()).finished() {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
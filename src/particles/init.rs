@@ -1,5 +1,5 @@
 use crate::level_instantiation::spawning::objects::player;
-use crate::particles::SprintingParticle;
+use crate::particles::{ProjectileImpactParticle, SprintingParticle, WaveSpawnEffect};
 use bevy::pbr::NotShadowReceiver;
 use bevy::prelude::*;
 use bevy_hanabi::prelude::*;
@@ -15,6 +15,19 @@ pub(crate) fn init_effects(mut commands: Commands, mut effects: ResMut<Assets<Ef
         },
         NotShadowReceiver,
     ));
+
+    let projectile_impact = create_projectile_impact_effect(&mut effects);
+    commands.spawn((
+        Name::new("Projectile impact particle"),
+        ProjectileImpactParticle,
+        ParticleEffectBundle {
+            effect: projectile_impact,
+            ..default()
+        },
+        NotShadowReceiver,
+    ));
+
+    commands.insert_resource(WaveSpawnEffect(create_wave_spawn_effect(&mut effects)));
 }
 
 fn create_sprinting_effect(effects: &mut Assets<EffectAsset>) -> ParticleEffect {
@@ -63,3 +76,87 @@ fn create_sprinting_effect(effects: &mut Assets<EffectAsset>) -> ParticleEffect
         ),
     )
 }
+
+fn create_projectile_impact_effect(effects: &mut Assets<EffectAsset>) -> ParticleEffect {
+    let mut color_gradient = Gradient::new();
+    color_gradient.add_key(0.0, Vec4::new(4.0, 3.0, 1.5, 1.0));
+    color_gradient.add_key(0.5, Vec4::new(2.0, 1.2, 0.6, 0.6));
+    color_gradient.add_key(1.0, Vec4::new(1.0, 0.6, 0.3, 0.0));
+
+    let mut size_gradient = Gradient::new();
+    size_gradient.add_key(0.0, Vec2::splat(0.05));
+    size_gradient.add_key(1.0, Vec2::splat(0.01));
+
+    ParticleEffect::new(
+        effects.add(
+            EffectAsset {
+                name: "Projectile impact".to_string(),
+                capacity: 64,
+                spawner: Spawner::once(16.0.into(), false),
+                ..Default::default()
+            }
+            .init(InitPositionSphereModifier {
+                center: Vec3::ZERO,
+                radius: 0.05,
+                dimension: ShapeDimension::Volume,
+            })
+            .init(InitVelocitySphereModifier {
+                speed: 3_f32.into(),
+                center: Vec3::ZERO,
+            })
+            .init(InitLifetimeModifier {
+                lifetime: 0.3.into(),
+            })
+            .update(LinearDragModifier { drag: 4. })
+            .update(AccelModifier::constant(Vec3::new(0., -9.81, 0.)))
+            .render(BillboardModifier {})
+            .render(ColorOverLifetimeModifier {
+                gradient: color_gradient,
+            })
+            .render(SizeOverLifetimeModifier {
+                gradient: size_gradient,
+            }),
+        ),
+    )
+}
+
+fn create_wave_spawn_effect(effects: &mut Assets<EffectAsset>) -> Handle<EffectAsset> {
+    let mut color_gradient = Gradient::new();
+    color_gradient.add_key(0.0, Vec4::new(2.0, 0.8, 3.0, 0.0));
+    color_gradient.add_key(0.2, Vec4::new(2.0, 0.8, 3.0, 0.8));
+    color_gradient.add_key(1.0, Vec4::new(0.6, 0.2, 1.0, 0.0));
+
+    let mut size_gradient = Gradient::new();
+    size_gradient.add_key(0.0, Vec2::splat(0.15));
+    size_gradient.add_key(1.0, Vec2::splat(0.05));
+
+    effects.add(
+        EffectAsset {
+            name: "Wave spawn".to_string(),
+            capacity: 128,
+            spawner: Spawner::once(64.0.into(), true),
+            ..Default::default()
+        }
+        .init(InitPositionCircleModifier {
+            dimension: ShapeDimension::Surface,
+            radius: 0.6,
+            center: Vec3::ZERO,
+            axis: Vec3::Y,
+        })
+        .init(InitVelocitySphereModifier {
+            speed: 0.5_f32.into(),
+            center: Vec3::ZERO,
+        })
+        .init(InitLifetimeModifier {
+            lifetime: 1.0.into(),
+        })
+        .update(AccelModifier::constant(Vec3::new(0., 2., 0.)))
+        .render(BillboardModifier {})
+        .render(ColorOverLifetimeModifier {
+            gradient: color_gradient,
+        })
+        .render(SizeOverLifetimeModifier {
+            gradient: size_gradient,
+        }),
+    )
+}
@@ -1,3 +1,4 @@
+use crate::localization::Localization;
 use crate::util::criteria::is_frozen;
 use bevy::prelude::*;
 use leafwing_input_manager::axislike::DualAxisData;
@@ -47,7 +48,9 @@ pub(crate) enum PlayerAction {
     #[default]
     Move,
     Attack,
+    HeavyAttack,
     Block,
+    UseTool,
     Sprint,
     Jump,
     Interact,
@@ -98,32 +101,68 @@ pub(crate) enum UiAction {
     TogglePause,
 }
 
+/// Keys are bound by their location on a QWERTY keyboard, so that other layouts keep the same hand positions.
+const PLAYER_KEY_BINDINGS: [(QwertyScanCode, PlayerAction); 16] = [
+    (QwertyScanCode::Space, PlayerAction::Jump),
+    (QwertyScanCode::LShift, PlayerAction::Sprint),
+    (QwertyScanCode::E, PlayerAction::Interact),
+    (QwertyScanCode::F, PlayerAction::HeavyAttack),
+    (QwertyScanCode::Q, PlayerAction::UseTool),
+    (QwertyScanCode::Space, PlayerAction::SpeedUpDialog),
+    (QwertyScanCode::Key1, PlayerAction::NumberedChoice1),
+    (QwertyScanCode::Key2, PlayerAction::NumberedChoice2),
+    (QwertyScanCode::Key3, PlayerAction::NumberedChoice3),
+    (QwertyScanCode::Key4, PlayerAction::NumberedChoice4),
+    (QwertyScanCode::Key5, PlayerAction::NumberedChoice5),
+    (QwertyScanCode::Key6, PlayerAction::NumberedChoice6),
+    (QwertyScanCode::Key7, PlayerAction::NumberedChoice7),
+    (QwertyScanCode::Key8, PlayerAction::NumberedChoice8),
+    (QwertyScanCode::Key9, PlayerAction::NumberedChoice9),
+    (QwertyScanCode::Key0, PlayerAction::NumberedChoice0),
+];
+
+/// Mouse buttons and the key of their name in the string tables.
+const PLAYER_MOUSE_BINDINGS: [(MouseButton, PlayerAction, &str); 2] = [
+    (MouseButton::Left, PlayerAction::Attack, "input.mouse-left"),
+    (MouseButton::Right, PlayerAction::Block, "input.mouse-right"),
+];
+
 pub(crate) fn create_player_action_input_manager_bundle() -> InputManagerBundle<PlayerAction> {
+    let mut input_map = InputMap::new(PLAYER_KEY_BINDINGS);
+    input_map.insert(VirtualDPad::wasd(), PlayerAction::Move);
+    for (button, action, _) in PLAYER_MOUSE_BINDINGS {
+        input_map.insert(button, action);
+    }
     InputManagerBundle {
-        input_map: InputMap::new([
-            (QwertyScanCode::Space, PlayerAction::Jump),
-            (QwertyScanCode::LShift, PlayerAction::Sprint),
-            (QwertyScanCode::E, PlayerAction::Interact),
-            (QwertyScanCode::Space, PlayerAction::SpeedUpDialog),
-            (QwertyScanCode::Key1, PlayerAction::NumberedChoice1),
-            (QwertyScanCode::Key2, PlayerAction::NumberedChoice2),
-            (QwertyScanCode::Key3, PlayerAction::NumberedChoice3),
-            (QwertyScanCode::Key4, PlayerAction::NumberedChoice4),
-            (QwertyScanCode::Key5, PlayerAction::NumberedChoice5),
-            (QwertyScanCode::Key6, PlayerAction::NumberedChoice6),
-            (QwertyScanCode::Key7, PlayerAction::NumberedChoice7),
-            (QwertyScanCode::Key8, PlayerAction::NumberedChoice8),
-            (QwertyScanCode::Key9, PlayerAction::NumberedChoice9),
-            (QwertyScanCode::Key0, PlayerAction::NumberedChoice0),
-        ])
-        .insert(VirtualDPad::wasd(), PlayerAction::Move)
-        .insert(MouseButton::Left, PlayerAction::Attack)
-        .insert(MouseButton::Right, PlayerAction::Block)
-        .build(),
+        input_map,
         ..default()
     }
 }
 
+/// The name of the first input bound to the action, e.g. `Q` or `LMB`, for showing it in prompts.
+pub(crate) fn get_binding_name(
+    input_map: &InputMap<PlayerAction>,
+    action: PlayerAction,
+    localization: &Localization,
+) -> String {
+    let Some(input) = input_map.get(action).iter().next() else {
+        return localization.get("input.unbound");
+    };
+    let mouse_name = PLAYER_MOUSE_BINDINGS
+        .iter()
+        .find(|(button, ..)| UserInput::from(*button) == *input)
+        .map(|(.., key)| localization.get(key));
+    let key_name = || {
+        PLAYER_KEY_BINDINGS
+            .iter()
+            .find(|(key, _)| UserInput::from(*key) == *input)
+            .map(|(key, _)| format!("{key:?}"))
+    };
+    mouse_name
+        .or_else(key_name)
+        .unwrap_or_else(|| format!("{input:?}"))
+}
+
 pub(crate) fn create_camera_action_input_manager_bundle() -> InputManagerBundle<CameraAction> {
     InputManagerBundle {
         input_map: InputMap::default()
@@ -151,7 +190,9 @@ pub(crate) fn remove_actions_when_frozen(
         player_actions.release(PlayerAction::Interact);
         player_actions.release(PlayerAction::Sprint);
         player_actions.release(PlayerAction::Attack);
+        player_actions.release(PlayerAction::HeavyAttack);
         player_actions.release(PlayerAction::Block);
+        player_actions.release(PlayerAction::UseTool);
     }
     for mut camera_actions in camera_actions_query.iter_mut() {
         camera_actions
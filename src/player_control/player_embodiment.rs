@@ -39,6 +39,12 @@ pub(crate) fn player_embodiment_plugin(app: &mut App) {
         .register_type::<CancellationTimes>()
         .register_type::<PeriodicCancellationTimes>()
         .register_type::<PlayerAttacks>()
+        .register_type::<ComboNode>()
+        .register_type::<ComboBranch>()
+        .register_type::<ComboInput>()
+        .register_type::<ComboTiming>()
+        .register_type::<AttackCharge>()
+        .register_type::<PlayerTool>()
         .register_type::<PlayerHurtEvent>()
         .register_type::<BlockedByPlayerEvent>()
         .register_type::<DeflectedByPlayerEvent>()
@@ -68,6 +74,9 @@ pub(crate) fn player_embodiment_plugin(app: &mut App) {
                 combat::update_block_history,
                 combat::block,
                 combat::attack,
+                combat::charge_attack,
+                combat::use_tool,
+                combat::refill_tool,
                 combat::update_states,
                 combat::collision::handle_player_being_hit,
                 combat::after_hit::handle_hurt_events,
@@ -1,17 +1,21 @@
-use crate::combat::{AttackHitbox, ParentToHitboxLink};
+use crate::combat::{AttackHitbox, ParentToHitboxLink, ProjectileKind, ProjectileSpawnInput};
 use crate::level_instantiation::spawning::objects::GameCollisionGroup;
 use crate::level_instantiation::spawning::AnimationEntityLink;
 use crate::player_control::player_embodiment::PlayerAction;
+use crate::world_interaction::room::{CurrentRoom, EnterRoomEvent, RoomClearEvent};
 use anyhow::{Context, Result};
 use bevy::prelude::*;
 use bevy_mod_sysfail::sysfail;
 use bevy_rapier3d::prelude::*;
+pub(crate) use combo::*;
 pub(crate) use components::*;
 use leafwing_input_manager::prelude::*;
+use spew::prelude::*;
 use std::time::Duration;
 
 pub(crate) mod after_hit;
 pub(crate) mod collision;
+mod combo;
 mod components;
 pub(crate) mod debug;
 pub(crate) mod posture;
@@ -21,22 +25,49 @@ pub(crate) fn attack(
     mut players: Query<(
         &ActionState<PlayerAction>,
         &mut PlayerCombatState,
-        &PlayerCombatAnimations,
+        &PlayerAttacks,
     )>,
 ) {
-    for (actions, mut combat_state, animations) in players.iter_mut() {
-        if actions.just_pressed(PlayerAction::Attack) {
-            let attack_kind = if let PlayerCombatKind::Attack(attack) = combat_state.kind {
-                let next_attack = (attack + 1) % animations.attacks.len() as u16;
-                PlayerCombatKind::Attack(next_attack)
-            } else {
-                PlayerCombatKind::Attack(0)
-            };
+    for (actions, mut combat_state, attacks) in players.iter_mut() {
+        let input = [ComboInput::Light, ComboInput::Heavy]
+            .into_iter()
+            .find(|input| actions.just_pressed(input.action()));
+        let Some(input) = input else {
+            continue;
+        };
+        if let Some(attack_kind) =
+            attacks.get_next(combat_state.kind, input, combat_state.commitment)
+        {
             combat_state.try_use_next_kind(attack_kind, |current| !current.is_attack());
         }
     }
 }
 
+/// Starts charging a chargeable attack as long as an attack input is held when it begins.
+pub(crate) fn charge_attack(
+    mut players: Query<(
+        &ActionState<PlayerAction>,
+        &mut PlayerCombatState,
+        &PlayerAttacks,
+    )>,
+) {
+    for (actions, mut combat_state, attacks) in players.iter_mut() {
+        let Some(charge) = attacks.get_node(combat_state.kind).and_then(|node| node.charge) else {
+            continue;
+        };
+        let is_held = [ComboInput::Light, ComboInput::Heavy]
+            .into_iter()
+            .any(|input| actions.pressed(input.action()));
+        if combat_state.is_charging {
+            if !is_held || combat_state.charge_time >= charge.max_time {
+                combat_state.is_charging = false;
+            }
+        } else if is_held && combat_state.time_in_state < 1e-5 && combat_state.charge_time < 1e-5 {
+            combat_state.is_charging = true;
+        }
+    }
+}
+
 pub(crate) fn block(
     mut players: Query<(
         &ActionState<PlayerAction>,
@@ -62,18 +93,71 @@ pub(crate) fn block(
     }
 }
 
+pub(crate) fn use_tool(
+    time: Res<Time>,
+    mut players: Query<(
+        Entity,
+        &ActionState<PlayerAction>,
+        &PlayerCombatState,
+        &mut PlayerTool,
+    )>,
+    mut spawn_events: EventWriter<SpawnEvent<ProjectileKind, (Entity, ProjectileSpawnInput)>>,
+) {
+    for (entity, actions, combat_state, mut tool) in players.iter_mut() {
+        tool.time_since_use += time.delta_seconds();
+        let can_act = combat_state.commitment.is_cancellable()
+            && matches!(
+                combat_state.kind,
+                PlayerCombatKind::Idle | PlayerCombatKind::Block | PlayerCombatKind::Attack(_)
+            );
+        if actions.just_pressed(PlayerAction::UseTool) && can_act && tool.is_ready() {
+            tool.use_up();
+            spawn_events.send(SpawnEvent::with_data(
+                tool.kind,
+                (entity, tool.spawn_input.clone()),
+            ));
+        }
+    }
+}
+
+/// Refills the [`PlayerTool`] once whenever a room is cleared, which includes entering a room without enemies like a rest room.
+/// A new run spawns a new player, whose tool starts out full anyway.
+pub(crate) fn refill_tool(
+    mut refilled_room: Local<Option<usize>>,
+    mut enter_room_events: EventReader<EnterRoomEvent>,
+    mut room_clear_events: EventReader<RoomClearEvent>,
+    current_room: Res<CurrentRoom>,
+    mut tools: Query<&mut PlayerTool>,
+) {
+    if !enter_room_events.is_empty() {
+        enter_room_events.clear();
+        *refilled_room = None;
+    }
+    // Sent every frame while the room is cleared
+    let is_room_cleared = !room_clear_events.is_empty();
+    room_clear_events.clear();
+    if !is_room_cleared || *refilled_room == Some(current_room.number) {
+        return;
+    }
+    *refilled_room = Some(current_room.number);
+    for mut tool in tools.iter_mut() {
+        tool.refill();
+    }
+}
+
 pub(crate) fn update_states(
     time: Res<Time>,
     mut players: Query<(
         &mut PlayerCombatState,
         &PlayerCombatAnimations,
+        &PlayerAttacks,
         &mut BlockHistory,
     )>,
     animation_clips: Res<Assets<AnimationClip>>,
 ) {
-    for (mut combat_state, combat_animations, mut block_history) in players.iter_mut() {
+    for (mut combat_state, combat_animations, attacks, mut block_history) in players.iter_mut() {
         combat_state.update_timers(time.delta_seconds());
-        let animation = combat_state.kind.get_animation(combat_animations);
+        let animation = combat_state.kind.get_animation(combat_animations, attacks);
         let last_kind = combat_state.kind;
         match animation.cancellation_times {
             CancellationTimes::Always => {
@@ -131,7 +215,7 @@ pub(crate) fn update_hitbox(
         hitbox.active = combat_state.kind.is_attack() && !combat_state.commitment.is_cancellable();
         if hitbox.active {
             collision_groups.filters |= GameCollisionGroup::ENEMY.into();
-            hitbox.attack = combat_state.kind.get_attack(&attacks).context("Failed to get attack from combat state even though according to hitbox activation it should be an attack")?;
+            hitbox.attack = combat_state.kind.get_attack(attacks, combat_state.charge_time).context("Failed to get attack from combat state even though according to hitbox activation it should be an attack")?;
         } else {
             collision_groups.filters -= GameCollisionGroup::ENEMY.into();
         }
@@ -144,18 +228,24 @@ pub(crate) fn play_animations(
     mut players: Query<(
         &mut PlayerCombatState,
         &PlayerCombatAnimations,
+        &PlayerAttacks,
         &AnimationEntityLink,
     )>,
     mut animation_players: Query<&mut AnimationPlayer>,
 ) -> Result<()> {
-    for (mut combat_state, animations, animation_entity_link) in players.iter_mut() {
-        if combat_state.started_animation {
-            continue;
-        }
+    for (mut combat_state, animations, attacks, animation_entity_link) in players.iter_mut() {
         let mut animation_player = animation_players.get_mut(animation_entity_link.0).context(
             "Animation entity link points to an entity that does not have an animation player",
         )?;
-        let animation = combat_state.kind.get_animation(animations);
+        if combat_state.is_charging {
+            animation_player.pause();
+        } else if animation_player.is_paused() {
+            animation_player.resume();
+        }
+        if combat_state.started_animation {
+            continue;
+        }
+        let animation = combat_state.kind.get_animation(animations, attacks);
         animation_player
             .start_with_transition(animation.handle.clone(), Duration::from_secs_f32(0.1));
         if matches!(animation.cancellation_times, CancellationTimes::Always) {
@@ -1,5 +1,5 @@
 use crate::combat::collision::DeflectedByEnemyEvent;
-use crate::combat::{Attack, Constitution, Enemy};
+use crate::combat::{Attack, Constitution, Enemy, Projectile};
 use crate::player_control::player_embodiment::combat::collision::{
     BlockedByPlayerEvent, DeflectedByPlayerEvent, PlayerHurtEvent,
 };
@@ -110,8 +110,13 @@ pub(crate) fn handle_deflect_events(
 pub(crate) fn handle_enemy_deflect_events(
     mut attacks: EventReader<DeflectedByEnemyEvent>,
     mut players: Query<(&mut PlayerCombatState,)>,
+    projectiles: Query<(), With<Projectile>>,
 ) {
-    for _attack in attacks.iter() {
+    for attack in attacks.iter() {
+        // Deflected projectiles do not stagger the player who threw them
+        if projectiles.contains(attack.source) {
+            continue;
+        }
         for (mut combat_state,) in players.iter_mut() {
             combat_state.force_use_next_kind(PlayerCombatKind::Deflected);
             combat_state.commitment = AttackCommitment::Committed;
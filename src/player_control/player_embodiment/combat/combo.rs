@@ -0,0 +1,231 @@
+use crate::combat::Attack;
+use crate::file_system_interaction::asset_loading::FpsDummyAnimationAssets;
+use crate::player_control::actions::PlayerAction;
+use crate::player_control::player_embodiment::combat::{
+    AttackCommitment, CancellationTimes, PlayerCombatAnimation, PlayerCombatKind,
+};
+use anyhow::{bail, Context, Result};
+use bevy::prelude::*;
+use bevy::reflect::TypeUuid;
+use serde::{Deserialize, Serialize};
+
+/// The attacks of the player as read from `assets/combos/<name>.combo.ron`.
+/// Animations are referred to by their name in [`FpsDummyAnimationAssets`], e.g. `"attack_one"`.
+#[derive(Debug, Clone, PartialEq, Deserialize, TypeUuid)]
+#[uuid = "4321204b-9367-439b-be90-8867af33b65e"]
+pub(crate) struct ComboDefinition {
+    pub(crate) nodes: Vec<ComboNodeDefinition>,
+    /// Branches taken when the player is not currently attacking.
+    pub(crate) openers: Vec<ComboBranch>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub(crate) struct ComboNodeDefinition {
+    pub(crate) name: String,
+    /// Health damage of the attack, its posture damage and knockback are derived from it.
+    pub(crate) damage: f32,
+    pub(crate) animation: String,
+    #[serde(default)]
+    pub(crate) cancellation_times: CancellationTimes,
+    /// Checked in order, the first matching branch is taken.
+    #[serde(default)]
+    pub(crate) branches: Vec<ComboBranch>,
+    #[serde(default)]
+    pub(crate) charge: Option<AttackCharge>,
+}
+
+impl ComboDefinition {
+    /// Resolves the animation names and checks that every branch leads to an existing node.
+    pub(crate) fn build(&self, animations: &FpsDummyAnimationAssets) -> Result<PlayerAttacks> {
+        let branches = self
+            .openers
+            .iter()
+            .chain(self.nodes.iter().flat_map(|node| node.branches.iter()));
+        for branch in branches {
+            if branch.next as usize >= self.nodes.len() {
+                bail!(
+                    "Combo branch leads to node {}, but there are only {} nodes",
+                    branch.next,
+                    self.nodes.len()
+                );
+            }
+        }
+        let nodes = self
+            .nodes
+            .iter()
+            .map(|node| {
+                let handle = get_animation(animations, &node.animation).with_context(|| {
+                    format!(
+                        "Unknown animation \"{}\" in combo node \"{}\"",
+                        node.animation, node.name
+                    )
+                })?;
+                Ok(ComboNode {
+                    attack: Attack::new(node.name.clone())
+                        .with_health_damage_scaling_rest(node.damage),
+                    animation: PlayerCombatAnimation {
+                        handle,
+                        cancellation_times: node.cancellation_times,
+                    },
+                    branches: node.branches.clone(),
+                    charge: node.charge,
+                })
+            })
+            .collect::<Result<_>>()?;
+        Ok(PlayerAttacks {
+            nodes,
+            openers: self.openers.clone(),
+        })
+    }
+}
+
+fn get_animation(
+    animations: &FpsDummyAnimationAssets,
+    name: &str,
+) -> Option<Handle<AnimationClip>> {
+    let handle = match name {
+        "idle" => &animations.idle,
+        "attack_one" => &animations.attack_one,
+        "attack_two" => &animations.attack_two,
+        "attack_three" => &animations.attack_three,
+        "block" => &animations.block,
+        "hurt" => &animations.hurt,
+        "blocked" => &animations.blocked,
+        _ => return None,
+    };
+    Some(handle.clone())
+}
+
+/// Graph of all attacks the player can chain together.
+/// [`PlayerCombatKind::Attack`] holds an index into [`PlayerAttacks::nodes`].
+#[derive(Debug, Clone, Component, Reflect, FromReflect, Default)]
+#[reflect(Component)]
+pub(crate) struct PlayerAttacks {
+    pub(crate) nodes: Vec<ComboNode>,
+    /// Branches taken when the player is not currently attacking.
+    pub(crate) openers: Vec<ComboBranch>,
+}
+
+impl PlayerAttacks {
+    pub(crate) fn get_node(&self, kind: PlayerCombatKind) -> Option<&ComboNode> {
+        match kind {
+            PlayerCombatKind::Attack(index) => self.nodes.get(index as usize),
+            _ => None,
+        }
+    }
+
+    /// Returns the attack that follows `current` when pressing `input` at the time described by `commitment`.
+    pub(crate) fn get_next(
+        &self,
+        current: PlayerCombatKind,
+        input: ComboInput,
+        commitment: AttackCommitment,
+    ) -> Option<PlayerCombatKind> {
+        let branches = match self.get_node(current) {
+            Some(node) => &node.branches,
+            None => &self.openers,
+        };
+        branches
+            .iter()
+            .find(|branch| branch.input == input && branch.timing.matches(commitment))
+            .map(|branch| PlayerCombatKind::Attack(branch.next))
+    }
+}
+
+#[derive(Debug, Clone, Reflect, FromReflect, Default)]
+pub(crate) struct ComboNode {
+    pub(crate) attack: Attack,
+    pub(crate) animation: PlayerCombatAnimation,
+    /// Checked in order, the first matching branch is taken.
+    pub(crate) branches: Vec<ComboBranch>,
+    pub(crate) charge: Option<AttackCharge>,
+}
+
+impl ComboNode {
+    /// The attack with its damage scaled by how long it was charged.
+    pub(crate) fn get_charged_attack(&self, charge_time: f32) -> Attack {
+        let factor = self
+            .charge
+            .map(|charge| charge.get_damage_factor(charge_time))
+            .unwrap_or(1.0);
+        Attack {
+            health_damage: self.attack.health_damage * factor,
+            posture_damage: self.attack.posture_damage * factor,
+            ..self.attack.clone()
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Reflect, FromReflect, Serialize, Deserialize, Default)]
+#[reflect(Serialize, Deserialize)]
+pub(crate) struct ComboBranch {
+    pub(crate) input: ComboInput,
+    #[serde(default)]
+    pub(crate) timing: ComboTiming,
+    /// Index into [`PlayerAttacks::nodes`].
+    pub(crate) next: u16,
+}
+
+#[derive(
+    Debug, Clone, Copy, Eq, PartialEq, Reflect, FromReflect, Serialize, Deserialize, Default,
+)]
+#[reflect(Serialize, Deserialize)]
+pub(crate) enum ComboInput {
+    #[default]
+    Light,
+    Heavy,
+}
+
+impl ComboInput {
+    pub(crate) fn action(self) -> PlayerAction {
+        match self {
+            ComboInput::Light => PlayerAction::Attack,
+            ComboInput::Heavy => PlayerAction::HeavyAttack,
+        }
+    }
+}
+
+/// When the input has to be pressed during the current attack for a branch to be taken.
+#[derive(
+    Debug, Clone, Copy, Eq, PartialEq, Reflect, FromReflect, Serialize, Deserialize, Default,
+)]
+#[reflect(Serialize, Deserialize)]
+pub(crate) enum ComboTiming {
+    #[default]
+    Any,
+    /// Pressed during the buffer window, i.e. chained right away.
+    Buffered,
+    /// Pressed after the buffer window, i.e. after a short pause.
+    Delayed,
+}
+
+impl ComboTiming {
+    fn matches(self, commitment: AttackCommitment) -> bool {
+        match self {
+            ComboTiming::Any => true,
+            ComboTiming::Buffered => commitment == AttackCommitment::InBufferPeriod,
+            ComboTiming::Delayed => commitment == AttackCommitment::LateCancellable,
+        }
+    }
+}
+
+/// Lets an attack be held before it is released, scaling its damage.
+#[derive(Debug, Clone, Copy, PartialEq, Reflect, FromReflect, Serialize, Deserialize, Default)]
+#[reflect(Serialize, Deserialize)]
+pub(crate) struct AttackCharge {
+    /// In seconds. Charging stops automatically afterwards.
+    pub(crate) max_time: f32,
+    /// Damage multiplier reached after charging for `max_time`.
+    pub(crate) max_damage_factor: f32,
+}
+
+impl AttackCharge {
+    pub(crate) fn get_damage_factor(self, charge_time: f32) -> f32 {
+        let fraction = if self.max_time < 1e-5 {
+            1.0
+        } else {
+            (charge_time / self.max_time).clamp(0.0, 1.0)
+        };
+        1.0 + (self.max_damage_factor - 1.0) * fraction
+    }
+}
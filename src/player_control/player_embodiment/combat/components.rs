@@ -1,4 +1,5 @@
-use crate::combat::{Attack, Constitution};
+use crate::combat::{Attack, Constitution, ProjectileKind, ProjectileSpawnInput};
+use crate::player_control::player_embodiment::combat::PlayerAttacks;
 use bevy::prelude::*;
 use serde::{Deserialize, Serialize};
 
@@ -9,9 +10,12 @@ pub(crate) struct PlayerCombatBundle {
     pub(crate) player_attacks: PlayerAttacks,
     pub(crate) constitution: Constitution,
     pub(crate) block_history: BlockHistory,
+    pub(crate) tool: PlayerTool,
 }
 
-#[derive(Debug, Clone, Copy, Component, Reflect, FromReflect, Serialize, Deserialize, Default)]
+#[derive(
+    Debug, Clone, Copy, PartialEq, Component, Reflect, FromReflect, Serialize, Deserialize, Default,
+)]
 #[reflect(Component, Serialize, Deserialize)]
 pub(crate) struct PlayerCombatState {
     pub(crate) kind: PlayerCombatKind,
@@ -21,6 +25,9 @@ pub(crate) struct PlayerCombatState {
     pub(crate) time_since_hurt_or_block: f32,
     pub(crate) time_since_sprint: f32,
     pub(crate) started_animation: bool,
+    /// While charging, `time_in_state` does not advance and the animation is paused.
+    pub(crate) is_charging: bool,
+    pub(crate) charge_time: f32,
 }
 
 #[derive(Debug, Clone, Component, Reflect, FromReflect, Serialize, Deserialize, Default)]
@@ -110,7 +117,11 @@ impl PlayerCombatState {
     }
 
     pub(crate) fn update_timers(&mut self, dt: f32) {
-        self.time_in_state += dt;
+        if self.is_charging {
+            self.charge_time += dt;
+        } else {
+            self.time_in_state += dt;
+        }
         self.time_since_hurt_or_block += dt;
         self.time_since_sprint += dt;
     }
@@ -141,7 +152,6 @@ impl AttackCommitment {
 #[reflect(Component)]
 pub(crate) struct PlayerCombatAnimations {
     pub(crate) idle: PlayerCombatAnimation,
-    pub(crate) attacks: [PlayerCombatAnimation; 3],
     pub(crate) block: PlayerCombatAnimation,
     pub(crate) hurt: PlayerCombatAnimation,
     pub(crate) deflected: PlayerCombatAnimation,
@@ -171,7 +181,7 @@ impl PlayerCombatAnimation {
     }
 }
 
-#[derive(Debug, Clone, Copy, Reflect, FromReflect)]
+#[derive(Debug, Clone, Copy, PartialEq, Reflect, FromReflect, Serialize, Deserialize)]
 pub(crate) enum CancellationTimes {
     Always,
     Periodic(PeriodicCancellationTimes),
@@ -183,7 +193,7 @@ impl Default for CancellationTimes {
     }
 }
 
-#[derive(Debug, Clone, Copy, Reflect, FromReflect)]
+#[derive(Debug, Clone, Copy, PartialEq, Reflect, FromReflect, Serialize, Deserialize)]
 pub(crate) struct PeriodicCancellationTimes {
     pub(crate) early_cancel_end: f32,
     pub(crate) late_cancel_start: f32,
@@ -215,13 +225,17 @@ pub(crate) enum PlayerCombatKind {
 }
 
 impl PlayerCombatKind {
-    pub(crate) fn get_animation(
+    pub(crate) fn get_animation<'a>(
         self,
-        animations: &PlayerCombatAnimations,
-    ) -> &PlayerCombatAnimation {
+        animations: &'a PlayerCombatAnimations,
+        attacks: &'a PlayerAttacks,
+    ) -> &'a PlayerCombatAnimation {
         match self {
             PlayerCombatKind::Idle => &animations.idle,
-            PlayerCombatKind::Attack(attack) => &animations.attacks[attack as usize],
+            // Attacks from another combo graph, e.g. in an old save, fall back to idling
+            PlayerCombatKind::Attack(_) => attacks
+                .get_node(self)
+                .map_or(&animations.idle, |node| &node.animation),
             PlayerCombatKind::Block => &animations.block,
             PlayerCombatKind::Hurt => &animations.hurt,
             PlayerCombatKind::Deflected => &animations.deflected,
@@ -229,11 +243,10 @@ impl PlayerCombatKind {
         }
     }
 
-    pub(crate) fn get_attack(self, attacks: &PlayerAttacks) -> Option<Attack> {
-        match self {
-            PlayerCombatKind::Attack(attack) => Some(attacks.attacks[attack as usize].clone()),
-            _ => None,
-        }
+    pub(crate) fn get_attack(self, attacks: &PlayerAttacks, charge_time: f32) -> Option<Attack> {
+        attacks
+            .get_node(self)
+            .map(|node| node.get_charged_attack(charge_time))
     }
 
     pub(crate) fn is_attack(self) -> bool {
@@ -241,8 +254,66 @@ impl PlayerCombatKind {
     }
 }
 
+/// Secondary tool with limited uses. Fires through the same projectile spawners enemies use.
 #[derive(Debug, Clone, Component, Reflect, FromReflect, Default)]
 #[reflect(Component)]
-pub(crate) struct PlayerAttacks {
-    pub(crate) attacks: [Attack; 3],
+pub(crate) struct PlayerTool {
+    /// Key of the name in the string tables, e.g. `tool.kunai`.
+    pub(crate) name: String,
+    pub(crate) kind: ProjectileKind,
+    pub(crate) spawn_input: ProjectileSpawnInput,
+    pub(crate) uses: u16,
+    pub(crate) max_uses: u16,
+    /// In seconds
+    pub(crate) cooldown: f32,
+    pub(crate) time_since_use: f32,
+}
+
+impl PlayerTool {
+    pub(crate) fn new(
+        name: impl Into<String>,
+        kind: ProjectileKind,
+        spawn_input: ProjectileSpawnInput,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            kind,
+            spawn_input,
+            ..default()
+        }
+    }
+
+    pub(crate) fn with_max_uses(mut self, max_uses: u16) -> Self {
+        self.max_uses = max_uses;
+        self.uses = max_uses;
+        self
+    }
+
+    pub(crate) fn with_cooldown(mut self, cooldown: f32) -> Self {
+        self.cooldown = cooldown;
+        self.time_since_use = cooldown;
+        self
+    }
+
+    pub(crate) fn is_ready(&self) -> bool {
+        self.uses > 0 && self.time_since_use >= self.cooldown
+    }
+
+    /// Restores all uses, see [`refill_tool`](crate::player_control::player_embodiment::combat::refill_tool).
+    pub(crate) fn refill(&mut self) {
+        self.uses = self.max_uses;
+    }
+
+    pub(crate) fn use_up(&mut self) {
+        self.uses = self.uses.saturating_sub(1);
+        self.time_since_use = 0.0;
+    }
+
+    pub(crate) fn cooldown_fraction(&self) -> f32 {
+        if self.cooldown < 1e-5 {
+            0.0
+        } else {
+            (1.0 - self.time_since_use / self.cooldown).clamp(0.0, 1.0)
+        }
+    }
 }
@@ -1,5 +1,6 @@
 use crate::combat::Constitution;
 use crate::file_system_interaction::level_serialization::WorldLoadRequest;
+use crate::localization::Localization;
 use crate::movement::general_movement::Walking;
 use crate::player_control::actions::ActionsFrozen;
 use crate::player_control::player_embodiment::combat::{
@@ -58,6 +59,7 @@ pub(crate) fn handle_death(
     mut actions_frozen: ResMut<ActionsFrozen>,
     mut egui_contexts: EguiContexts,
     current_room: Res<CurrentRoom>,
+    localization: Localization,
 ) {
     let room_number = current_room.number;
     for constitution in player.iter_mut() {
@@ -78,16 +80,20 @@ pub(crate) fn handle_death(
                 ui.vertical_centered_justified(|ui| {
                     ui.visuals_mut().override_text_color = Some(egui::Color32::from_gray(240));
                     ui.add_space(100.0);
-                    ui.heading("You died");
+                    ui.heading(localization.get("death.title"));
                     ui.separator();
                     ui.vertical_centered_justified(|ui| {
-                        ui.label(format!("You beat {room_number} rooms!"));
-                        ui.label("Wanna try again?");
-                        if ui.button("Heck yeah!").clicked() {
+                        ui.label(
+                            localization
+                                .format("death.rooms-beaten", &[("room-number", &room_number)]),
+                        );
+                        ui.label(localization.get("death.try-again"));
+                        if ui.button(localization.get("death.restart")).clicked() {
                             *pause = false;
                             actions_frozen.unfreeze();
                             loader.send(WorldLoadRequest {
                                 filename: "intro_room".to_string(),
+                                ..default()
                             });
                         }
                     });
@@ -1,14 +1,25 @@
 use crate::combat::Constitution;
 use crate::file_system_interaction::asset_loading::TextureAssets;
+use crate::localization::Localization;
+use crate::player_control::actions::{get_binding_name, PlayerAction};
+use crate::player_control::player_embodiment::combat::PlayerTool;
 use crate::player_control::player_embodiment::Player;
+use crate::util::criteria::is_frozen;
 use crate::GameState;
 use anyhow::Result;
 use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts};
 use bevy_mod_sysfail::macros::*;
+use leafwing_input_manager::prelude::InputMap;
 
 pub(crate) fn player_combat_ui_plugin(app: &mut App) {
     app.add_system(spawn_constitution_bars.in_schedule(OnEnter(GameState::Playing)))
-        .add_systems((update_constitution_bars,).in_set(OnUpdate(GameState::Playing)));
+        .add_system(update_constitution_bars.in_set(OnUpdate(GameState::Playing)))
+        .add_system(
+            display_tool
+                .run_if(not(is_frozen))
+                .in_set(OnUpdate(GameState::Playing)),
+        );
 }
 
 fn spawn_constitution_bars(mut commands: Commands, textures: Res<TextureAssets>) {
@@ -194,3 +205,32 @@ fn update_constitution_bars(
     }
     Ok(())
 }
+
+fn display_tool(
+    mut egui_contexts: EguiContexts,
+    players: Query<(&PlayerTool, &InputMap<PlayerAction>), With<Player>>,
+    localization: Localization,
+) {
+    for (tool, input_map) in players.iter() {
+        let key = get_binding_name(input_map, PlayerAction::UseTool, &localization);
+        let name = localization.get(&tool.name);
+        let label = localization.format(
+            "tool.label",
+            &[
+                ("key", &key),
+                ("tool", &name),
+                ("uses", &tool.uses),
+                ("max-uses", &tool.max_uses),
+            ],
+        );
+        egui::Window::new("Tool")
+            .collapsible(false)
+            .title_bar(false)
+            .auto_sized()
+            .anchor(egui::Align2::RIGHT_BOTTOM, egui::Vec2::new(-12.0, -12.0))
+            .show(egui_contexts.ctx_mut(), |ui| {
+                ui.label(label);
+                ui.add(egui::ProgressBar::new(1.0 - tool.cooldown_fraction()).desired_width(120.0));
+            });
+    }
+}
@@ -1,5 +1,6 @@
 #![allow(clippy::extra_unused_type_parameters)]
 use crate::file_system_interaction::asset_loading::TextureAssets;
+use crate::level_instantiation::spawning::scene_tags::SceneTags;
 use crate::GameState;
 use anyhow::{Context, Result};
 use bevy::asset::HandleId;
@@ -13,8 +14,6 @@ use bevy::render::render_resource::{
 };
 use bevy::utils::HashMap;
 use bevy_mod_sysfail::macros::*;
-use regex::Regex;
-use std::sync::LazyLock;
 
 /// Handles instantiation of shaders. The shaders can be found in the [`shaders`](https://github.com/janhohenheim/egg slash/tree/main/assets/shaders) directory.
 /// Shaders are stored in [`Material`]s which can be used on objects by attaching a `Handle<Material>` to an entity.
@@ -121,24 +120,20 @@ impl Material for RepeatedMaterial {
     }
 }
 
-static REPEAT_REGEX: LazyLock<Regex> = LazyLock::new(|| {
-    Regex::new(r"\[repeat:\s*(\d+),\s*(\d+)\]").expect("Failed to compile repeat regex")
-});
-
 #[sysfail(log(level = "error"))]
 pub(crate) fn set_texture_to_repeat(
     mut commands: Commands,
-    added_name: Query<(&Name, &Children), Added<Name>>,
+    added_tags: Query<(&SceneTags, &Children), Added<SceneTags>>,
     material_handles: Query<&Handle<StandardMaterial>>,
     mut materials: ResMut<Materials>,
     standard_materials: Res<Assets<StandardMaterial>>,
     mut repeated_materials: ResMut<Assets<RepeatedMaterial>>,
 ) -> Result<()> {
-    for (name, children) in &added_name {
-        if let Some(captures) = REPEAT_REGEX.captures(&name.to_lowercase()) {
+    for (tags, children) in &added_tags {
+        if let Some((horizontal, vertical)) = tags.repeat() {
             let repeats = Repeats {
-                horizontal: captures[1].parse().context("Failed to parse repeat")?,
-                vertical: captures[2].parse().context("Failed to parse repeat")?,
+                horizontal,
+                vertical,
                 ..default()
             };
             for child in children.iter() {
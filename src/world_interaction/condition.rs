@@ -1,12 +1,22 @@
+use crate::world_interaction::room::CurrentRoom;
+use crate::world_interaction::side_effects::{SideEffect, SideEffects};
 use crate::GameState;
+use bevy::ecs::system::SystemParam;
 use bevy::prelude::*;
-use bevy::utils::HashSet;
+use bevy::utils::{HashMap, HashSet};
 use serde::{Deserialize, Serialize};
 
 pub(crate) fn condition_plugin(app: &mut App) {
-    app.init_resource::<ActiveConditions>()
+    app.register_type::<Counters>()
+        .init_resource::<ActiveConditions>()
+        .init_resource::<Counters>()
         .add_event::<ConditionAddEvent>()
-        .add_system(add_conditions.in_set(OnUpdate(GameState::Playing)));
+        .add_event::<ConditionRemoveEvent>()
+        .add_systems(
+            (add_conditions, remove_conditions)
+                .chain()
+                .in_set(OnUpdate(GameState::Playing)),
+        );
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, Resource, Reflect, Serialize, Deserialize, Default)]
@@ -18,6 +28,109 @@ impl ActiveConditions {
     }
 }
 
+/// Numbers that dialogs can count up and compare against, e.g. how often the player talked to someone.
+/// Counters that were never set are 0.
+#[derive(Debug, Clone, Eq, PartialEq, Resource, Reflect, Serialize, Deserialize, Default)]
+#[reflect(Resource, Serialize, Deserialize)]
+pub(crate) struct Counters(pub(crate) HashMap<String, i32>);
+impl Counters {
+    pub(crate) fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+    pub(crate) fn get(&self, counter: &str) -> i32 {
+        self.0.get(counter).copied().unwrap_or_default()
+    }
+    pub(crate) fn set(&mut self, counter: &str, value: i32) {
+        self.0.insert(counter.to_string(), value);
+    }
+    pub(crate) fn add(&mut self, counter: &str, amount: i32) {
+        *self.0.entry(counter.to_string()).or_default() += amount;
+    }
+}
+
+/// Everything a [`Requirement`] can look at.
+#[derive(SystemParam)]
+pub(crate) struct ConditionContext<'w> {
+    pub(crate) active_conditions: Res<'w, ActiveConditions>,
+    pub(crate) counters: Res<'w, Counters>,
+    current_room: Res<'w, CurrentRoom>,
+    side_effects: Res<'w, SideEffects>,
+}
+
+impl<'w> ConditionContext<'w> {
+    fn get(&self, variable: &Variable) -> i32 {
+        match variable {
+            Variable::Counter(counter) => self.counters.get(counter),
+            Variable::RoomNumber => self.current_room.number as i32,
+            Variable::SideEffect(side_effect) => self.side_effects.get(*side_effect),
+        }
+    }
+}
+
+/// A boolean expression over the active conditions and numeric variables, written in `.dlg.ron` as e.g.
+/// `All([Active("choice:who"), Not(Active("choice:bye")), Compare(Counter("visits"), AtLeast, 3)])`.
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub(crate) enum Requirement {
+    Active(ConditionId),
+    Not(Box<Requirement>),
+    /// True if there are no requirements.
+    All(Vec<Requirement>),
+    /// False if there are no requirements.
+    Any(Vec<Requirement>),
+    Compare(Variable, Comparison, i32),
+}
+
+impl Requirement {
+    pub(crate) fn evaluate(&self, context: &ConditionContext) -> bool {
+        match self {
+            Requirement::Active(condition) => context.active_conditions.0.contains(condition),
+            Requirement::Not(requirement) => !requirement.evaluate(context),
+            Requirement::All(requirements) => requirements
+                .iter()
+                .all(|requirement| requirement.evaluate(context)),
+            Requirement::Any(requirements) => requirements
+                .iter()
+                .any(|requirement| requirement.evaluate(context)),
+            Requirement::Compare(variable, comparison, value) => {
+                comparison.compare(context.get(variable), *value)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub(crate) enum Variable {
+    /// See [`Counters`].
+    Counter(String),
+    /// How many rooms the player went through in this run.
+    RoomNumber,
+    /// How often the side effect was increased minus how often it was decreased.
+    SideEffect(SideEffect),
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub(crate) enum Comparison {
+    Less,
+    AtMost,
+    Equal,
+    NotEqual,
+    AtLeast,
+    Greater,
+}
+
+impl Comparison {
+    fn compare(self, left: i32, right: i32) -> bool {
+        match self {
+            Comparison::Less => left < right,
+            Comparison::AtMost => left <= right,
+            Comparison::Equal => left == right,
+            Comparison::NotEqual => left != right,
+            Comparison::AtLeast => left >= right,
+            Comparison::Greater => left > right,
+        }
+    }
+}
+
 #[derive(
     Debug, Clone, Eq, PartialEq, Default, Reflect, Hash, Serialize, Deserialize, FromReflect,
 )]
@@ -41,6 +154,10 @@ impl From<ConditionId> for String {
 #[reflect(Serialize, Deserialize)]
 pub(crate) struct ConditionAddEvent(pub(crate) ConditionId);
 
+#[derive(Debug, Clone, Eq, PartialEq, Default, Reflect, Hash, Serialize, Deserialize)]
+#[reflect(Serialize, Deserialize)]
+pub(crate) struct ConditionRemoveEvent(pub(crate) ConditionId);
+
 fn add_conditions(
     mut conditions: ResMut<ActiveConditions>,
     mut incoming_conditions: EventReader<ConditionAddEvent>,
@@ -49,3 +166,12 @@ fn add_conditions(
         conditions.0.insert(incoming_condition.0.clone());
     }
 }
+
+fn remove_conditions(
+    mut conditions: ResMut<ActiveConditions>,
+    mut outgoing_conditions: EventReader<ConditionRemoveEvent>,
+) {
+    for outgoing_condition in outgoing_conditions.iter() {
+        conditions.0.remove(&outgoing_condition.0);
+    }
+}
@@ -1,42 +1,69 @@
 use crate::file_system_interaction::asset_loading::DialogAssets;
 use crate::file_system_interaction::config::GameConfig;
+use crate::file_system_interaction::level_serialization::ReflectLevelComponent;
+use crate::level_instantiation::spawning::scene_tags::SceneTags;
+use crate::localization::{Locale, Localization};
 use crate::player_control::actions::{ActionsFrozen, PlayerAction};
-use crate::world_interaction::condition::{ActiveConditions, ConditionAddEvent, ConditionId};
-use crate::world_interaction::dialog::resources::Page;
+use crate::world_interaction::condition::{ConditionAddEvent, ConditionContext, ConditionId};
+use crate::world_interaction::dialog::actions::run_dialog_actions;
+use crate::world_interaction::dialog::markup::parse_markup;
+use crate::world_interaction::dialog::resources::CachedMarkup;
 pub(crate) use crate::world_interaction::dialog::resources::{
-    CurrentDialog, Dialog, DialogEvent, DialogId, NextPage,
+    CurrentDialog, Dialog, DialogAction, DialogEvent, DialogId, NextPage,
 };
 use crate::GameState;
 use anyhow::{Context, Ok, Result};
 use bevy::prelude::*;
 use bevy_egui::egui::FontFamily::Proportional;
 use bevy_egui::egui::FontId;
-use bevy_egui::egui::TextStyle::{Body, Button};
+use bevy_egui::egui::TextStyle::{Body, Button, Heading};
 use bevy_egui::{egui, EguiContexts, EguiPlugin};
 use bevy_mod_sysfail::macros::*;
 use leafwing_input_manager::prelude::ActionState;
 use serde::{Deserialize, Serialize};
 use std::path::Path;
-use unicode_segmentation::UnicodeSegmentation;
 
+mod actions;
+mod markup;
 mod resources;
+#[cfg(feature = "dev")]
+mod validation;
 
 pub(crate) fn dialog_plugin(app: &mut App) {
     app.add_plugin(EguiPlugin)
         .register_type::<DialogId>()
+        .register_type::<DialogTarget>()
         .add_event::<DialogEvent>()
-        .add_systems((set_current_dialog, show_dialog).in_set(OnUpdate(GameState::Playing)));
+        .add_systems(
+            (set_current_dialog, show_dialog, run_dialog_actions)
+                .chain()
+                .in_set(OnUpdate(GameState::Playing)),
+        )
+        .add_system(add_dialog_targets.in_set(OnUpdate(GameState::Playing)));
+    #[cfg(feature = "dev")]
+    app.add_startup_system(validation::check_dialogs);
 }
 
-#[derive(Debug, Clone, Eq, PartialEq, Component, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Eq, PartialEq, Component, Reflect, Serialize, Deserialize, Default)]
+#[reflect(Component, Serialize, Deserialize, LevelComponent)]
 pub(crate) struct DialogTarget {
     pub(crate) dialog_id: DialogId,
 }
 
+fn add_dialog_targets(mut commands: Commands, tags: Query<(Entity, &SceneTags), Added<SceneTags>>) {
+    for (entity, tags) in tags.iter() {
+        if let Some(dialog_id) = tags.dialog() {
+            commands.entity(entity).insert(DialogTarget {
+                dialog_id: DialogId::new(dialog_id),
+            });
+        }
+    }
+}
+
 #[sysfail(log(level = "error"))]
 fn set_current_dialog(
     mut commands: Commands,
-    active_conditions: Res<ActiveConditions>,
+    conditions: ConditionContext,
     mut dialog_events: EventReader<DialogEvent>,
     dialogs: Res<Assets<Dialog>>,
     dialog_handles: Res<DialogAssets>,
@@ -72,22 +99,34 @@ fn set_current_dialog(
             dialog
                 .initial_page
                 .iter()
-                .find(|page| page.is_available(&active_conditions))
+                .find(|page| page.is_available(&conditions))
                 ?
                 .id
                 .clone()
                 .into()
         }).with_context(|| {
             format!(
-                "No valid active page for dialog {dialog:?}. Current conditions: {active_conditions:?}"
+                "No valid active page for dialog {dialog:?}. Current conditions: {:?}, counters: {:?}",
+                conditions.active_conditions, conditions.counters
             )
         })?;
+        // A dialog resumed at a given page was saved after the actions of that page already ran
+        let pending_actions = match dialog_event.page {
+            Some(_) => Vec::new(),
+            None => dialog
+                .pages
+                .get(&current_page)
+                .map(|page| page.actions.clone())
+                .unwrap_or_default(),
+        };
         commands.insert_resource(CurrentDialog {
             source: dialog_event.source,
             id: dialog_event.dialog.clone(),
             dialog: dialog.clone(),
             current_page,
             last_choice: None,
+            pending_actions,
+            cached_markup: None,
         });
         actions_frozen.freeze();
     }
@@ -98,7 +137,7 @@ fn set_current_dialog(
 fn show_dialog(
     mut commands: Commands,
     current_dialog: Option<ResMut<CurrentDialog>>,
-    active_conditions: Res<ActiveConditions>,
+    conditions: ConditionContext,
     mut condition_writer: EventWriter<ConditionAddEvent>,
     mut egui_contexts: EguiContexts,
     mut actions_frozen: ResMut<ActionsFrozen>,
@@ -106,6 +145,8 @@ fn show_dialog(
     time: Res<Time>,
     mut elapsed_time: Local<f32>,
     config: Res<GameConfig>,
+    locale: Res<Locale>,
+    localization: Localization,
 ) -> Result<()> {
     let Some(mut current_dialog) = current_dialog else {
             *elapsed_time = 0.0;
@@ -114,6 +155,23 @@ fn show_dialog(
 
     for actions in actions.iter() {
         let current_page = current_dialog.fetch_current_page()?;
+        let cached_markup = current_dialog
+            .cached_markup
+            .take()
+            .filter(|cached| {
+                cached.page == current_dialog.current_page && cached.language == locale.language
+            })
+            .unwrap_or_else(|| {
+                // Problems with the markup are reported by the dialog validation in dev builds
+                let (markup, _problems) = parse_markup(&localization.text(&current_page.text));
+                CachedMarkup {
+                    page: current_dialog.current_page.clone(),
+                    language: locale.language.clone(),
+                    markup,
+                }
+            });
+        let markup = &cached_markup.markup;
+        let letters_per_second = config.dialog.base_letters_per_second * current_page.talking_speed;
         get_dialog_window()
             .show(egui_contexts.ctx_mut(), |ui| {
                 // Get current context style
@@ -122,11 +180,25 @@ fn show_dialog(
                 ui.set_width(dialog_size.x);
                 ui.set_height(dialog_size.y);
 
-                let dialog_text = create_dialog_rich_text(&current_page, *elapsed_time, &config);
+                let base_format = egui::text::TextFormat {
+                    font_id: Body.resolve(ui.style()),
+                    color: ui.visuals().text_color(),
+                    ..default()
+                };
+                let revealed_text = markup.reveal(
+                    *elapsed_time,
+                    letters_per_second,
+                    &base_format,
+                    ui.visuals().strong_text_color(),
+                );
                 ui.vertical(|ui| {
                     ui.add_space(5.);
-                    ui.label(&dialog_text);
-                    if dialog_text == current_page.text {
+                    if let Some(speaker) = &markup.speaker {
+                        ui.label(egui::RichText::new(speaker).heading().strong());
+                        ui.add_space(3.);
+                    }
+                    ui.label(revealed_text.job);
+                    if revealed_text.is_complete {
                         ui.add_space(3.);
                         ui.separator();
                         ui.add_space(8.);
@@ -134,7 +206,8 @@ fn show_dialog(
                             ui,
                             &mut commands,
                             &mut current_dialog,
-                            &active_conditions,
+                            &conditions,
+                            &localization,
                             &mut condition_writer,
                             &mut actions_frozen,
                             actions,
@@ -150,6 +223,8 @@ fn show_dialog(
             .context("Failed to show dialog window")?
             .inner
             .context("Failed to fetch inner result when showing dialog window")??;
+        // Stale once a choice moved to another page, which the next frame notices
+        current_dialog.cached_markup = Some(cached_markup);
         let dt_speed_multiplier = if actions.pressed(PlayerAction::SpeedUpDialog) {
             4.
         } else {
@@ -164,7 +239,8 @@ fn present_choices(
     ui: &mut egui::Ui,
     commands: &mut Commands,
     current_dialog: &mut CurrentDialog,
-    active_conditions: &ActiveConditions,
+    conditions: &ConditionContext,
+    localization: &Localization,
     condition_writer: &mut EventWriter<ConditionAddEvent>,
     actions_frozen: &mut ActionsFrozen,
     actions: &ActionState<PlayerAction>,
@@ -173,9 +249,9 @@ fn present_choices(
 ) -> Result<()> {
     match next_page {
         NextPage::Continue(next_page_id) => {
-            let text = create_choice_rich_text(0, "Continue");
+            let text = create_choice_rich_text(0, &localization.get("dialog.continue"));
             if ui.button(text).clicked() || actions.just_pressed(PlayerAction::numbered_choice(1)) {
-                current_dialog.current_page = next_page_id;
+                current_dialog.go_to_page(next_page_id)?;
                 *elapsed_time = 0.0;
             }
         }
@@ -184,12 +260,11 @@ fn present_choices(
             for (index, (choice_id, choice)) in choices
                 .iter()
                 .filter(|(choice_id, choice)| {
-                    choice.is_available(active_conditions)
-                        && !was_just_picked(current_dialog, choice_id)
+                    choice.is_available(conditions) && !was_just_picked(current_dialog, choice_id)
                 })
                 .enumerate()
             {
-                let text = create_choice_rich_text(index, &choice.text);
+                let text = create_choice_rich_text(index, &localization.text(&choice.text));
                 if ui.button(&text).clicked()
                     || actions.just_pressed(PlayerAction::numbered_choice(index as u8 + 1))
                 {
@@ -198,8 +273,7 @@ fn present_choices(
             }
             if let Some((choice_id, choice)) = picked_choice {
                 condition_writer.send(ConditionAddEvent(choice_id.clone()));
-                current_dialog.last_choice = Some(choice_id);
-                current_dialog.current_page = choice.next_page_id;
+                current_dialog.pick_choice(choice_id, choice)?;
                 *elapsed_time = 0.0;
             }
         }
@@ -209,7 +283,8 @@ fn present_choices(
                 ui,
                 commands,
                 current_dialog,
-                active_conditions,
+                conditions,
+                localization,
                 condition_writer,
                 actions_frozen,
                 actions,
@@ -218,7 +293,7 @@ fn present_choices(
             )?;
         }
         NextPage::Exit => {
-            let text = create_choice_rich_text(0, "Exit");
+            let text = create_choice_rich_text(0, &localization.get("dialog.exit"));
             if ui.button(text).clicked() || actions.just_pressed(PlayerAction::numbered_choice(1)) {
                 commands.remove_resource::<CurrentDialog>();
                 actions_frozen.unfreeze();
@@ -244,6 +319,7 @@ fn get_dialog_window() -> egui::Window<'static> {
 
 fn set_dialog_style(style: &mut egui::Style) {
     style.text_styles = [
+        (Heading, FontId::new(18.0, Proportional)),
         (Body, FontId::new(16.0, Proportional)),
         (Button, FontId::new(14.0, Proportional)),
     ]
@@ -252,12 +328,6 @@ fn set_dialog_style(style: &mut egui::Style) {
     style.visuals.widgets.noninteractive.fg_stroke.color = egui::Color32::from_gray(250);
 }
 
-fn create_dialog_rich_text(page: &Page, elapsed_time: f32, config: &GameConfig) -> String {
-    let base_letters_per_second = config.dialog.base_letters_per_second;
-    let letters_to_display = (base_letters_per_second * page.talking_speed * elapsed_time) as usize;
-    page.text.graphemes(true).take(letters_to_display).collect()
-}
-
 fn create_choice_rich_text(index: usize, text: &str) -> String {
     format!("{}. {}", index + 1, text)
 }
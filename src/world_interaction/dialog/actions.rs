@@ -0,0 +1,83 @@
+use crate::combat::Constitution;
+use crate::file_system_interaction::level_serialization::WorldLoadRequest;
+use crate::level_instantiation::spawning::GameObject;
+use crate::player_control::actions::ActionsFrozen;
+use crate::player_control::player_embodiment::Player;
+use crate::world_interaction::condition::{ConditionAddEvent, ConditionRemoveEvent, Counters};
+use crate::world_interaction::dialog::{CurrentDialog, DialogAction};
+use crate::world_interaction::room::SelectPotionEvent;
+use crate::world_interaction::side_effects::SideEffects;
+use anyhow::{Context, Result};
+use bevy::prelude::*;
+use bevy_kira_audio::prelude::{Audio, AudioControl};
+use bevy_mod_sysfail::macros::*;
+use spew::prelude::*;
+
+#[sysfail(log(level = "error"))]
+pub(super) fn run_dialog_actions(
+    current_dialog: Option<ResMut<CurrentDialog>>,
+    mut add_condition_events: EventWriter<ConditionAddEvent>,
+    mut remove_condition_events: EventWriter<ConditionRemoveEvent>,
+    mut counters: ResMut<Counters>,
+    mut side_effects: ResMut<SideEffects>,
+    mut players: Query<&mut Constitution, With<Player>>,
+    transforms: Query<&GlobalTransform>,
+    mut spawn_events: EventWriter<SpawnEvent<GameObject, Transform>>,
+    mut select_potion_events: EventWriter<SelectPotionEvent>,
+    mut load_events: EventWriter<WorldLoadRequest>,
+    mut actions_frozen: ResMut<ActionsFrozen>,
+    audio: Res<Audio>,
+    asset_server: Res<AssetServer>,
+) -> Result<()> {
+    let Some(mut current_dialog) = current_dialog else {
+        return Ok(());
+    };
+    if current_dialog.pending_actions.is_empty() {
+        return Ok(());
+    }
+    let source = current_dialog.source;
+    for action in std::mem::take(&mut current_dialog.pending_actions) {
+        match action {
+            DialogAction::AddCondition(condition) => {
+                add_condition_events.send(ConditionAddEvent(condition))
+            }
+            DialogAction::RemoveCondition(condition) => {
+                remove_condition_events.send(ConditionRemoveEvent(condition))
+            }
+            DialogAction::AddToCounter(counter, amount) => counters.add(&counter, amount),
+            DialogAction::SetCounter(counter, value) => counters.set(&counter, value),
+            DialogAction::GrantSideEffect(side_effect) => side_effects.add_positive(side_effect),
+            DialogAction::InflictSideEffect(side_effect) => side_effects.add_negative(side_effect),
+            DialogAction::Heal(amount) => {
+                for mut constitution in players.iter_mut() {
+                    constitution.recover_health(amount);
+                }
+            }
+            DialogAction::Spawn { object, offset } => {
+                let source_transform = transforms
+                    .get(source)
+                    .context("Failed to get transform of dialog source")?
+                    .compute_transform();
+                let transform = Transform::from_translation(source_transform * offset)
+                    .with_rotation(source_transform.rotation);
+                spawn_events.send(SpawnEvent::with_data(object, transform));
+            }
+            DialogAction::LeaveRoom => {
+                select_potion_events.send(SelectPotionEvent);
+                actions_frozen.freeze();
+            }
+            DialogAction::PlaySound(path) => {
+                audio.play(asset_server.load(path.as_str()));
+            }
+            DialogAction::LoadWorld(filename) => {
+                // Loading removes the dialog, which would otherwise unfreeze the actions when it ends
+                actions_frozen.unfreeze();
+                load_events.send(WorldLoadRequest {
+                    filename,
+                    ..default()
+                });
+            }
+        }
+    }
+    Ok(())
+}
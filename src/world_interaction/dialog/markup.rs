@@ -0,0 +1,355 @@
+use anyhow::{bail, Context, Result};
+use bevy_egui::egui;
+use bevy_egui::egui::text::{LayoutJob, TextFormat};
+use bevy_egui::egui::Color32;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Bold text is drawn in the strong text color and this much bigger, since the default fonts have no bold variant.
+const STRONG_FONT_SCALE: f32 = 1.1;
+/// Shaking text is drawn this much smaller so that it can jitter up and down within its row.
+const SHAKE_FONT_SCALE: f32 = 0.8;
+/// How often shaking letters jump to a new position.
+const SHAKE_STEPS_PER_SECOND: f32 = 20.;
+
+/// Page text with its markup resolved. The supported tags are:
+/// - `[speaker=<name>]`: Shows the name above the text.
+/// - `[pause=<seconds>]`: Stops revealing the text for a while.
+/// - `[color=<name or #rrggbb>]...[/color]`: Colors the text.
+/// - `[b]...[/b]`: Emphasizes the text.
+/// - `[i]...[/i]`: Writes the text in italics.
+/// - `[speed=<factor>]...[/speed]`: Multiplies the talking speed.
+/// - `[shake]...[/shake]`: Makes the letters tremble.
+///
+/// `[[` writes a literal `[`.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub(super) struct DialogMarkup {
+    pub(super) speaker: Option<String>,
+    segments: Vec<Segment>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Segment {
+    Text { text: String, style: SpanStyle },
+    Pause(f32),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct SpanStyle {
+    color: Option<Color32>,
+    strong: bool,
+    italics: bool,
+    shake: bool,
+    speed: f32,
+}
+
+impl Default for SpanStyle {
+    fn default() -> Self {
+        Self {
+            color: None,
+            strong: false,
+            italics: false,
+            shake: false,
+            speed: 1.,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Span {
+    Color(Color32),
+    Strong,
+    Italics,
+    Speed(f32),
+    Shake,
+}
+
+impl Span {
+    fn name(self) -> &'static str {
+        match self {
+            Span::Color(_) => "color",
+            Span::Strong => "b",
+            Span::Italics => "i",
+            Span::Speed(_) => "speed",
+            Span::Shake => "shake",
+        }
+    }
+
+    fn apply(self, style: &mut SpanStyle) {
+        match self {
+            Span::Color(color) => style.color = Some(color),
+            Span::Strong => style.strong = true,
+            Span::Italics => style.italics = true,
+            Span::Speed(speed) => style.speed *= speed,
+            Span::Shake => style.shake = true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Tag {
+    Speaker(String),
+    Pause(f32),
+    Open(Span),
+    Close(String),
+}
+
+/// Parses the markup of the text. Malformed tags are written out as they are and unclosed spans end with the text,
+/// so that a mistake never hides the text. They are reported in the returned problems instead.
+pub(super) fn parse_markup(text: &str) -> (DialogMarkup, Vec<String>) {
+    let mut markup = DialogMarkup::default();
+    let mut problems = Vec::new();
+    let mut open_spans: Vec<(Span, SpanStyle)> = Vec::new();
+    let mut style = SpanStyle::default();
+    let mut current_text = String::new();
+    let mut rest = text;
+    while let Some(index) = rest.find('[') {
+        current_text.push_str(&rest[..index]);
+        rest = &rest[index..];
+        if let Some(after_escape) = rest.strip_prefix("[[") {
+            current_text.push('[');
+            rest = after_escape;
+            continue;
+        }
+        let Some(end) = rest.find(']') else {
+            problems.push(format!("Unclosed bracket in \"{rest}\""));
+            break;
+        };
+        let tag = &rest[1..end];
+        let raw_tag = &rest[..=end];
+        rest = &rest[end + 1..];
+        match parse_tag(tag) {
+            Ok(Tag::Speaker(speaker)) => {
+                if markup.speaker.is_some() {
+                    problems.push(format!("More than one speaker in \"{raw_tag}\""));
+                }
+                markup.speaker = Some(speaker);
+            }
+            Ok(Tag::Pause(duration)) => {
+                markup.push_text(&mut current_text, style);
+                markup.segments.push(Segment::Pause(duration));
+            }
+            Ok(Tag::Open(span)) => {
+                markup.push_text(&mut current_text, style);
+                open_spans.push((span, style));
+                span.apply(&mut style);
+            }
+            Ok(Tag::Close(name)) => match open_spans.last() {
+                Some((span, previous_style)) if span.name() == name => {
+                    markup.push_text(&mut current_text, style);
+                    style = *previous_style;
+                    open_spans.pop();
+                }
+                Some((span, _)) => {
+                    problems.push(format!(
+                        "\"{raw_tag}\" does not match the open \"[{}]\"",
+                        span.name()
+                    ));
+                    current_text.push_str(raw_tag);
+                }
+                None => {
+                    problems.push(format!("\"{raw_tag}\" closes nothing"));
+                    current_text.push_str(raw_tag);
+                }
+            },
+            Err(e) => {
+                problems.push(format!("{e:#}"));
+                current_text.push_str(raw_tag);
+            }
+        }
+    }
+    current_text.push_str(rest);
+    markup.push_text(&mut current_text, style);
+    for (span, _) in open_spans {
+        problems.push(format!("\"[{}]\" is never closed", span.name()));
+    }
+    (markup, problems)
+}
+
+fn parse_tag(tag: &str) -> Result<Tag> {
+    if let Some(name) = tag.strip_prefix('/') {
+        return Ok(Tag::Close(name.to_string()));
+    }
+    let (name, value) = match tag.split_once('=') {
+        Some((name, value)) => (name, Some(value)),
+        None => (tag, None),
+    };
+    let tag = match (name, value) {
+        ("speaker", Some(speaker)) => Tag::Speaker(speaker.to_string()),
+        ("pause", Some(duration)) => Tag::Pause(parse_positive(duration)?),
+        ("color", Some(color)) => Tag::Open(Span::Color(parse_color(color)?)),
+        ("b", None) => Tag::Open(Span::Strong),
+        ("i", None) => Tag::Open(Span::Italics),
+        ("speed", Some(speed)) => Tag::Open(Span::Speed(parse_positive(speed)?)),
+        ("shake", None) => Tag::Open(Span::Shake),
+        _ => bail!("Unknown tag \"[{tag}]\""),
+    };
+    Ok(tag)
+}
+
+fn parse_positive(value: &str) -> Result<f32> {
+    let number: f32 = value
+        .parse()
+        .with_context(|| format!("Failed to parse \"{value}\" as a number"))?;
+    if number <= 0. || !number.is_finite() {
+        bail!("Expected a positive number, but got \"{value}\"");
+    }
+    Ok(number)
+}
+
+fn parse_color(value: &str) -> Result<Color32> {
+    let color = match value {
+        "white" => Color32::WHITE,
+        "gray" => Color32::GRAY,
+        "red" => Color32::LIGHT_RED,
+        "green" => Color32::LIGHT_GREEN,
+        "blue" => Color32::LIGHT_BLUE,
+        "yellow" => Color32::LIGHT_YELLOW,
+        "gold" => Color32::GOLD,
+        _ => {
+            let hex = value
+                .strip_prefix('#')
+                .filter(|hex| hex.len() == 6 && hex.is_ascii())
+                .with_context(|| format!("Unknown color \"{value}\""))?;
+            let channel = |index: usize| {
+                u8::from_str_radix(&hex[index..index + 2], 16)
+                    .with_context(|| format!("Failed to parse color \"{value}\""))
+            };
+            Color32::from_rgb(channel(0)?, channel(2)?, channel(4)?)
+        }
+    };
+    Ok(color)
+}
+
+/// The part of the text the typewriter has revealed so far.
+pub(super) struct RevealedText {
+    pub(super) job: LayoutJob,
+    /// Whether every letter and pause has been shown.
+    pub(super) is_complete: bool,
+}
+
+impl DialogMarkup {
+    fn push_text(&mut self, text: &mut String, style: SpanStyle) {
+        if text.is_empty() {
+            return;
+        }
+        self.segments.push(Segment::Text {
+            text: std::mem::take(text),
+            style,
+        });
+    }
+
+    /// Reveals `letters_per_second` graphemes per second, sped up or slowed down by `[speed]` spans and stopped by `[pause]`s.
+    /// Tags take no time, so they never show up letter by letter.
+    pub(super) fn reveal(
+        &self,
+        elapsed_time: f32,
+        letters_per_second: f32,
+        base_format: &TextFormat,
+        strong_color: Color32,
+    ) -> RevealedText {
+        let mut job = LayoutJob::default();
+        let mut remaining_time = elapsed_time;
+        let mut shake_index = 0;
+        for segment in self.segments.iter() {
+            match segment {
+                Segment::Pause(duration) => {
+                    if remaining_time < *duration {
+                        return RevealedText {
+                            job,
+                            is_complete: false,
+                        };
+                    }
+                    remaining_time -= duration;
+                }
+                Segment::Text { text, style } => {
+                    let speed = letters_per_second * style.speed;
+                    let graphemes: Vec<_> = text.graphemes(true).collect();
+                    let letters_to_display = (speed * remaining_time) as usize;
+                    let is_segment_complete = letters_to_display >= graphemes.len();
+                    let visible = &graphemes[..letters_to_display.min(graphemes.len())];
+                    let format = style.get_format(base_format, strong_color);
+                    if style.shake {
+                        for grapheme in visible {
+                            let mut format = format.clone();
+                            format.font_id.size *= SHAKE_FONT_SCALE;
+                            format.valign = get_shake_alignment(elapsed_time, shake_index);
+                            job.append(grapheme, 0., format);
+                            shake_index += 1;
+                        }
+                    } else {
+                        job.append(&visible.concat(), 0., format);
+                    }
+                    if !is_segment_complete {
+                        return RevealedText {
+                            job,
+                            is_complete: false,
+                        };
+                    }
+                    remaining_time -= graphemes.len() as f32 / speed;
+                }
+            }
+        }
+        RevealedText {
+            job,
+            is_complete: true,
+        }
+    }
+}
+
+impl SpanStyle {
+    fn get_format(&self, base_format: &TextFormat, strong_color: Color32) -> TextFormat {
+        let mut format = base_format.clone();
+        if self.strong {
+            format.color = strong_color;
+            format.font_id.size *= STRONG_FONT_SCALE;
+        }
+        if let Some(color) = self.color {
+            format.color = color;
+        }
+        format.italics = self.italics;
+        format
+    }
+}
+
+/// Picks a pseudo-random vertical position for a shaking letter that changes [`SHAKE_STEPS_PER_SECOND`] times per second.
+fn get_shake_alignment(elapsed_time: f32, index: usize) -> egui::Align {
+    let step = (elapsed_time * SHAKE_STEPS_PER_SECOND) as usize;
+    match (step.wrapping_mul(31) ^ index.wrapping_mul(17)) % 3 {
+        0 => egui::Align::TOP,
+        1 => egui::Align::Center,
+        _ => egui::Align::BOTTOM,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_text(markup: &DialogMarkup) -> String {
+        markup
+            .segments
+            .iter()
+            .filter_map(|segment| match segment {
+                Segment::Text { text, .. } => Some(text.as_str()),
+                Segment::Pause(_) => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn writes_out_malformed_tags() {
+        let (markup, problems) = parse_markup("[b]Hello[/i][/b] [/shake][wave]there");
+        assert_eq!(problems.len(), 3, "{problems:#?}");
+        assert_eq!(get_text(&markup), "Hello[/i] [/shake][wave]there");
+    }
+
+    #[test]
+    fn parses_tags() {
+        let (markup, problems) =
+            parse_markup("[speaker=Follower]Hi[pause=0.5] [color=red][[friend][/color]!");
+        assert!(problems.is_empty(), "{problems:#?}");
+        assert_eq!(markup.speaker.as_deref(), Some("Follower"));
+        assert_eq!(get_text(&markup), "Hi [friend]!");
+        assert!(markup.segments.contains(&Segment::Pause(0.5)));
+    }
+}
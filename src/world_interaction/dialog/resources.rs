@@ -1,4 +1,8 @@
-use crate::world_interaction::condition::{ActiveConditions, ConditionId};
+use crate::level_instantiation::spawning::GameObject;
+use crate::localization::LocalizedText;
+use crate::world_interaction::condition::{ConditionContext, ConditionId, Requirement};
+use crate::world_interaction::dialog::markup::DialogMarkup;
+use crate::world_interaction::side_effects::SideEffect;
 use anyhow::{Context, Result};
 use bevy::prelude::*;
 use bevy::reflect::TypeUuid;
@@ -21,8 +25,37 @@ pub(crate) struct CurrentDialog {
     pub(crate) dialog: Dialog,
     pub(crate) current_page: PageId,
     pub(crate) last_choice: Option<ConditionId>,
+    /// Actions of the pages shown and choices picked that did not run yet.
+    #[serde(default)]
+    pub(crate) pending_actions: Vec<DialogAction>,
+    /// Parsed again when the page or the language changes.
+    #[serde(skip)]
+    pub(super) cached_markup: Option<CachedMarkup>,
+}
+
+/// The markup of a page, so that it is not parsed every frame the page is shown.
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct CachedMarkup {
+    pub(super) page: PageId,
+    pub(super) language: String,
+    pub(super) markup: DialogMarkup,
 }
 impl CurrentDialog {
+    pub(crate) fn go_to_page(&mut self, page_id: PageId) -> Result<()> {
+        let page = self.fetch_page(&page_id)?;
+        self.pending_actions.extend(page.actions);
+        self.current_page = page_id;
+        Ok(())
+    }
+    pub(crate) fn pick_choice(
+        &mut self,
+        choice_id: ConditionId,
+        choice: DialogChoice,
+    ) -> Result<()> {
+        self.pending_actions.extend(choice.actions);
+        self.last_choice = Some(choice_id);
+        self.go_to_page(choice.next_page_id)
+    }
     pub(crate) fn fetch_page(&self, page_id: &PageId) -> Result<Page> {
         self.dialog
             .pages
@@ -42,29 +75,38 @@ pub(crate) struct Dialog {
     pub(crate) pages: HashMap<PageId, Page>,
 }
 
-#[derive(Debug, Clone, Eq, PartialEq, Reflect, Serialize, Deserialize, Default, FromReflect)]
-#[reflect(Serialize, Deserialize)]
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize, Default)]
 pub(crate) struct InitialPage {
     pub(crate) id: PageId,
     #[serde(default, skip_serializing_if = "HashSet::is_empty")]
     pub(crate) positive_requirements: HashSet<ConditionId>,
     #[serde(default, skip_serializing_if = "HashSet::is_empty")]
     pub(crate) negative_requirements: HashSet<ConditionId>,
+    /// Has to hold in addition to the positive and negative requirements.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) requirement: Option<Requirement>,
 }
 
 impl InitialPage {
-    pub(crate) fn is_available(&self, active_conditions: &ActiveConditions) -> bool {
-        self.positive_requirements.is_subset(&active_conditions.0)
-            && self.negative_requirements.is_disjoint(&active_conditions.0)
+    pub(crate) fn is_available(&self, context: &ConditionContext) -> bool {
+        is_available(
+            &self.positive_requirements,
+            &self.negative_requirements,
+            &self.requirement,
+            context,
+        )
     }
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub(crate) struct Page {
-    pub(crate) text: String,
+    pub(crate) text: LocalizedText,
     #[serde(default = "get_default_talking_speed")]
     pub(crate) talking_speed: f32,
     pub(crate) next_page: NextPage,
+    /// Run when the page is shown.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) actions: Vec<DialogAction>,
 }
 
 fn get_default_talking_speed() -> f32 {
@@ -77,11 +119,12 @@ impl Default for Page {
             text: default(),
             talking_speed: get_default_talking_speed(),
             next_page: default(),
+            actions: default(),
         }
     }
 }
 
-#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub(crate) enum NextPage {
     /// There is only one automatic option for the next page
     Continue(PageId),
@@ -98,25 +141,74 @@ impl Default for NextPage {
     }
 }
 
-#[derive(Debug, Clone, Eq, PartialEq, Default, Reflect, Serialize, Deserialize, FromReflect)]
-#[reflect(Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
 pub(crate) struct DialogChoice {
     /// The player's answer
-    pub(crate) text: String,
+    pub(crate) text: LocalizedText,
     pub(crate) next_page_id: PageId,
     #[serde(default, skip_serializing_if = "HashSet::is_empty")]
     pub(crate) positive_requirements: HashSet<ConditionId>,
     #[serde(default, skip_serializing_if = "HashSet::is_empty")]
     pub(crate) negative_requirements: HashSet<ConditionId>,
+    /// Has to hold in addition to the positive and negative requirements.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) requirement: Option<Requirement>,
+    /// Run when the choice is picked, before the actions of the next page.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) actions: Vec<DialogAction>,
 }
 
 impl DialogChoice {
-    pub(crate) fn is_available(&self, active_conditions: &ActiveConditions) -> bool {
-        self.positive_requirements.is_subset(&active_conditions.0)
-            && self.negative_requirements.is_disjoint(&active_conditions.0)
+    pub(crate) fn is_available(&self, context: &ConditionContext) -> bool {
+        is_available(
+            &self.positive_requirements,
+            &self.negative_requirements,
+            &self.requirement,
+            context,
+        )
     }
 }
 
+fn is_available(
+    positive_requirements: &HashSet<ConditionId>,
+    negative_requirements: &HashSet<ConditionId>,
+    requirement: &Option<Requirement>,
+    context: &ConditionContext,
+) -> bool {
+    positive_requirements.is_subset(&context.active_conditions.0)
+        && negative_requirements.is_disjoint(&context.active_conditions.0)
+        && requirement
+            .as_ref()
+            .map_or(true, |requirement| requirement.evaluate(context))
+}
+
+/// Lets conversations drive gameplay. Runs through [`DialogChoice::actions`] and [`Page::actions`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) enum DialogAction {
+    AddCondition(ConditionId),
+    RemoveCondition(ConditionId),
+    /// Adds the amount to the [`Counters`](crate::world_interaction::condition::Counters) entry, which may be negative.
+    AddToCounter(String, i32),
+    SetCounter(String, i32),
+    GrantSideEffect(SideEffect),
+    InflictSideEffect(SideEffect),
+    /// Recovers this much of the player's health.
+    Heal(f32),
+    /// Spawns the object at the entity the dialog was started from.
+    Spawn {
+        object: GameObject,
+        /// Relative to the entity the dialog was started from.
+        #[serde(default)]
+        offset: Vec3,
+    },
+    /// Lets the player choose the side effects and path of the next room, like the exit does.
+    LeaveRoom,
+    /// Path of the sound relative to the assets directory.
+    PlaySound(String),
+    /// Ends the dialog and loads the level from `levels/<filename>.lvl.ron`.
+    LoadWorld(String),
+}
+
 #[derive(
     Debug,
     Clone,
@@ -134,7 +226,7 @@ impl DialogChoice {
 #[serde(from = "String", into = "String")]
 pub(crate) struct DialogId(pub(crate) String);
 impl DialogId {
-    pub(crate) fn _new(id: &str) -> Self {
+    pub(crate) fn new(id: &str) -> Self {
         Self(id.to_string())
     }
 }
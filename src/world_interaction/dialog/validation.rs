@@ -0,0 +1,412 @@
+use crate::localization::{LocalizedText, StringTable, FALLBACK_LANGUAGE};
+use crate::world_interaction::condition::{ConditionId, Requirement};
+use crate::world_interaction::dialog::markup::parse_markup;
+use crate::world_interaction::dialog::resources::{Dialog, NextPage, PageId};
+use anyhow::{Context, Result};
+use bevy::prelude::*;
+use bevy::utils::{HashMap, HashSet};
+use bevy_mod_sysfail::macros::*;
+use std::collections::VecDeque;
+
+/// Requirements with more distinct conditions and comparisons than this are assumed to be satisfiable,
+/// since checking them tries every combination.
+const MAX_CHECKED_TERMS: usize = 12;
+
+/// Finds mistakes in a dialog that would otherwise only show up once the player runs into them:
+/// references to pages that do not exist, `SameAs` cycles, pages that can never be shown,
+/// choices or initial pages whose requirements can never be met, translations without the fallback language
+/// and malformed markup in page text.
+pub(crate) fn validate_dialog(dialog: &Dialog) -> Vec<String> {
+    let mut problems = Vec::new();
+    let mut page_ids: Vec<_> = dialog.pages.keys().collect();
+    page_ids.sort_by_key(|id| &id.0);
+
+    let mut check_reference = |source: String, target: &PageId| {
+        if !dialog.pages.contains_key(target) {
+            problems.push(format!("{source} refers to missing page \"{}\"", target.0));
+        }
+    };
+    for initial_page in dialog.initial_page.iter() {
+        check_reference("Initial page".to_string(), &initial_page.id);
+    }
+    for &page_id in page_ids.iter() {
+        let source = format!("Page \"{}\"", page_id.0);
+        match &dialog.pages[page_id].next_page {
+            NextPage::Continue(target) | NextPage::SameAs(target) => {
+                check_reference(source, target)
+            }
+            NextPage::Choice(choices) => {
+                for (choice_id, choice) in choices.iter() {
+                    check_reference(
+                        format!("{source}, choice \"{}\"", choice_id.0),
+                        &choice.next_page_id,
+                    );
+                }
+            }
+            NextPage::Exit => {}
+        }
+    }
+
+    for (source, text) in get_texts(dialog, &page_ids) {
+        if let LocalizedText::Translations(translations) = text
+            && !translations.contains_key(FALLBACK_LANGUAGE)
+        {
+            problems.push(format!(
+                "{source} has no text in the fallback language \"{FALLBACK_LANGUAGE}\""
+            ));
+        }
+    }
+    for &page_id in page_ids.iter() {
+        let texts: Vec<_> = match &dialog.pages[page_id].text {
+            LocalizedText::Literal(text) => vec![text],
+            LocalizedText::Key(_) => Vec::new(),
+            LocalizedText::Translations(translations) => {
+                let mut texts: Vec<_> = translations.values().collect();
+                texts.sort();
+                texts
+            }
+        };
+        for text in texts {
+            let (_markup, markup_problems) = parse_markup(text);
+            for problem in markup_problems {
+                problems.push(format!(
+                    "Page \"{}\" has invalid markup: {problem}",
+                    page_id.0
+                ));
+            }
+        }
+    }
+
+    let mut cyclic_pages = HashSet::new();
+    for &page_id in page_ids.iter() {
+        if let Err(cycle) = resolve_next_page(dialog, page_id) {
+            if cycle.iter().any(|id| cyclic_pages.contains(id)) {
+                continue;
+            }
+            let names: Vec<_> = cycle.iter().map(|id| format!("\"{}\"", id.0)).collect();
+            problems.push(format!("`SameAs` cycle between pages {}", names.join(", ")));
+            cyclic_pages.extend(cycle);
+        }
+    }
+
+    let mut always_available = None;
+    for initial_page in dialog.initial_page.iter() {
+        let requirement = combine_requirements(
+            &initial_page.positive_requirements,
+            &initial_page.negative_requirements,
+            &initial_page.requirement,
+        );
+        if let Some(earlier_page) = always_available {
+            problems.push(format!(
+                "Initial page \"{}\" is never available because \"{}\" before it always is",
+                initial_page.id.0, earlier_page
+            ));
+        } else if !is_satisfiable(&requirement) {
+            problems.push(format!(
+                "Initial page \"{}\" is never available because its requirements contradict each other",
+                initial_page.id.0
+            ));
+        } else if requirement == Requirement::All(Vec::new()) {
+            always_available = Some(initial_page.id.0.as_str());
+        }
+    }
+
+    let mut reachable: HashSet<&PageId> = HashSet::new();
+    let mut queue: VecDeque<&PageId> = dialog
+        .initial_page
+        .iter()
+        .map(|initial_page| &initial_page.id)
+        .collect();
+    while let Some(page_id) = queue.pop_front() {
+        if !dialog.pages.contains_key(page_id) || !reachable.insert(page_id) {
+            continue;
+        }
+        match resolve_next_page(dialog, page_id) {
+            Ok(Some(NextPage::Continue(target))) => queue.push_back(target),
+            Ok(Some(NextPage::Choice(choices))) => {
+                for (choice_id, choice) in choices.iter() {
+                    let requirement = combine_requirements(
+                        &choice.positive_requirements,
+                        &choice.negative_requirements,
+                        &choice.requirement,
+                    );
+                    if is_satisfiable(&requirement) {
+                        queue.push_back(&choice.next_page_id);
+                    } else {
+                        problems.push(format!(
+                            "Page \"{}\", choice \"{}\" is never available because its requirements contradict each other",
+                            page_id.0, choice_id.0
+                        ));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    for &page_id in page_ids.iter() {
+        if !reachable.contains(page_id) {
+            problems.push(format!("Page \"{}\" can never be reached", page_id.0));
+        }
+    }
+    problems
+}
+
+/// The text of every page and choice, along with where it is used.
+fn get_texts<'a>(dialog: &'a Dialog, page_ids: &[&PageId]) -> Vec<(String, &'a LocalizedText)> {
+    let mut texts = Vec::new();
+    for &page_id in page_ids.iter() {
+        let page = &dialog.pages[page_id];
+        let source = format!("Page \"{}\"", page_id.0);
+        if let NextPage::Choice(choices) = &page.next_page {
+            for (choice_id, choice) in choices.iter() {
+                texts.push((
+                    format!("{source}, choice \"{}\"", choice_id.0),
+                    &choice.text,
+                ));
+            }
+        }
+        texts.push((source, &page.text));
+    }
+    texts
+}
+
+/// Reports `Key`s in the dialog that the string table of the fallback language does not contain
+/// or whose text there has malformed markup.
+fn validate_keys(dialog: &Dialog, fallback_table: &StringTable) -> Vec<String> {
+    let mut problems = Vec::new();
+    let mut page_ids: Vec<_> = dialog.pages.keys().collect();
+    page_ids.sort_by_key(|id| &id.0);
+    for (source, text) in get_texts(dialog, &page_ids) {
+        if let LocalizedText::Key(key) = text && !fallback_table.0.contains_key(key) {
+            problems.push(format!(
+                "{source} refers to missing key \"{key}\" in the fallback language \"{FALLBACK_LANGUAGE}\""
+            ));
+        }
+    }
+    for &page_id in page_ids.iter() {
+        if let LocalizedText::Key(key) = &dialog.pages[page_id].text
+            && let Some(text) = fallback_table.0.get(key)
+        {
+            let (_markup, markup_problems) = parse_markup(text);
+            for problem in markup_problems {
+                problems.push(format!(
+                    "Page \"{}\" refers to key \"{key}\" with invalid markup: {problem}",
+                    page_id.0
+                ));
+            }
+        }
+    }
+    problems
+}
+
+/// Follows `SameAs` until reaching the [`NextPage`] that is actually used.
+/// Returns `None` if a page along the way is missing and the pages of the cycle if there is one.
+fn resolve_next_page<'a>(
+    dialog: &'a Dialog,
+    page_id: &'a PageId,
+) -> Result<Option<&'a NextPage>, Vec<&'a PageId>> {
+    let mut visited = vec![page_id];
+    let mut current = page_id;
+    loop {
+        let Some(page) = dialog.pages.get(current) else {
+            return Ok(None);
+        };
+        let NextPage::SameAs(target) = &page.next_page else {
+            return Ok(Some(&page.next_page));
+        };
+        if let Some(index) = visited.iter().position(|&visited| visited == target) {
+            return Err(visited.split_off(index));
+        }
+        visited.push(target);
+        current = target;
+    }
+}
+
+fn combine_requirements(
+    positive_requirements: &HashSet<ConditionId>,
+    negative_requirements: &HashSet<ConditionId>,
+    requirement: &Option<Requirement>,
+) -> Requirement {
+    let positive = positive_requirements
+        .iter()
+        .map(|condition| Requirement::Active(condition.clone()));
+    let negative = negative_requirements
+        .iter()
+        .map(|condition| Requirement::Not(Box::new(Requirement::Active(condition.clone()))));
+    Requirement::All(
+        positive
+            .chain(negative)
+            .chain(requirement.iter().cloned())
+            .collect(),
+    )
+}
+
+/// Tries every combination of the conditions and comparisons in the requirement.
+/// Comparisons are treated as independent of each other, so contradicting comparisons like
+/// `Compare(RoomNumber, Less, 2)` and `Compare(RoomNumber, Greater, 5)` are not detected.
+fn is_satisfiable(requirement: &Requirement) -> bool {
+    let mut terms = Vec::new();
+    collect_terms(requirement, &mut terms);
+    if terms.len() > MAX_CHECKED_TERMS {
+        return true;
+    }
+    (0..1_u32 << terms.len()).any(|assignment| {
+        let values = terms
+            .iter()
+            .enumerate()
+            .map(|(index, &term)| (term, assignment & (1 << index) != 0))
+            .collect();
+        evaluate_assuming(requirement, &values)
+    })
+}
+
+fn collect_terms<'a>(requirement: &'a Requirement, terms: &mut Vec<&'a Requirement>) {
+    match requirement {
+        Requirement::Active(_) | Requirement::Compare(..) => {
+            if !terms.contains(&requirement) {
+                terms.push(requirement);
+            }
+        }
+        Requirement::Not(requirement) => collect_terms(requirement, terms),
+        Requirement::All(requirements) | Requirement::Any(requirements) => {
+            for requirement in requirements.iter() {
+                collect_terms(requirement, terms);
+            }
+        }
+    }
+}
+
+fn evaluate_assuming(requirement: &Requirement, values: &HashMap<&Requirement, bool>) -> bool {
+    match requirement {
+        Requirement::Active(_) | Requirement::Compare(..) => values[requirement],
+        Requirement::Not(requirement) => !evaluate_assuming(requirement, values),
+        Requirement::All(requirements) => requirements
+            .iter()
+            .all(|requirement| evaluate_assuming(requirement, values)),
+        Requirement::Any(requirements) => requirements
+            .iter()
+            .any(|requirement| evaluate_assuming(requirement, values)),
+    }
+}
+
+/// Logs the problems [`validate_dialog`] finds in every dialog in `assets/dialogs`,
+/// as well as keys missing from the string table of the [`FALLBACK_LANGUAGE`].
+#[sysfail(log(level = "error"))]
+pub(super) fn check_dialogs() -> Result<()> {
+    let fallback_path = format!("./assets/locales/{FALLBACK_LANGUAGE}.strings.ron");
+    let serialized = std::fs::read_to_string(&fallback_path)
+        .with_context(|| format!("Failed to read string table at {fallback_path}"))?;
+    let fallback_table: StringTable = ron::from_str(&serialized)
+        .with_context(|| format!("Failed to deserialize string table at {fallback_path}"))?;
+    for path in glob::glob("./assets/dialogs/*.dlg.ron").context("Failed to read glob pattern")? {
+        let path = path.context("Failed to read dialog path")?;
+        let serialized = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read dialog at {path:?}"))?;
+        let dialog: Dialog = match ron::from_str(&serialized) {
+            Ok(dialog) => dialog,
+            Err(e) => {
+                error!("Failed to deserialize dialog at {path:?}: {e}");
+                continue;
+            }
+        };
+        let mut problems = validate_dialog(&dialog);
+        problems.extend(validate_keys(&dialog, &fallback_table));
+        for problem in problems.iter() {
+            error!("Invalid dialog at {path:?}: {problem}");
+        }
+        if problems.is_empty() {
+            debug!("Validated dialog at {path:?}");
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world_interaction::dialog::resources::{InitialPage, Page};
+    use std::fs;
+    use std::path::Path;
+
+    /// Has one of every kind of markup problem, see [`assert_markup_problems`].
+    const INVALID_MARKUP: &str = "[b]Hello[/i][/b] [/shake][wave]there[i]";
+
+    fn create_dialog(text: LocalizedText) -> Dialog {
+        let page_id = PageId("page:start".to_string());
+        Dialog {
+            initial_page: vec![InitialPage {
+                id: page_id.clone(),
+                ..default()
+            }],
+            pages: [(
+                page_id,
+                Page {
+                    text,
+                    next_page: NextPage::Exit,
+                    ..default()
+                },
+            )]
+            .into_iter()
+            .collect(),
+        }
+    }
+
+    fn assert_markup_problems(problems: &[String]) {
+        let expected = [
+            "\"[/i]\" does not match the open \"[b]\"",
+            "\"[/shake]\" closes nothing",
+            "Unknown tag \"[wave]\"",
+            "\"[i]\" is never closed",
+        ];
+        assert_eq!(problems.len(), expected.len(), "{problems:#?}");
+        for expected in expected {
+            assert!(
+                problems.iter().any(|problem| problem.contains(expected)),
+                "Missing problem {expected:?} in {problems:#?}"
+            );
+        }
+    }
+
+    #[test]
+    fn dialog_assets_are_valid() -> Result<()> {
+        let assets = Path::new(env!("CARGO_MANIFEST_DIR")).join("assets");
+        let fallback_path = assets.join(format!("locales/{FALLBACK_LANGUAGE}.strings.ron"));
+        let serialized = fs::read_to_string(&fallback_path)
+            .with_context(|| format!("Failed to read string table at {fallback_path:?}"))?;
+        let fallback_table: StringTable = ron::from_str(&serialized)
+            .with_context(|| format!("Failed to deserialize string table at {fallback_path:?}"))?;
+        let pattern = assets.join("dialogs/*.dlg.ron");
+        for path in glob::glob(&pattern.to_string_lossy()).context("Failed to read glob pattern")? {
+            let path = path.context("Failed to read dialog path")?;
+            let serialized = fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read dialog at {path:?}"))?;
+            let dialog: Dialog = ron::from_str(&serialized)
+                .with_context(|| format!("Failed to deserialize dialog at {path:?}"))?;
+            let mut problems = validate_dialog(&dialog);
+            problems.extend(validate_keys(&dialog, &fallback_table));
+            assert!(
+                problems.is_empty(),
+                "Invalid dialog at {path:?}: {problems:#?}"
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn reports_invalid_markup_in_page_text() {
+        let dialog = create_dialog(LocalizedText::Literal(INVALID_MARKUP.to_string()));
+        assert_markup_problems(&validate_dialog(&dialog));
+    }
+
+    #[test]
+    fn reports_invalid_markup_in_string_table() {
+        let dialog = create_dialog(LocalizedText::Key("dialog.test".to_string()));
+        let fallback_table = StringTable(
+            [("dialog.test".to_string(), INVALID_MARKUP.to_string())]
+                .into_iter()
+                .collect(),
+        );
+        assert!(validate_dialog(&dialog).is_empty());
+        assert_markup_problems(&validate_keys(&dialog, &fallback_table));
+    }
+}
@@ -0,0 +1,140 @@
+use crate::level_instantiation::spawning::scene_tags::{SceneTagSystemSet, SceneTags};
+use crate::player_control::actions::ActionsFrozen;
+use crate::world_interaction::condition::{ConditionAddEvent, ConditionId};
+use crate::world_interaction::dialog::{DialogEvent, DialogId, DialogTarget};
+use crate::world_interaction::room::SelectPotionEvent;
+use crate::GameState;
+use anyhow::{Context, Result};
+use bevy::prelude::*;
+use bevy_mod_sysfail::macros::*;
+
+pub(crate) fn interaction_plugin(app: &mut App) {
+    app.add_event::<InteractEvent>()
+        .init_resource::<OpenSign>()
+        .add_systems(
+            (add_interactables.after(SceneTagSystemSet), interact)
+                .in_set(OnUpdate(GameState::Playing)),
+        );
+}
+
+/// Something the player can use by pressing [`PlayerAction::Interact`](crate::player_control::actions::PlayerAction::Interact)
+/// while it is the best candidate around, see [`interactions_ui_plugin`](crate::world_interaction::interactions_ui::interactions_ui_plugin).
+#[derive(Debug, Clone, PartialEq, Component)]
+pub(crate) struct Interactable {
+    /// Key of the prompt in the [`StringTable`](crate::localization::StringTable)s.
+    pub(crate) prompt: String,
+    /// How close the player has to be, in meters.
+    pub(crate) range: f32,
+    /// Whether the camera has to look roughly at the object.
+    pub(crate) requires_facing: bool,
+    pub(crate) action: InteractionAction,
+}
+
+impl Interactable {
+    pub(crate) fn new(action: InteractionAction) -> Self {
+        let prompt = match &action {
+            InteractionAction::LeaveRoom => "interaction.leave-room",
+            InteractionAction::StartDialog(_) => "interaction.talk",
+            InteractionAction::PickUp(_) => "interaction.pick-up",
+            InteractionAction::OpenDoor => "interaction.open-door",
+            InteractionAction::ReadSign(_) => "interaction.read-sign",
+        };
+        Self {
+            prompt: prompt.to_string(),
+            range: 4.0,
+            requires_facing: true,
+            action,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum InteractionAction {
+    /// Lets the player choose the side effects of the next room, as long as the current one is cleared.
+    LeaveRoom,
+    StartDialog(DialogId),
+    /// Adds the condition and removes the item from the level.
+    PickUp(ConditionId),
+    /// Removes the door from the level.
+    OpenDoor,
+    /// Shows the text until the player interacts again or moves on.
+    ReadSign(String),
+}
+
+/// Sent when the player uses an [`Interactable`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct InteractEvent {
+    pub(crate) target: Entity,
+}
+
+/// The sign whose text is currently shown.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Resource, Default)]
+pub(crate) struct OpenSign(pub(crate) Option<Entity>);
+
+fn add_interactables(
+    mut commands: Commands,
+    dialog_targets: Query<(Entity, &DialogTarget), Added<DialogTarget>>,
+    tags: Query<(Entity, &SceneTags), Added<SceneTags>>,
+) {
+    for (entity, dialog_target) in dialog_targets.iter() {
+        commands
+            .entity(entity)
+            .insert(Interactable::new(InteractionAction::StartDialog(
+                dialog_target.dialog_id.clone(),
+            )));
+    }
+    for (entity, tags) in tags.iter() {
+        let action = if let Some(text) = tags.sign() {
+            InteractionAction::ReadSign(text.to_string())
+        } else if let Some(condition) = tags.item() {
+            InteractionAction::PickUp(ConditionId(condition.to_string()))
+        } else if tags.is_door() {
+            InteractionAction::OpenDoor
+        } else {
+            continue;
+        };
+        commands.entity(entity).insert(Interactable::new(action));
+    }
+}
+
+#[sysfail(log(level = "error"))]
+fn interact(
+    mut commands: Commands,
+    mut interact_events: EventReader<InteractEvent>,
+    interactables: Query<&Interactable>,
+    mut open_sign: ResMut<OpenSign>,
+    mut dialog_events: EventWriter<DialogEvent>,
+    mut condition_events: EventWriter<ConditionAddEvent>,
+    mut select_potion_events: EventWriter<SelectPotionEvent>,
+    mut actions_frozen: ResMut<ActionsFrozen>,
+) -> Result<()> {
+    for event in interact_events.iter() {
+        let interactable = interactables
+            .get(event.target)
+            .context("Failed to get interactable of interact event")?;
+        match &interactable.action {
+            InteractionAction::LeaveRoom => {
+                select_potion_events.send(SelectPotionEvent);
+                actions_frozen.freeze();
+            }
+            InteractionAction::StartDialog(dialog) => dialog_events.send(DialogEvent {
+                dialog: dialog.clone(),
+                source: event.target,
+                page: None,
+            }),
+            InteractionAction::PickUp(condition) => {
+                condition_events.send(ConditionAddEvent(condition.clone()));
+                commands.entity(event.target).despawn_recursive();
+            }
+            InteractionAction::OpenDoor => commands.entity(event.target).despawn_recursive(),
+            InteractionAction::ReadSign(_) => {
+                open_sign.0 = if open_sign.0 == Some(event.target) {
+                    None
+                } else {
+                    Some(event.target)
+                };
+            }
+        }
+    }
+    Ok(())
+}
@@ -1,76 +1,215 @@
-use crate::player_control::actions::{ActionsFrozen, PlayerAction};
+use crate::combat::collision::get_min_backstab_angle;
+use crate::combat::Enemy;
+use crate::localization::Localization;
+use crate::player_control::actions::{get_binding_name, PlayerAction};
 use crate::player_control::camera::IngameCamera;
+use crate::player_control::player_embodiment::Player;
 use crate::util::criteria::is_frozen;
 use crate::util::trait_extension::F32Ext;
-use crate::world_interaction::room::{CurrentRoom, Exit, SelectPotionEvent};
+use crate::world_interaction::interaction::{
+    InteractEvent, Interactable, InteractionAction, OpenSign,
+};
+use crate::world_interaction::room::{CurrentRoom, PreviousRoom, TriggerState};
 use crate::GameState;
 use anyhow::{Context, Result};
+use bevy::math::Vec3Swizzles;
 use bevy::prelude::*;
 use bevy::window::PrimaryWindow;
 use bevy_egui::{egui, EguiContexts};
 use bevy_mod_sysfail::macros::*;
-use leafwing_input_manager::prelude::ActionState;
+use leafwing_input_manager::prelude::{ActionState, InputMap};
 
 pub(crate) fn interactions_ui_plugin(app: &mut App) {
     app.add_system(
         display_interaction_prompt
             .run_if(not(is_frozen))
             .in_set(OnUpdate(GameState::Playing)),
+    )
+    .add_system(
+        display_deathblow_prompt
+            .run_if(not(is_frozen))
+            .in_set(OnUpdate(GameState::Playing)),
     );
 }
 
-fn is_facing_target(camera_transform: Transform, target_transform: Transform) -> bool {
+/// Objects are only considered in front of the camera if they are within this angle of where it looks, in degrees.
+const MAX_FACING_ANGLE: f32 = 45.0;
+
+/// How far above the origin of an [`Interactable`] its prompt is shown, in meters.
+const PROMPT_HEIGHT: f32 = 0.5;
+
+/// How far above the origin of an [`Enemy`] the deathblow prompt is shown, in meters.
+const DEATHBLOW_PROMPT_HEIGHT: f32 = 1.0;
+
+/// Where a point in the world appears on screen in egui coordinates, or the center of the window if it is not in view.
+fn get_prompt_position(
+    camera: &Camera,
+    camera_global_transform: &GlobalTransform,
+    window: &Window,
+    translation: Vec3,
+) -> egui::Pos2 {
+    camera
+        .world_to_viewport(camera_global_transform, translation)
+        .map(|position| egui::Pos2::new(position.x, window.height() - position.y))
+        .unwrap_or(egui::Pos2::new(window.width() / 2., window.height() / 2.))
+}
+
+fn get_facing_angle(camera_transform: Transform, target_translation: Vec3) -> f32 {
     let look_direction = camera_transform.forward();
-    let target_direction = target_transform.translation - camera_transform.translation;
-    let angle = look_direction.angle_between(target_direction);
-    angle.to_degrees() < 45.0
+    let target_direction = target_translation - camera_transform.translation;
+    look_direction.angle_between(target_direction).to_degrees()
+}
+
+fn is_facing_target(camera_transform: Transform, target_transform: Transform) -> bool {
+    get_facing_angle(camera_transform, target_transform.translation) < MAX_FACING_ANGLE
+}
+
+/// Lower is better. Closer objects and objects nearer to the center of the view win.
+/// `None` if the object is out of range or not faced although it has to be.
+fn get_interaction_score(
+    player_translation: Vec3,
+    camera_transform: Transform,
+    target_translation: Vec3,
+    interactable: &Interactable,
+) -> Option<f32> {
+    let distance = player_translation.distance(target_translation);
+    if distance > interactable.range {
+        return None;
+    }
+    let angle = get_facing_angle(camera_transform, target_translation);
+    if interactable.requires_facing && angle >= MAX_FACING_ANGLE {
+        return None;
+    }
+    Some(distance / interactable.range + angle / 180.0)
 }
 
 #[sysfail(log(level = "error"))]
 fn display_interaction_prompt(
     mut egui_contexts: EguiContexts,
-    players: Query<
-        (&Transform, &ActionState<PlayerAction>),
-        (Without<IngameCamera>, Without<Exit>),
-    >,
-    cameras: Query<&Transform, (With<IngameCamera>, Without<Exit>)>,
+    players: Query<(&Transform, &ActionState<PlayerAction>), Without<IngameCamera>>,
+    cameras: Query<(&Transform, &Camera, &GlobalTransform), With<IngameCamera>>,
     primary_windows: Query<&Window, With<PrimaryWindow>>,
-    exits: Query<&Transform, With<Exit>>,
+    interactables: Query<(Entity, &GlobalTransform, &Interactable), Without<PreviousRoom>>,
     current_room: Res<CurrentRoom>,
-    mut select_potion_events: EventWriter<SelectPotionEvent>,
-    mut actions_frozen: ResMut<ActionsFrozen>,
+    trigger_state: Res<TriggerState>,
+    mut open_sign: ResMut<OpenSign>,
+    mut interact_events: EventWriter<InteractEvent>,
+    localization: Localization,
 ) -> Result<()> {
     for (player_transform, actions) in players.iter() {
-        for exit_transform in exits.iter() {
-            for camera_transform in cameras.iter() {
-                let is_near_exit = (player_transform.translation - exit_transform.translation)
-                    .length_squared()
-                    < 4.0.squared();
-                let should_display =
-                    is_facing_target(*camera_transform, *exit_transform) && is_near_exit;
+        for (camera_transform, camera, camera_global_transform) in cameras.iter() {
+            let target = interactables
+                .iter()
+                .filter_map(|(entity, transform, interactable)| {
+                    let score = get_interaction_score(
+                        player_transform.translation,
+                        *camera_transform,
+                        transform.translation(),
+                        interactable,
+                    )?;
+                    Some((score, entity, transform, interactable))
+                })
+                .min_by(|(a, ..), (b, ..)| a.total_cmp(b));
+            let Some((_score, entity, transform, interactable)) = target else {
+                open_sign.0 = None;
+                continue;
+            };
+            if open_sign.0.is_some() && open_sign.0 != Some(entity) {
+                open_sign.0 = None;
+            }
+            let window = primary_windows
+                .get_single()
+                .context("Failed to get primary window")?;
+            let blocked_message = match interactable.action {
+                InteractionAction::LeaveRoom if trigger_state.is_exit_locked => {
+                    Some("interaction.blocked")
+                }
+                InteractionAction::LeaveRoom if !current_room.cleared => {
+                    Some("interaction.enemies-left")
+                }
+                _ => None,
+            };
+            let message = match (&interactable.action, blocked_message) {
+                (_, Some(key)) => localization.get(key),
+                (InteractionAction::ReadSign(text), _) if open_sign.0 == Some(entity) => {
+                    text.clone()
+                }
+                _ => localization.get(&interactable.prompt),
+            };
+            let prompt_position = get_prompt_position(
+                camera,
+                camera_global_transform,
+                window,
+                transform.translation() + Vec3::Y * PROMPT_HEIGHT,
+            );
+            egui::Window::new("Interaction")
+                .collapsible(false)
+                .title_bar(false)
+                .auto_sized()
+                .fixed_pos(prompt_position)
+                .show(egui_contexts.ctx_mut(), |ui| {
+                    ui.label(message);
+                });
+            if blocked_message.is_none() && actions.just_pressed(PlayerAction::Interact) {
+                interact_events.send(InteractEvent { target: entity });
+            }
+        }
+    }
+    Ok(())
+}
+
+#[sysfail(log(level = "error"))]
+fn display_deathblow_prompt(
+    mut egui_contexts: EguiContexts,
+    players: Query<
+        (&Transform, &InputMap<PlayerAction>),
+        (With<Player>, Without<IngameCamera>, Without<Enemy>),
+    >,
+    cameras: Query<(&Transform, &Camera, &GlobalTransform), (With<IngameCamera>, Without<Enemy>)>,
+    primary_windows: Query<&Window, With<PrimaryWindow>>,
+    enemies: Query<(&Transform, &Enemy)>,
+    localization: Localization,
+) -> Result<()> {
+    for (player_transform, input_map) in players.iter() {
+        for (enemy_transform, enemy) in enemies.iter() {
+            if enemy.is_aggroed || enemy.is_dead {
+                continue;
+            }
+            for (camera_transform, camera, camera_global_transform) in cameras.iter() {
+                let enemy_to_player = player_transform.translation - enemy_transform.translation;
+                let is_near_enemy = enemy_to_player.length_squared() < 2.0.squared();
+                let is_behind_enemy = enemy_transform
+                    .forward()
+                    .xz()
+                    .angle_between(enemy_to_player.xz())
+                    .to_degrees()
+                    .abs()
+                    > get_min_backstab_angle();
+                let should_display = is_near_enemy
+                    && is_behind_enemy
+                    && is_facing_target(*camera_transform, *enemy_transform);
                 if !should_display {
                     continue;
                 }
                 let window = primary_windows
                     .get_single()
                     .context("Failed to get primary window")?;
-                let message = if current_room.cleared {
-                    "E: Choose side effects"
-                } else {
-                    "Kill all enemies before leaving"
-                };
-                egui::Window::new("Interaction")
+                let prompt_position = get_prompt_position(
+                    camera,
+                    camera_global_transform,
+                    window,
+                    enemy_transform.translation + Vec3::Y * DEATHBLOW_PROMPT_HEIGHT,
+                );
+                let key = get_binding_name(input_map, PlayerAction::Attack, &localization);
+                egui::Window::new("Deathblow")
                     .collapsible(false)
                     .title_bar(false)
                     .auto_sized()
-                    .fixed_pos(egui::Pos2::new(window.width() / 2., window.height() / 2.))
+                    .fixed_pos(prompt_position)
                     .show(egui_contexts.ctx_mut(), |ui| {
-                        ui.label(message);
+                        ui.label(localization.format("interaction.deathblow", &[("key", &key)]));
                     });
-                if current_room.cleared && actions.just_pressed(PlayerAction::Interact) {
-                    select_potion_events.send(SelectPotionEvent);
-                    actions_frozen.freeze();
-                }
+                return Ok(());
             }
         }
     }
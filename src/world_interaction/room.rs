@@ -1,6 +1,11 @@
 use crate::combat::Enemy;
+use crate::file_system_interaction::asset_loading::RoomAssets;
 use crate::file_system_interaction::audio::AudioHandles;
-use crate::level_instantiation::spawning::GameObject;
+use crate::file_system_interaction::config::GameConfig;
+use crate::file_system_interaction::game_state_serialization::RunRestore;
+use crate::level_instantiation::spawning::scene_tags::SceneTagSystemSet;
+use crate::level_instantiation::spawning::{GameObject, RoomSpawner};
+use crate::localization::Localization;
 use crate::player_control::actions::ActionsFrozen;
 use crate::player_control::player_embodiment::Player;
 use crate::world_interaction::side_effects::potions::{generate_potions, Potion, POTION_COUNT};
@@ -9,28 +14,69 @@ use crate::GameState;
 use bevy::prelude::*;
 use bevy_egui::{egui, EguiContexts};
 use bevy_kira_audio::AudioInstance;
+pub(crate) use definition::{MusicCue, RoomDefinition, RoomId};
+use encounter::{advance_encounter, get_current_room_definition, start_encounter};
+pub(crate) use encounter::{CurrentEncounter, Wave, WaveSpawnEvent};
+use layout::get_run_pool;
+pub(crate) use layout::{RoomKind, RoomNode, RunLayout};
 use rand::Rng;
+use serde::{Deserialize, Serialize};
 use spew::prelude::SpawnEvent;
+use trigger::{
+    react_to_triggers, reset_trigger_state, spawn_trigger_volumes, update_trigger_volumes,
+};
+pub(crate) use trigger::{TriggerEnterEvent, TriggerExitEvent, TriggerReaction, TriggerState};
+
+mod definition;
+mod encounter;
+mod layout;
+mod trigger;
 
 pub(crate) fn exit_plugin(app: &mut App) {
     app.add_event::<EnterRoomEvent>()
         .add_event::<RoomClearEvent>()
         .add_event::<LeaveRoomEvent>()
         .add_event::<SelectPotionEvent>()
+        .add_event::<WaveSpawnEvent>()
+        .add_event::<TriggerEnterEvent>()
+        .add_event::<TriggerExitEvent>()
         .register_type::<CurrentRoom>()
+        .register_type::<CurrentEncounter>()
         .register_type::<SelectPotionUi>()
+        .register_type::<RunLayout>()
         .init_resource::<CurrentRoom>()
         .init_resource::<SelectPotionUi>()
+        .init_resource::<TriggerState>()
         .add_systems(
             (
+                start_run
+                    .run_if(not(resource_exists::<RunLayout>()))
+                    .run_if(not(resource_exists::<RunRestore>())),
                 enter_first_room,
-                update_room,
-                leave_room,
-                activate_select_potion_ui,
-                select_potion,
+                start_encounter
+                    .run_if(not(resource_exists::<CurrentEncounter>()))
+                    .run_if(not(resource_exists::<RoomTransition>()))
+                    .run_if(not(resource_exists::<RunRestore>())),
+                advance_encounter.run_if(resource_exists::<CurrentEncounter>()),
+                update_room.run_if(not(resource_exists::<RunRestore>())),
+                leave_room.run_if(resource_exists::<RunLayout>()),
+                cross_into_next_room,
+                activate_select_potion_ui.run_if(resource_exists::<RunLayout>()),
+                select_potion.run_if(resource_exists::<RunLayout>()),
             )
                 .chain()
                 .in_set(OnUpdate(GameState::Playing)),
+        )
+        .add_systems(
+            (
+                spawn_trigger_volumes,
+                update_trigger_volumes,
+                reset_trigger_state,
+                react_to_triggers,
+            )
+                .chain()
+                .after(SceneTagSystemSet)
+                .in_set(OnUpdate(GameState::Playing)),
         );
 }
 
@@ -40,8 +86,29 @@ pub(crate) struct Exit;
 #[derive(Debug, Clone, Component)]
 pub(crate) struct Room;
 
-#[derive(Debug, Clone, Resource, Reflect, FromReflect, Default)]
-#[reflect(Resource)]
+/// Marks the entities of the room the player is leaving. They are unloaded once the player crossed into the next room.
+#[derive(Debug, Clone, Copy, Component)]
+pub(crate) struct PreviousRoom;
+
+/// Present while the player walks from the previous room into the next one.
+/// The next room is spawned at the `[exit]` of the previous room and moved so that its `[entrance]` lines up with it,
+/// so that the rooms form one continuous space.
+#[derive(Debug, Clone, Copy, PartialEq, Resource)]
+pub(crate) struct RoomTransition {
+    /// Where the exit of the previous room and the entrance of the next room meet.
+    pub(crate) anchor: Transform,
+    /// Horizontal direction pointing from the previous room into the next one.
+    direction: Vec3,
+    pub(crate) is_next_room_aligned: bool,
+}
+
+/// How far past the anchor the player has to walk before the previous room is unloaded.
+const CROSSING_DISTANCE: f32 = 1.5;
+
+#[derive(
+    Debug, Clone, PartialEq, Resource, Reflect, FromReflect, Serialize, Deserialize, Default,
+)]
+#[reflect(Resource, Serialize, Deserialize)]
 pub(crate) struct CurrentRoom {
     pub(crate) cleared: bool,
     pub(crate) number: usize,
@@ -58,6 +125,8 @@ impl CurrentRoom {
 #[reflect(Resource)]
 struct SelectPotionUi {
     potions: Option<[Potion; POTION_COUNT]>,
+    /// Index of the chosen next room in the [`RunLayout`].
+    next_room: Option<usize>,
 }
 
 #[derive(Debug, Clone)]
@@ -67,11 +136,27 @@ pub(crate) struct EnterRoomEvent;
 pub(crate) struct RoomClearEvent;
 
 #[derive(Debug, Clone)]
-pub(crate) struct LeaveRoomEvent;
+pub(crate) struct LeaveRoomEvent {
+    /// Index of the next room in the [`RunLayout`].
+    pub(crate) next_room: usize,
+}
 
 #[derive(Debug, Clone)]
 pub(crate) struct SelectPotionEvent;
 
+/// Starts a new run whenever a level was loaded without restoring a saved run.
+fn start_run(
+    mut commands: Commands,
+    config: Res<GameConfig>,
+    room_assets: Res<RoomAssets>,
+    definitions: Res<Assets<RoomDefinition>>,
+) {
+    let seed = config.run.seed.unwrap_or_else(|| rand::thread_rng().gen());
+    info!("Starting run with seed {seed}");
+    let pool = get_run_pool(&room_assets, &definitions);
+    commands.insert_resource(RunLayout::generate(seed, &config.run, &pool));
+}
+
 fn enter_first_room(
     mut start_room_events: EventWriter<EnterRoomEvent>,
     mut loaded: Local<bool>,
@@ -92,8 +177,16 @@ fn update_room(
     players: Query<(), With<Player>>,
     mut room_clear_events: EventWriter<RoomClearEvent>,
     mut current_room: ResMut<CurrentRoom>,
+    encounter: Option<Res<CurrentEncounter>>,
+    rooms: Query<&GameObject, (With<Room>, Without<PreviousRoom>)>,
+    room_assets: Res<RoomAssets>,
+    definitions: Res<Assets<RoomDefinition>>,
 ) {
-    if !players.is_empty() && enemies.is_empty() {
+    let Some(encounter) = encounter else {
+        return;
+    };
+    let definition = get_current_room_definition(&rooms, &room_assets, &definitions);
+    if !players.is_empty() && enemies.is_empty() && encounter.is_finished(definition) {
         room_clear_events.send(RoomClearEvent);
         current_room.cleared = true;
     }
@@ -102,9 +195,19 @@ fn update_room(
 fn activate_select_potion_ui(
     mut events: EventReader<SelectPotionEvent>,
     mut select_potion_ui: ResMut<SelectPotionUi>,
+    mut run_layout: ResMut<RunLayout>,
+    config: Res<GameConfig>,
+    room_assets: Res<RoomAssets>,
+    definitions: Res<Assets<RoomDefinition>>,
 ) {
     for _ in events.iter() {
+        if run_layout.is_at_end() {
+            let pool = get_run_pool(&room_assets, &definitions);
+            *run_layout = run_layout.generate_next_floor(&config.run, &pool);
+            info!("Continuing run on floor {}", run_layout.floor);
+        }
         select_potion_ui.potions = Some(generate_potions());
+        select_potion_ui.next_room = run_layout.next_nodes().map(|(index, _)| index).next();
     }
 }
 
@@ -113,80 +216,162 @@ fn select_potion(
     mut leave_room_events: EventWriter<LeaveRoomEvent>,
     mut select_potion_ui: ResMut<SelectPotionUi>,
     mut side_effects: ResMut<SideEffects>,
+    run_layout: Res<RunLayout>,
+    localization: Localization,
 ) {
     let Some(potions) = select_potion_ui.potions.clone() else {
         return;
     };
-    egui::Window::new("Select a Potion")
+    let Some(mut next_room) = select_potion_ui.next_room else {
+        return;
+    };
+    egui::Window::new(localization.get("potion.window-title"))
         .resizable(false)
         .collapsible(false)
         .default_pos([400., 400.])
         .show(egui_contexts.ctx_mut(), |ui| {
-            ui.label("The pirates left some potions behind. Which one do you want to take?");
+            if run_layout.current_node().next.len() > 1 {
+                ui.label(localization.get("potion.path-splits"));
+                for (index, node) in run_layout.next_nodes() {
+                    ui.radio_value(&mut next_room, index, node.kind.describe(&localization));
+                }
+                ui.separator();
+            }
+            ui.label(localization.get("potion.choose"));
             ui.separator();
             egui::Grid::new("potion_grid")
                 .min_col_width(50.)
                 .spacing([30., 3.])
                 .show(ui, |ui| {
                     for potion in potions.iter() {
-                        ui.heading(&potion.name);
+                        ui.heading(potion.name(&localization));
                     }
                     ui.end_row();
                     for potion in potions.iter() {
-                        ui.label(&potion.positive_side_effect.format_positive());
+                        ui.label(&potion.positive_side_effect.format_positive(&localization));
                     }
                     ui.end_row();
                     for _potion in potions.iter() {
-                        ui.label("BUT");
+                        ui.label(localization.get("potion.but"));
                     }
                     ui.end_row();
                     for potion in potions.iter() {
-                        ui.label(&potion.negative_side_effect.format_negative());
+                        ui.label(&potion.negative_side_effect.format_negative(&localization));
                     }
                     ui.end_row();
                     for potion in potions.iter() {
                         ui.horizontal_centered(|ui| {
-                            if ui.button("Drink!").clicked() {
+                            if ui.button(localization.get("potion.drink")).clicked() {
                                 side_effects.add_positive(potion.positive_side_effect);
                                 side_effects.add_negative(potion.negative_side_effect);
                                 select_potion_ui.potions = None;
-                                leave_room_events.send(LeaveRoomEvent);
+                                leave_room_events.send(LeaveRoomEvent { next_room });
                             }
                         });
                     }
                 });
         });
+    if select_potion_ui.potions.is_some() {
+        select_potion_ui.next_room = Some(next_room);
+    }
 }
 
 fn leave_room(
     mut commands: Commands,
     mut leave_room_events: EventReader<LeaveRoomEvent>,
     mut current_room: ResMut<CurrentRoom>,
-    mut spawn_events: EventWriter<SpawnEvent<GameObject, Transform>>,
-    rooms: Query<Entity, With<Room>>,
+    mut run_layout: ResMut<RunLayout>,
+    mut spawn_events: EventWriter<SpawnEvent<RoomSpawner, (RoomId, Transform)>>,
+    rooms: Query<
+        (
+            Entity,
+            Option<&GameObject>,
+            &GlobalTransform,
+            Option<&PreviousRoom>,
+        ),
+        With<Room>,
+    >,
+    exits: Query<&GlobalTransform, (With<Exit>, Without<PreviousRoom>)>,
     mut actions_frozen: ResMut<ActionsFrozen>,
     mut enter_room_events: EventWriter<EnterRoomEvent>,
 ) {
-    for _ in leave_room_events.iter() {
+    for leave_room_event in leave_room_events.iter() {
         actions_frozen.unfreeze();
+        commands.remove_resource::<CurrentEncounter>();
         current_room.enter_next();
-        spawn_events.send(SpawnEvent::new(choose_room()));
-        enter_room_events.send(EnterRoomEvent);
-        for room in rooms.iter() {
-            commands.entity(room).despawn_recursive();
+        run_layout.current = leave_room_event.next_room;
+        let next_room = run_layout.current_node().room;
+        info!(
+            "Choosing room {} at depth {}",
+            next_room,
+            run_layout.current_node().depth
+        );
+        // Only happens if the player left the next room before fully crossing into it
+        for (entity, .., previous_room) in rooms.iter() {
+            if previous_room.is_some() {
+                commands.entity(entity).despawn_recursive();
+            }
+        }
+
+        let Some(exit) = exits.iter().next().map(GlobalTransform::compute_transform) else {
+            // Without an exit to attach to, the next room replaces the current one
+            for (entity, ..) in rooms.iter() {
+                commands.entity(entity).despawn_recursive();
+            }
+            commands.remove_resource::<RoomTransition>();
+            spawn_events.send(SpawnEvent::with_data(
+                RoomSpawner::Room,
+                (next_room, Transform::default()),
+            ));
+            enter_room_events.send(EnterRoomEvent);
+            continue;
+        };
+        let exit = exit.with_scale(Vec3::ONE);
+        let room_origin = rooms
+            .iter()
+            .find(|(_, game_object, ..)| {
+                game_object.map_or(false, |game_object| game_object.is_room())
+            })
+            .map(|(_, _, transform, _)| transform.translation());
+        let direction = room_origin
+            .map(|origin| exit.translation - origin)
+            .map(|direction| Vec3::new(direction.x, 0., direction.z))
+            .filter(|direction| direction.length_squared() > f32::EPSILON)
+            .unwrap_or_else(|| exit.forward())
+            .normalize_or_zero();
+        for (entity, ..) in rooms.iter() {
+            commands.entity(entity).insert(PreviousRoom);
         }
+        commands.insert_resource(RoomTransition {
+            anchor: exit,
+            direction,
+            is_next_room_aligned: false,
+        });
+        spawn_events.send(SpawnEvent::with_data(RoomSpawner::Room, (next_room, exit)));
     }
 }
 
-fn choose_room() -> GameObject {
-    let mut rng = rand::thread_rng();
-    let room = rng.gen_range(0..=3);
-    info!("Choosing room {}", room);
-    match room {
-        0 => GameObject::IntroRoom,
-        1 => GameObject::RoomOne,
-        2 => GameObject::RoomTwo,
-        3 => GameObject::RoomThree,
-        _ => GameObject::IntroRoom,
+fn cross_into_next_room(
+    mut commands: Commands,
+    room_transition: Option<Res<RoomTransition>>,
+    players: Query<&Transform, With<Player>>,
+    previous_rooms: Query<Entity, With<PreviousRoom>>,
+    mut enter_room_events: EventWriter<EnterRoomEvent>,
+) {
+    let Some(room_transition) = room_transition else {
+        return;
+    };
+    let Ok(player_transform) = players.get_single() else {
+        return;
+    };
+    let distance_past_anchor = (player_transform.translation - room_transition.anchor.translation)
+        .dot(room_transition.direction);
+    if !room_transition.is_next_room_aligned || distance_past_anchor < CROSSING_DISTANCE {
+        return;
+    }
+    for entity in previous_rooms.iter() {
+        commands.entity(entity).despawn_recursive();
     }
+    commands.remove_resource::<RoomTransition>();
+    enter_room_events.send(EnterRoomEvent);
 }
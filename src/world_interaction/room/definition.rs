@@ -0,0 +1,127 @@
+use crate::file_system_interaction::asset_loading::RoomAssets;
+use crate::world_interaction::room::{RoomKind, TriggerReaction, Wave};
+use bevy::prelude::*;
+use bevy::reflect::TypeUuid;
+use bevy::utils::HashSet;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::sync::{LazyLock, Mutex};
+
+/// Describes a room of the pool, read from `assets/rooms/<id>.room.ron`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TypeUuid)]
+#[uuid = "4b0a6a3e-7d1c-4f55-9a1f-3c8f0e62b9d4"]
+pub(crate) struct RoomDefinition {
+    /// Path of the glTF scene of the room, relative to the assets directory.
+    pub(crate) scene: String,
+    /// Compared against the difficulty curve of the run when picking rooms.
+    pub(crate) difficulty: u32,
+    /// `combat`, `rest`, `shop` and `boss` make the room eligible for that [`RoomKind`] in a run.
+    #[serde(default)]
+    pub(crate) tags: Vec<String>,
+    /// Maximum number of enemies spawned from the room's `[enemy:...]` tags. `None` spawns all of them.
+    #[serde(default)]
+    pub(crate) enemy_budget: Option<u32>,
+    #[serde(default)]
+    pub(crate) music: MusicCue,
+    /// Spawned one after the other once the room was entered. The room is only cleared after the last one.
+    #[serde(default)]
+    pub(crate) waves: Vec<Wave>,
+    /// What happens when the player enters or leaves the `[trigger: <id>]` volumes of the room.
+    #[serde(default)]
+    pub(crate) reactions: Vec<TriggerReaction>,
+}
+
+impl RoomDefinition {
+    pub(crate) fn kinds(&self) -> impl Iterator<Item = RoomKind> + '_ {
+        self.tags.iter().filter_map(|tag| RoomKind::from_tag(tag))
+    }
+}
+
+/// The music that plays while in the room.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize, Default)]
+pub(crate) enum MusicCue {
+    #[default]
+    IntroAndLoop,
+    IntroAndLoopFast,
+    FastLoopOnly,
+}
+
+/// Name of a room definition, e.g. `room_one` for `rooms/room_one.room.ron`.
+/// Interned so that [`GameObject`](crate::level_instantiation::spawning::GameObject) stays `Copy`.
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Reflect, FromReflect)]
+#[reflect_value(PartialEq, Hash, Serialize, Deserialize)]
+pub(crate) struct RoomId(&'static str);
+
+static ROOM_IDS: LazyLock<Mutex<HashSet<&'static str>>> = LazyLock::new(default);
+
+impl RoomId {
+    pub(crate) fn new(name: &str) -> Self {
+        let mut room_ids = ROOM_IDS.lock().expect("Failed to lock room ids");
+        if let Some(id) = room_ids.get(name) {
+            return Self(*id);
+        }
+        let id: &'static str = Box::leak(name.to_owned().into_boxed_str());
+        room_ids.insert(id);
+        Self(id)
+    }
+
+    pub(crate) fn from_path(path: &str) -> Option<Self> {
+        path.strip_prefix("rooms/")?
+            .strip_suffix(".room.ron")
+            .map(Self::new)
+    }
+
+    pub(crate) fn path(self) -> String {
+        format!("rooms/{}.room.ron", self.0)
+    }
+
+    pub(crate) fn get_definition<'a>(
+        self,
+        room_assets: &RoomAssets,
+        definitions: &'a Assets<RoomDefinition>,
+    ) -> Option<&'a RoomDefinition> {
+        let definition = room_assets
+            .rooms
+            .get(&self.path())
+            .and_then(|handle| definitions.get(handle));
+        if definition.is_none() {
+            error!(
+                "Failed to get room definition \"{}\". Available rooms: {:?}",
+                self.path(),
+                room_assets.rooms.keys()
+            );
+        }
+        definition
+    }
+}
+
+impl fmt::Debug for RoomId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("RoomId").field(&self.0).finish()
+    }
+}
+
+impl fmt::Display for RoomId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.0)
+    }
+}
+
+impl Serialize for RoomId {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for RoomId {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer).map(|name| Self::new(&name))
+    }
+}
+
+/// Names no room, only there so that configs containing room ids can be defaulted.
+impl Default for RoomId {
+    fn default() -> Self {
+        Self("")
+    }
+}
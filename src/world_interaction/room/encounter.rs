@@ -0,0 +1,193 @@
+use crate::combat::archetype::{EnemyParameters, EnemySpawner};
+use crate::combat::Enemy;
+use crate::file_system_interaction::asset_loading::RoomAssets;
+use crate::level_instantiation::spawning::scene_tags::SceneTags;
+use crate::level_instantiation::spawning::GameObject;
+use crate::world_interaction::room::trigger::TriggerVolume;
+use crate::world_interaction::room::{PreviousRoom, Room, RoomDefinition};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Enemies spawned into the room after it was entered, in addition to the ones tagged with `[enemy: ...]` in its scene.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct Wave {
+    #[serde(default)]
+    pub(crate) trigger: WaveTrigger,
+    /// Seconds between the trigger firing and the wave spawning.
+    #[serde(default)]
+    pub(crate) delay: f32,
+    pub(crate) enemies: Vec<WaveEnemy>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub(crate) enum WaveTrigger {
+    /// Fires as soon as the previous wave spawned, or the room was entered for the first wave.
+    #[default]
+    Immediately,
+    /// Fires once every enemy in the room is dead.
+    RoomCleared,
+    /// Fires once the player is inside the room's `[trigger: <id>]` volume.
+    PlayerEnters(String),
+    /// Only fires through a [`TriggerAction::SpawnWave`](super::trigger::TriggerAction::SpawnWave) reaction.
+    Scripted,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct WaveEnemy {
+    /// Same names as in `[enemy: <name>]` tags.
+    pub(crate) enemy: String,
+    /// Same as `level` in `[enemy: <name>, level: <level>]` tags. Defaults to 1.
+    #[serde(default)]
+    pub(crate) level: Option<u32>,
+    /// Id of a `[spawn: <id>]` point in the room's scene.
+    pub(crate) spawn_point: String,
+}
+
+/// Progress through the waves of the room the player is in. Absent while walking into the next room.
+#[derive(
+    Debug, Clone, PartialEq, Resource, Reflect, FromReflect, Serialize, Deserialize, Default,
+)]
+#[reflect(Resource, Serialize, Deserialize)]
+pub(crate) struct CurrentEncounter {
+    /// Index into [`RoomDefinition::waves`].
+    next_wave: usize,
+    /// Seconds until the next wave spawns, once its trigger fired.
+    countdown: Option<f32>,
+    /// Set after spawning a wave until its enemies showed up, so that the room does not count as cleared in between.
+    is_waiting_for_enemies: bool,
+}
+
+impl CurrentEncounter {
+    /// Continues an encounter as saved in an older format.
+    pub(crate) fn from_progress(
+        next_wave: usize,
+        countdown: Option<f32>,
+        is_waiting_for_enemies: bool,
+    ) -> Self {
+        Self {
+            next_wave,
+            countdown,
+            is_waiting_for_enemies,
+        }
+    }
+
+    pub(crate) fn is_finished(&self, definition: Option<&RoomDefinition>) -> bool {
+        let wave_count = definition.map_or(0, |definition| definition.waves.len());
+        self.next_wave >= wave_count && !self.is_waiting_for_enemies
+    }
+
+    /// Starts the countdown of the next wave as if its trigger fired.
+    pub(crate) fn trigger_next_wave(&mut self, definition: &RoomDefinition) {
+        if self.countdown.is_none() && let Some(wave) = definition.waves.get(self.next_wave) {
+            self.countdown = Some(wave.delay);
+        }
+    }
+}
+
+/// Sent for every enemy spawned by a wave so that its arrival can be shown.
+#[derive(Debug, Clone, PartialEq, Reflect, Serialize, Deserialize, FromReflect, Default)]
+#[reflect(Serialize, Deserialize)]
+pub(crate) struct WaveSpawnEvent {
+    pub(crate) location: Vec3,
+}
+
+pub(crate) fn get_current_room_definition<'a>(
+    rooms: &Query<&GameObject, (With<Room>, Without<PreviousRoom>)>,
+    room_assets: &RoomAssets,
+    definitions: &'a Assets<RoomDefinition>,
+) -> Option<&'a RoomDefinition> {
+    rooms.iter().find_map(|game_object| match game_object {
+        GameObject::Room(room) => room.get_definition(room_assets, definitions),
+        _ => None,
+    })
+}
+
+pub(super) fn start_encounter(
+    mut commands: Commands,
+    rooms: Query<&GameObject, (With<Room>, Without<PreviousRoom>)>,
+) {
+    if rooms.iter().any(|game_object| game_object.is_room()) {
+        commands.insert_resource(CurrentEncounter::default());
+    }
+}
+
+pub(super) fn advance_encounter(
+    time: Res<Time>,
+    mut encounter: ResMut<CurrentEncounter>,
+    rooms: Query<&GameObject, (With<Room>, Without<PreviousRoom>)>,
+    room_assets: Res<RoomAssets>,
+    definitions: Res<Assets<RoomDefinition>>,
+    enemies: Query<(), With<Enemy>>,
+    trigger_volumes: Query<&TriggerVolume>,
+    markers: Query<(&GlobalTransform, &SceneTags)>,
+    mut enemy_spawner: EnemySpawner,
+    mut wave_spawn_events: EventWriter<WaveSpawnEvent>,
+) {
+    if encounter.is_waiting_for_enemies {
+        if enemies.is_empty() {
+            return;
+        }
+        encounter.is_waiting_for_enemies = false;
+    }
+    let Some(definition) = get_current_room_definition(&rooms, &room_assets, &definitions) else {
+        return;
+    };
+    let Some(wave) = definition.waves.get(encounter.next_wave) else {
+        return;
+    };
+    let Some(countdown) = encounter.countdown else {
+        let has_triggered = match &wave.trigger {
+            WaveTrigger::Immediately => true,
+            WaveTrigger::RoomCleared => enemies.is_empty(),
+            WaveTrigger::PlayerEnters(trigger) => trigger_volumes
+                .iter()
+                .any(|volume| &volume.id == trigger && volume.contains_player),
+            WaveTrigger::Scripted => false,
+        };
+        if has_triggered {
+            encounter.countdown = Some(wave.delay);
+        }
+        return;
+    };
+    let countdown = countdown - time.delta_seconds();
+    if countdown > 0. {
+        encounter.countdown = Some(countdown);
+        return;
+    }
+    encounter.countdown = None;
+    encounter.next_wave += 1;
+    for wave_enemy in wave.enemies.iter() {
+        let Some(spawn_point) =
+            get_markers(&markers, SceneTags::spawn_point, &wave_enemy.spawn_point).first().copied() else {
+            error!(
+                "Failed to spawn {} in wave: No spawn point \"{}\" in the room",
+                wave_enemy.enemy, wave_enemy.spawn_point
+            );
+            continue;
+        };
+        let transform = spawn_point.compute_transform().with_scale(Vec3::ONE);
+        let parameters = EnemyParameters {
+            level: wave_enemy.level.unwrap_or(1),
+            ..EnemyParameters::new(&wave_enemy.enemy)
+        };
+        if let Err(e) = enemy_spawner.spawn(parameters, transform) {
+            error!("Failed to spawn enemy in wave: {e:?}");
+            continue;
+        }
+        wave_spawn_events.send(WaveSpawnEvent {
+            location: transform.translation,
+        });
+        encounter.is_waiting_for_enemies = true;
+    }
+}
+
+fn get_markers<'a>(
+    markers: &'a Query<(&GlobalTransform, &SceneTags)>,
+    get_id: fn(&SceneTags) -> Option<&str>,
+    id: &str,
+) -> Vec<&'a GlobalTransform> {
+    markers
+        .iter()
+        .filter_map(|(transform, tags)| (get_id(tags) == Some(id)).then_some(transform))
+        .collect()
+}
@@ -0,0 +1,280 @@
+use crate::file_system_interaction::asset_loading::RoomAssets;
+use crate::file_system_interaction::config::Run;
+use crate::localization::Localization;
+use crate::world_interaction::room::{RoomDefinition, RoomId};
+use bevy::prelude::*;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, Reflect, FromReflect, Serialize, Deserialize, Default,
+)]
+#[reflect(Serialize, Deserialize)]
+pub(crate) enum RoomKind {
+    #[default]
+    Combat,
+    Rest,
+    Shop,
+    Boss,
+}
+
+impl RoomKind {
+    pub(crate) fn from_tag(tag: &str) -> Option<Self> {
+        match tag {
+            "combat" => Some(RoomKind::Combat),
+            "rest" => Some(RoomKind::Rest),
+            "shop" => Some(RoomKind::Shop),
+            "boss" => Some(RoomKind::Boss),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn describe(self, localization: &Localization) -> String {
+        let key = match self {
+            RoomKind::Combat => "room-kind.combat",
+            RoomKind::Rest => "room-kind.rest",
+            RoomKind::Shop => "room-kind.shop",
+            RoomKind::Boss => "room-kind.boss",
+        };
+        localization.get(key)
+    }
+}
+
+/// The rooms of a run and how they connect, generated from [`Run`] and the pool of [`RoomDefinition`]s.
+/// Rooms are laid out in layers of increasing depth, starting with the start room and ending with a boss room.
+/// Every room leads to one or more rooms of the next layer, between which the player chooses when leaving.
+/// Generation only depends on the seed, the config and the pool, and the layout is saved with the run.
+#[derive(
+    Debug, Clone, PartialEq, Resource, Reflect, FromReflect, Serialize, Deserialize, Default,
+)]
+#[reflect(Resource, Serialize, Deserialize)]
+pub(crate) struct RunLayout {
+    pub(crate) seed: u64,
+    /// Incremented every time a boss was beaten and the run continues with a new layout.
+    pub(crate) floor: usize,
+    pub(crate) nodes: Vec<RoomNode>,
+    /// Index of the room the player is in.
+    pub(crate) current: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Reflect, FromReflect, Serialize, Deserialize, Default)]
+#[reflect(Serialize, Deserialize)]
+pub(crate) struct RoomNode {
+    pub(crate) room: RoomId,
+    pub(crate) kind: RoomKind,
+    pub(crate) depth: usize,
+    /// Indices of the rooms this room leads to.
+    pub(crate) next: Vec<usize>,
+}
+
+impl RunLayout {
+    pub(crate) fn generate(seed: u64, config: &Run, pool: &[RunRoom]) -> Self {
+        Self::generate_floor(seed, 0, config.start_room, config, pool)
+    }
+
+    /// Continues the run after the boss room, which becomes the start of the new floor.
+    pub(crate) fn generate_next_floor(&self, config: &Run, pool: &[RunRoom]) -> Self {
+        Self::generate_floor(
+            self.seed,
+            self.floor + 1,
+            self.current_node().room,
+            config,
+            pool,
+        )
+    }
+
+    fn generate_floor(
+        seed: u64,
+        floor: usize,
+        start_room: RoomId,
+        config: &Run,
+        pool: &[RunRoom],
+    ) -> Self {
+        let mut rng = StdRng::seed_from_u64(seed.wrapping_add(floor as u64));
+        let length = config.length.max(1);
+        let mut nodes = vec![RoomNode {
+            room: start_room,
+            kind: RoomKind::Combat,
+            depth: 0,
+            next: vec![],
+        }];
+        let mut previous_layer = vec![0];
+        for depth in 1..=length + 1 {
+            let is_boss_layer = depth == length + 1;
+            let width = if is_boss_layer {
+                1
+            } else {
+                rng.gen_range(1..=config.max_branches.max(1))
+            };
+            let mut kinds = vec![RoomKind::Combat; width];
+            if is_boss_layer {
+                kinds[0] = RoomKind::Boss;
+            } else {
+                place_kind(
+                    &mut kinds,
+                    RoomKind::Rest,
+                    config.rest_every,
+                    depth,
+                    &mut rng,
+                );
+                place_kind(
+                    &mut kinds,
+                    RoomKind::Shop,
+                    config.shop_every,
+                    depth,
+                    &mut rng,
+                );
+            }
+            let layer: Vec<_> = (nodes.len()..nodes.len() + width).collect();
+            // Spreads the rooms of the previous layer over the new one without crossing paths,
+            // so that every room of the new layer can be reached
+            let previous_width = previous_layer.len();
+            for (i, &previous) in previous_layer.iter().enumerate() {
+                let first = i * width / previous_width;
+                let last = (((i + 1) * width).div_ceil(previous_width) - 1).max(first);
+                nodes[previous].next = layer[first..=last].to_vec();
+            }
+            let target_difficulty = config.start_difficulty
+                + (config.end_difficulty - config.start_difficulty) * depth as f32
+                    / (length + 1) as f32;
+            for (index, kind) in layer.iter().zip(kinds) {
+                let parent_rooms: Vec<_> = nodes
+                    .iter()
+                    .filter(|node| node.next.contains(index))
+                    .map(|node| node.room)
+                    .collect();
+                let room = choose_room(
+                    pool,
+                    kind,
+                    target_difficulty,
+                    &nodes,
+                    &parent_rooms,
+                    &mut rng,
+                )
+                .map(|room| (room.room, room.kind))
+                .unwrap_or((start_room, RoomKind::Combat));
+                nodes.push(RoomNode {
+                    room: room.0,
+                    kind: room.1,
+                    depth,
+                    next: vec![],
+                });
+            }
+            previous_layer = layer;
+        }
+        Self {
+            seed,
+            floor,
+            nodes,
+            current: 0,
+        }
+    }
+
+    pub(crate) fn current_node(&self) -> &RoomNode {
+        &self.nodes[self.current]
+    }
+
+    pub(crate) fn next_nodes(&self) -> impl Iterator<Item = (usize, &RoomNode)> {
+        self.current_node()
+            .next
+            .iter()
+            .map(|&index| (index, &self.nodes[index]))
+    }
+
+    pub(crate) fn is_at_end(&self) -> bool {
+        self.current_node().next.is_empty()
+    }
+}
+
+/// A room of the pool for one of the kinds it is tagged with.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct RunRoom {
+    pub(crate) room: RoomId,
+    pub(crate) kind: RoomKind,
+    pub(crate) difficulty: f32,
+}
+
+/// Collects the rooms of the pool in a fixed order, so that the same seed always generates the same layout.
+pub(crate) fn get_run_pool(
+    room_assets: &RoomAssets,
+    definitions: &Assets<RoomDefinition>,
+) -> Vec<RunRoom> {
+    let mut pool: Vec<_> = room_assets
+        .rooms
+        .iter()
+        .filter_map(|(path, handle)| Some((RoomId::from_path(path)?, definitions.get(handle)?)))
+        .flat_map(|(room, definition)| {
+            definition.kinds().map(move |kind| RunRoom {
+                room,
+                kind,
+                difficulty: definition.difficulty as f32,
+            })
+        })
+        .collect();
+    pool.sort_by(|a, b| {
+        (a.room.to_string(), a.kind as u8).cmp(&(b.room.to_string(), b.kind as u8))
+    });
+    pool
+}
+
+/// Turns one random combat room of the layer into `kind` on every `every`th layer. `0` disables the kind.
+fn place_kind(
+    kinds: &mut [RoomKind],
+    kind: RoomKind,
+    every: usize,
+    depth: usize,
+    rng: &mut StdRng,
+) {
+    if every == 0 || depth % every != 0 {
+        return;
+    }
+    let combat_rooms: Vec<_> = (0..kinds.len())
+        .filter(|&index| kinds[index] == RoomKind::Combat)
+        .collect();
+    if let Some(&index) = combat_rooms.choose(rng) {
+        kinds[index] = kind;
+    }
+}
+
+/// Picks the room of `kind` whose difficulty is closest to the target, counting every earlier use of a room
+/// as one step of difficulty away so that the run cycles through the pool.
+/// Rooms leading directly into this one are skipped if there is any alternative.
+/// Falls back to a combat room if the pool has no room of `kind`.
+fn choose_room<'a>(
+    pool: &'a [RunRoom],
+    kind: RoomKind,
+    target_difficulty: f32,
+    nodes: &[RoomNode],
+    parent_rooms: &[RoomId],
+    rng: &mut StdRng,
+) -> Option<&'a RunRoom> {
+    let of_kind = |kind| pool.iter().filter(move |room| room.kind == kind);
+    let mut candidates: Vec<_> = of_kind(kind).collect();
+    if candidates.is_empty() {
+        candidates = of_kind(RoomKind::Combat).collect();
+    }
+    if candidates
+        .iter()
+        .any(|candidate| !parent_rooms.contains(&candidate.room))
+    {
+        candidates.retain(|candidate| !parent_rooms.contains(&candidate.room));
+    }
+    let score = |candidate: &RunRoom| {
+        let uses = nodes
+            .iter()
+            .filter(|node| node.room == candidate.room)
+            .count();
+        (candidate.difficulty - target_difficulty).abs() + uses as f32
+    };
+    let best_score = candidates
+        .iter()
+        .map(|candidate| score(candidate))
+        .min_by(|a, b| a.total_cmp(b))?;
+    let best: Vec<_> = candidates
+        .into_iter()
+        .filter(|candidate| score(candidate) <= best_score + f32::EPSILON)
+        .collect();
+    best.choose(rng).copied()
+}
@@ -0,0 +1,246 @@
+use crate::combat::collision::unpack_event;
+use crate::file_system_interaction::asset_loading::RoomAssets;
+use crate::level_instantiation::spawning::objects::GameCollisionGroup;
+use crate::level_instantiation::spawning::scene_tags::SceneTags;
+use crate::level_instantiation::spawning::GameObject;
+use crate::player_control::camera::{IngameCamera, IngameCameraKind};
+use crate::player_control::player_embodiment::Player;
+use crate::util::trait_extension::MeshExt;
+use crate::world_interaction::condition::{ConditionAddEvent, ConditionId};
+use crate::world_interaction::dialog::{DialogEvent, DialogId};
+use crate::world_interaction::room::encounter::get_current_room_definition;
+use crate::world_interaction::room::{
+    CurrentEncounter, EnterRoomEvent, PreviousRoom, Room, RoomDefinition,
+};
+use anyhow::{Context, Result};
+use bevy::prelude::*;
+use bevy::utils::HashSet;
+use bevy_kira_audio::prelude::{Audio, AudioControl};
+use bevy_mod_sysfail::macros::*;
+use bevy_rapier3d::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// A sensor built from the meshes below a `[trigger: <id>]` node, or spanning -1 to 1 on every local axis of the node
+/// if it has none.
+#[derive(Debug, Clone, PartialEq, Eq, Component)]
+pub(crate) struct TriggerVolume {
+    pub(crate) id: String,
+    pub(crate) contains_player: bool,
+}
+
+/// Sent when the player walks into a [`TriggerVolume`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct TriggerEnterEvent {
+    pub(crate) trigger: String,
+    pub(crate) volume: Entity,
+}
+
+/// Sent when the player leaves a [`TriggerVolume`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct TriggerExitEvent {
+    pub(crate) trigger: String,
+    pub(crate) volume: Entity,
+}
+
+/// Runs its actions when the player enters or leaves the room's `[trigger: <id>]` volume.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct TriggerReaction {
+    pub(crate) trigger: String,
+    #[serde(default)]
+    pub(crate) on: TriggerEdge,
+    /// Whether the reaction runs every time instead of only the first time while in the room.
+    #[serde(default)]
+    pub(crate) repeat: bool,
+    pub(crate) actions: Vec<TriggerAction>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub(crate) enum TriggerEdge {
+    #[default]
+    Enter,
+    Exit,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) enum TriggerAction {
+    /// Starts `dialogs/<id>.dlg.ron`.
+    StartDialog(String),
+    AddCondition(String),
+    /// Starts the countdown of the room's next wave, regardless of its trigger.
+    SpawnWave,
+    /// Keeps the player from leaving the room, even if it was cleared.
+    LockExit,
+    UnlockExit,
+    /// Path of the sound relative to the assets directory.
+    PlaySound(String),
+    SetCamera(IngameCameraKind),
+}
+
+/// What the trigger reactions of the room the player is in have done so far.
+#[derive(Debug, Clone, PartialEq, Resource, Default)]
+pub(crate) struct TriggerState {
+    pub(crate) is_exit_locked: bool,
+    /// Indices into [`RoomDefinition::reactions`] that ran and do not repeat.
+    ran_reactions: HashSet<usize>,
+}
+
+#[sysfail(log(level = "error"))]
+pub(super) fn spawn_trigger_volumes(
+    mut commands: Commands,
+    added_tags: Query<(Entity, &SceneTags), Added<SceneTags>>,
+    children: Query<&Children>,
+    meshes: Res<Assets<Mesh>>,
+    mesh_handles: Query<&Handle<Mesh>>,
+) -> Result<()> {
+    for (entity, tags) in added_tags.iter() {
+        let Some(id) = tags.trigger() else {
+            continue;
+        };
+        let volume = TriggerVolume {
+            id: id.to_string(),
+            contains_player: false,
+        };
+        let sensor = (
+            Sensor,
+            ActiveEvents::COLLISION_EVENTS,
+            ActiveCollisionTypes::all(),
+            CollisionGroups::new(
+                GameCollisionGroup::OTHER.into(),
+                GameCollisionGroup::PLAYER.into(),
+            ),
+        );
+        let volume_meshes = Mesh::search_in_children(entity, &children, &meshes, &mesh_handles);
+        if volume_meshes.is_empty() {
+            commands
+                .entity(entity)
+                .insert((volume, Collider::cuboid(1., 1., 1.), sensor));
+            continue;
+        }
+        for (mesh_entity, mesh) in volume_meshes {
+            let collider = Collider::from_bevy_mesh(mesh, &ComputedColliderShape::ConvexHull)
+                .context("Failed to create trigger volume from mesh")?;
+            commands
+                .entity(mesh_entity)
+                .insert((volume.clone(), collider, sensor.clone()));
+        }
+    }
+    Ok(())
+}
+
+pub(super) fn update_trigger_volumes(
+    mut collision_events: EventReader<CollisionEvent>,
+    players: Query<(), With<Player>>,
+    mut volumes: Query<&mut TriggerVolume>,
+    mut enter_events: EventWriter<TriggerEnterEvent>,
+    mut exit_events: EventWriter<TriggerExitEvent>,
+) {
+    for event in collision_events.iter() {
+        let (entity_a, entity_b, ongoing) = unpack_event(event);
+        let volume_entity = if players.contains(entity_a) {
+            entity_b
+        } else if players.contains(entity_b) {
+            entity_a
+        } else {
+            continue;
+        };
+        let Ok(mut volume) = volumes.get_mut(volume_entity) else {
+            continue;
+        };
+        if volume.contains_player == ongoing {
+            continue;
+        }
+        volume.contains_player = ongoing;
+        let trigger = volume.id.clone();
+        if ongoing {
+            enter_events.send(TriggerEnterEvent {
+                trigger,
+                volume: volume_entity,
+            });
+        } else {
+            exit_events.send(TriggerExitEvent {
+                trigger,
+                volume: volume_entity,
+            });
+        }
+    }
+}
+
+pub(super) fn reset_trigger_state(
+    mut enter_room_events: EventReader<EnterRoomEvent>,
+    mut trigger_state: ResMut<TriggerState>,
+) {
+    if enter_room_events.iter().next().is_some() {
+        *trigger_state = default();
+    }
+}
+
+pub(super) fn react_to_triggers(
+    mut enter_events: EventReader<TriggerEnterEvent>,
+    mut exit_events: EventReader<TriggerExitEvent>,
+    rooms: Query<&GameObject, (With<Room>, Without<PreviousRoom>)>,
+    room_assets: Res<RoomAssets>,
+    definitions: Res<Assets<RoomDefinition>>,
+    mut trigger_state: ResMut<TriggerState>,
+    mut encounter: Option<ResMut<CurrentEncounter>>,
+    mut dialog_events: EventWriter<DialogEvent>,
+    mut condition_events: EventWriter<ConditionAddEvent>,
+    mut cameras: Query<&mut IngameCamera>,
+    audio: Res<Audio>,
+    asset_server: Res<AssetServer>,
+) {
+    let events: Vec<_> = enter_events
+        .iter()
+        .map(|event| (TriggerEdge::Enter, &event.trigger, event.volume))
+        .chain(
+            exit_events
+                .iter()
+                .map(|event| (TriggerEdge::Exit, &event.trigger, event.volume)),
+        )
+        .collect();
+    if events.is_empty() {
+        return;
+    }
+    let Some(definition) = get_current_room_definition(&rooms, &room_assets, &definitions) else {
+        return;
+    };
+    for (edge, trigger, volume) in events {
+        for (index, reaction) in definition.reactions.iter().enumerate() {
+            if reaction.on != edge
+                || &reaction.trigger != trigger
+                || trigger_state.ran_reactions.contains(&index)
+            {
+                continue;
+            }
+            if !reaction.repeat {
+                trigger_state.ran_reactions.insert(index);
+            }
+            for action in reaction.actions.iter() {
+                match action {
+                    TriggerAction::StartDialog(dialog) => dialog_events.send(DialogEvent {
+                        dialog: DialogId::new(dialog),
+                        source: volume,
+                        page: None,
+                    }),
+                    TriggerAction::AddCondition(condition) => {
+                        condition_events.send(ConditionAddEvent(ConditionId(condition.clone())))
+                    }
+                    TriggerAction::SpawnWave => {
+                        if let Some(encounter) = encounter.as_mut() {
+                            encounter.trigger_next_wave(definition);
+                        }
+                    }
+                    TriggerAction::LockExit => trigger_state.is_exit_locked = true,
+                    TriggerAction::UnlockExit => trigger_state.is_exit_locked = false,
+                    TriggerAction::PlaySound(path) => {
+                        audio.play(asset_server.load(path.as_str()));
+                    }
+                    TriggerAction::SetCamera(kind) => {
+                        for mut camera in cameras.iter_mut() {
+                            camera.kind = kind.clone();
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -1,9 +1,11 @@
 use crate::combat::Constitution;
+use crate::localization::Localization;
 use crate::player_control::player_embodiment::Player;
 use crate::world_interaction::room::EnterRoomEvent;
 use crate::GameState;
 use bevy::prelude::*;
 use bevy::utils::HashMap;
+use serde::{Deserialize, Serialize};
 use strum::IntoEnumIterator;
 use strum_macros::EnumIter;
 
@@ -15,8 +17,20 @@ pub(crate) fn side_effects_plugin(app: &mut App) {
         .add_system(apply_side_effects_to_constitution.in_set(OnUpdate(GameState::Playing)));
 }
 
-#[derive(Debug, Clone, Eq, PartialEq, Resource, Reflect, FromReflect, Deref, DerefMut)]
-#[reflect(Resource)]
+#[derive(
+    Debug,
+    Clone,
+    Eq,
+    PartialEq,
+    Resource,
+    Reflect,
+    FromReflect,
+    Serialize,
+    Deserialize,
+    Deref,
+    DerefMut,
+)]
+#[reflect(Resource, Serialize, Deserialize)]
 pub(crate) struct SideEffects(HashMap<SideEffect, i32>);
 
 impl SideEffects {
@@ -46,7 +60,10 @@ impl Default for SideEffects {
     }
 }
 
-#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Reflect, FromReflect, EnumIter)]
+#[derive(
+    Debug, Clone, Copy, Eq, PartialEq, Hash, Reflect, FromReflect, Serialize, Deserialize, EnumIter,
+)]
+#[reflect(Serialize, Deserialize)]
 pub(crate) enum SideEffect {
     Size,
     BaseSpeed,
@@ -82,29 +99,37 @@ fn apply_side_effects_to_constitution(
 }
 
 impl SideEffect {
-    pub(crate) fn format_positive(self) -> String {
-        format!("{} {}", self.positive_descriptor(), self.name())
+    pub(crate) fn format_positive(self, localization: &Localization) -> String {
+        self.format(self.positive_descriptor(), localization)
     }
 
-    pub(crate) fn format_negative(self) -> String {
-        format!("{} {}", self.negative_descriptor(), self.name())
+    pub(crate) fn format_negative(self, localization: &Localization) -> String {
+        self.format(self.negative_descriptor(), localization)
+    }
+
+    fn format(self, descriptor: &str, localization: &Localization) -> String {
+        let name = localization.get(&format!("side-effect.{}", self.name()));
+        localization.format(
+            &format!("side-effect.{descriptor}"),
+            &[("side-effect", &name)],
+        )
     }
 
     fn positive_descriptor(self) -> &'static str {
         match self {
-            Self::Size => "Increase",
-            Self::BaseSpeed => "Increase",
-            Self::HealthDamage => "Increase",
-            Self::AttackPostureDamage => "Increase",
-            Self::MaxPosture => "Increase",
-            Self::PostureRegenRate => "Increase",
-            Self::PostureRegenWait => "Decrease",
-            Self::DeflectWindow => "Increase",
-            Self::DeflectPostureDamage => "Increase",
-            Self::BackwardsWalkingSpeed => "Increase",
-            Self::Health => "Increase",
-            Self::AttackKnockback => "Increase",
-            Self::KnockbackResistance => "Increase",
+            Self::Size => "increase",
+            Self::BaseSpeed => "increase",
+            Self::HealthDamage => "increase",
+            Self::AttackPostureDamage => "increase",
+            Self::MaxPosture => "increase",
+            Self::PostureRegenRate => "increase",
+            Self::PostureRegenWait => "decrease",
+            Self::DeflectWindow => "increase",
+            Self::DeflectPostureDamage => "increase",
+            Self::BackwardsWalkingSpeed => "increase",
+            Self::Health => "increase",
+            Self::AttackKnockback => "increase",
+            Self::KnockbackResistance => "increase",
         }
     }
 
@@ -113,28 +138,29 @@ impl SideEffect {
         Self::invert_descriptor(positive_descriptor)
     }
 
+    /// The id of the side effect in keys like `side-effect.<name>`.
     fn name(self) -> &'static str {
         match self {
             Self::Size => "size",
-            Self::BaseSpeed => "walking speed",
-            Self::HealthDamage => "damage dealt to health by attacking",
-            Self::AttackPostureDamage => "damage dealt to posture by attacking",
-            Self::MaxPosture => "maximum posture",
-            Self::PostureRegenRate => "posture regeneration rate",
-            Self::PostureRegenWait => "time before posture regeneration starts",
-            Self::DeflectWindow => "time window where blocking an attack will deflect it",
-            Self::DeflectPostureDamage => "damage dealt to posture by deflecting an attack",
-            Self::BackwardsWalkingSpeed => "walking speed when walking backwards",
-            Self::Health => "your health",
-            Self::AttackKnockback => "knockback dealt by attacking",
-            Self::KnockbackResistance => "resistance to being knocked back",
+            Self::BaseSpeed => "base-speed",
+            Self::HealthDamage => "health-damage",
+            Self::AttackPostureDamage => "attack-posture-damage",
+            Self::MaxPosture => "max-posture",
+            Self::PostureRegenRate => "posture-regen-rate",
+            Self::PostureRegenWait => "posture-regen-wait",
+            Self::DeflectWindow => "deflect-window",
+            Self::DeflectPostureDamage => "deflect-posture-damage",
+            Self::BackwardsWalkingSpeed => "backwards-walking-speed",
+            Self::Health => "health",
+            Self::AttackKnockback => "attack-knockback",
+            Self::KnockbackResistance => "knockback-resistance",
         }
     }
 
     fn invert_descriptor(descriptor: &'static str) -> &'static str {
         match descriptor {
-            "Increase" => "Decrease",
-            "Decrease" => "Increase",
+            "increase" => "decrease",
+            "decrease" => "increase",
             _ => panic!("Invalid descriptor"),
         }
     }
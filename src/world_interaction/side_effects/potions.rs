@@ -1,3 +1,4 @@
+use crate::localization::Localization;
 use crate::world_interaction::side_effects::SideEffect;
 use bevy::prelude::*;
 use rand::distributions::Uniform;
@@ -6,11 +7,26 @@ use strum::IntoEnumIterator;
 
 #[derive(Debug, Clone, Reflect, FromReflect)]
 pub(crate) struct Potion {
-    pub(crate) name: String,
+    /// Ids of the words the name is made of, see [`Potion::name`].
+    pub(crate) adjective: String,
+    pub(crate) drink: String,
+    pub(crate) of: String,
     pub(crate) positive_side_effect: SideEffect,
     pub(crate) negative_side_effect: SideEffect,
 }
 
+impl Potion {
+    pub(crate) fn name(&self, localization: &Localization) -> String {
+        let adjective = localization.get(&format!("potion.adjective.{}", self.adjective));
+        let drink = localization.get(&format!("potion.drink.{}", self.drink));
+        let of = localization.get(&format!("potion.of.{}", self.of));
+        localization.format(
+            "potion.name",
+            &[("adjective", &adjective), ("drink", &drink), ("of", &of)],
+        )
+    }
+}
+
 pub(crate) const POTION_COUNT: usize = 3;
 
 pub(crate) fn generate_potions() -> [Potion; POTION_COUNT] {
@@ -26,14 +42,12 @@ pub(crate) fn generate_potions() -> [Potion; POTION_COUNT] {
     let side_effects = sample(&mut rng, &side_effects, POTION_COUNT * 2);
 
     let generate_potion = |index: usize| {
-        let adjective = adjectives[index];
-        let drink = drinks[index];
-        let of = ofs[index];
-        let name = format!("{} {} of {}", adjective, drink, of);
         let positive_side_effect = *side_effects[index];
         let negative_side_effect = *side_effects[side_effects.len() - index - 1];
         Potion {
-            name,
+            adjective: adjectives[index].to_string(),
+            drink: drinks[index].to_string(),
+            of: ofs[index].to_string(),
             positive_side_effect,
             negative_side_effect,
         }
@@ -51,92 +65,92 @@ fn sample<'a, 'b, T>(rng: &'b mut impl Rng, slice: &'a [T], amount: usize) -> Ve
 
 fn adjectives() -> Vec<&'static str> {
     vec![
-        "Aged",
-        "Ancient",
-        "Cool",
-        "Sparkling",
-        "Fizzy",
-        "Frothy",
-        "Fruity",
-        "Glowing",
-        "Gooey",
-        "Gross",
-        "Weird",
-        "Hot",
-        "Icy",
-        "Mysterious",
-        "Chilled",
-        "Muddy",
-        "Murky",
-        "Disgusting",
-        "Nasty",
-        "Tasty",
-        "Sweet",
-        "Sour",
-        "Salty",
-        "Bitter",
-        "Spicy",
-        "Savory",
-        "Sweaty",
-        "Slimy",
+        "aged",
+        "ancient",
+        "cool",
+        "sparkling",
+        "fizzy",
+        "frothy",
+        "fruity",
+        "glowing",
+        "gooey",
+        "gross",
+        "weird",
+        "hot",
+        "icy",
+        "mysterious",
+        "chilled",
+        "muddy",
+        "murky",
+        "disgusting",
+        "nasty",
+        "tasty",
+        "sweet",
+        "sour",
+        "salty",
+        "bitter",
+        "spicy",
+        "savory",
+        "sweaty",
+        "slimy",
     ]
 }
 
 fn drinks() -> Vec<&'static str> {
     vec![
-        "Ale",
-        "Beer",
-        "Brew",
-        "Cider",
-        "Juice",
-        "Lager",
-        "Liquor",
-        "Potion",
-        "Elixir",
-        "Soda",
-        "Slurpy",
-        "Smoothie",
-        "Energy Drink",
-        "Milkshake",
-        "Water",
-        "Wine",
-        "Tea",
-        "Coffee",
-        "Cocoa",
+        "ale",
+        "beer",
+        "brew",
+        "cider",
+        "juice",
+        "lager",
+        "liquor",
+        "potion",
+        "elixir",
+        "soda",
+        "slurpy",
+        "smoothie",
+        "energy-drink",
+        "milkshake",
+        "water",
+        "wine",
+        "tea",
+        "coffee",
+        "cocoa",
     ]
 }
 
 fn ofs() -> Vec<&'static str> {
     vec![
-        "the Bodhisattva ",
-        "the Buddha",
-        "Caesar",
-        "the Kind",
-        "the Wizard",
-        "Jesus",
-        "the Pope",
-        "the Warrior",
-        "the Monk",
-        "the Sage",
-        "the Dark One",
-        "Enlightenment",
-        "the Hanged Man",
-        "the Fool",
-        "the Magician",
-        "the High Priestess",
-        "the Empress",
-        "the Emperor",
-        "the Hierophant",
-        "the Lovers",
-        "the Chariot",
-        "Justice",
-        "the Hermit",
-        "the Astral Traveler",
-        "the Outlaw",
-        "the Outcast",
-        "the Devil",
-        "the Tower",
-        "Hohenheim",
-        "Bevy",
+        "bodhisattva",
+        "buddha",
+        "caesar",
+        "kind",
+        "wizard",
+        "jesus",
+        "pope",
+        "warrior",
+        "monk",
+        "sage",
+        "dark-one",
+        "enlightenment",
+        "hanged-man",
+        "fool",
+        "magician",
+        "high-priestess",
+        "empress",
+        "emperor",
+        "hierophant",
+        "lovers",
+        "chariot",
+        "justice",
+        "hermit",
+        "astral-traveler",
+        "outlaw",
+        "outcast",
+        "devil",
+        "tower",
+        "hohenheim",
+        "bevy",
     ]
 }